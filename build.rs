@@ -21,6 +21,12 @@ fn main() {
         if flag == "-DDEVELHELP" {
             println!("cargo:rustc-cfg=riot_develhelp");
         }
+        if flag == "-DNDEBUG" {
+            println!("cargo:rustc-cfg=riot_ndebug");
+        }
+        if flag == "-DDEBUG_ASSERT_VERBOSE" {
+            println!("cargo:rustc-cfg=riot_debug_assert_verbose");
+        }
     }
 
     let mut bindgen_output_file = None;
@@ -48,7 +54,99 @@ fn main() {
         {
             println!("cargo:rustc-cfg=marker_config_auto_init_enable_debug");
         }
+
+        write_config(&bindgen_output);
     } else {
         println!("cargo:warning=Old riot-sys did not provide BINDGEN_OUTPUT_FILE, assuming it's an old RIOT version");
+        write_config("");
     }
 }
+
+/// Configuration values that are exposed in the `config` module, along with the type they are
+/// exposed as
+///
+/// The names are the C names; any CONFIG_ prefix is stripped for the Rust name.
+const CONFIG_VALUES: &[(&str, &str)] = &[
+    // Threads
+    ("THREAD_STACKSIZE_DEFAULT", "usize"),
+    ("THREAD_STACKSIZE_MAIN", "usize"),
+    ("THREAD_STACKSIZE_IDLE", "usize"),
+    ("THREAD_STACKSIZE_SMALL", "usize"),
+    ("THREAD_STACKSIZE_LARGE", "usize"),
+    ("THREAD_EXTRA_STACKSIZE_PRINTF", "usize"),
+    ("SCHED_PRIO_LEVELS", "u8"),
+    ("THREAD_PRIORITY_MAIN", "u8"),
+    // Shell
+    ("SHELL_DEFAULT_BUFSIZE", "usize"),
+    // GNRC
+    ("CONFIG_GNRC_PKTBUF_SIZE", "usize"),
+    ("CONFIG_GNRC_NETIF_MSG_QUEUE_SIZE_EXP", "u8"),
+    ("CONFIG_GNRC_IPV6_MSG_QUEUE_SIZE_EXP", "u8"),
+    ("CONFIG_GNRC_NETIF_IPV6_ADDRS_NUMOF", "usize"),
+    ("CONFIG_GNRC_NETIF_IPV6_GROUPS_NUMOF", "usize"),
+    ("CONFIG_GNRC_NETIF_PKTQ_POOL_SIZE", "usize"),
+    ("CONFIG_GNRC_SIXLOWPAN_FRAG_FB_SIZE", "usize"),
+    ("CONFIG_GNRC_SIXLOWPAN_FRAG_RBUF_SIZE", "usize"),
+    ("CONFIG_GNRC_SIXLOWPAN_FRAG_RBUF_TIMEOUT_US", "u32"),
+    ("CONFIG_GNRC_SIXLOWPAN_FRAG_VRB_SIZE", "usize"),
+    ("CONFIG_GNRC_SIXLOWPAN_FRAG_VRB_TIMEOUT_US", "u32"),
+    // CoAP
+    ("CONFIG_COAP_ACK_TIMEOUT_MS", "u32"),
+    ("CONFIG_COAP_MAX_RETRANSMIT", "u8"),
+    ("CONFIG_COAP_RANDOM_FACTOR_1000", "u32"),
+    ("CONFIG_GCOAP_NON_TIMEOUT_MSEC", "u32"),
+    ("CONFIG_GCOAP_PDU_BUF_SIZE", "usize"),
+    ("CONFIG_GCOAP_REQ_WAITING_MAX", "usize"),
+    ("CONFIG_GCOAP_RESP_OPTIONS_BUF", "usize"),
+    ("CONFIG_GCOAP_OBS_CLIENTS_MAX", "usize"),
+    ("CONFIG_GCOAP_OBS_REGISTRATIONS_MAX", "usize"),
+    ("CONFIG_NANOCOAP_NOPTS_MAX", "usize"),
+    ("CONFIG_NANOCOAP_URI_MAX", "usize"),
+    // Sock
+    ("CONFIG_SOCK_DNS_CACHE_SIZE", "usize"),
+    ("CONFIG_DNS_CACHE_SIZE", "usize"),
+    // VFS
+    ("VFS_NAME_MAX", "usize"),
+    ("VFS_MAX_OPEN_FILES", "usize"),
+];
+
+/// Write `$OUT_DIR/config.rs`, containing constants for all [CONFIG_VALUES] that are present as
+/// plain integer constants in the bindgen output
+fn write_config(bindgen_output: &str) {
+    let mut config = String::new();
+
+    for line in bindgen_output.lines() {
+        // Normalize away the spaces that are present if rustfmt was not installed
+        let line: String = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        let line = line.replace(" :", ":").replace(" ;", ";");
+        let Some(rest) = line.strip_prefix("pub const ") else {
+            continue;
+        };
+        let Some((name, rest)) = rest.split_once(": ") else {
+            continue;
+        };
+        let Some((_c_type, value)) = rest.split_once(" = ") else {
+            continue;
+        };
+        let Some(value) = value.strip_suffix(';') else {
+            continue;
+        };
+        let Some((_, rust_type)) = CONFIG_VALUES.iter().find(|(n, _)| *n == name) else {
+            continue;
+        };
+        if value.parse::<u64>().is_err() {
+            continue;
+        }
+
+        let rust_name = name.strip_prefix("CONFIG_").unwrap_or(name);
+        // Allows code to fall back to defaults where values may be absent
+        println!("cargo:rustc-cfg=riot_config_{}", rust_name.to_lowercase());
+        config.push_str(&format!(
+            "/// Value of `{name}` in the RIOT build\npub const {rust_name}: {rust_type} = {value};\n"
+        ));
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by Cargo");
+    std::fs::write(std::path::Path::new(&out_dir).join("config.rs"), config)
+        .expect("Failed to write config.rs");
+}
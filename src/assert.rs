@@ -0,0 +1,67 @@
+//! Assertions that behave like RIOT's [assert()](https://doc.riot-os.org/assert_8h.html)
+//!
+//! The [riot_assert!](crate::riot_assert) macro checks a condition like [assert!], but reports
+//! failures the way C assertions in RIOT do: With `DEBUG_ASSERT_VERBOSE`, file and line are
+//! printed, otherwise the address of the failed assertion; either way, the system halts through
+//! `core_panic` with `PANIC_ASSERT_FAIL`, so the same crash handling applies as for C code. Like
+//! the C macro, the check is removed (and the condition not evaluated) when RIOT is built with
+//! `NDEBUG`.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # let index = 0; let len = 1;
+//! riot_wrappers::riot_assert!(index < len);
+//! ```
+
+/// Whether assertions are checked in this build
+///
+/// This is false if RIOT is built with `NDEBUG`.
+pub const ENABLED: bool = cfg!(not(riot_ndebug));
+
+/// Check a condition, and halt the system the way a failed C `assert()` does if it is false
+///
+/// See the [module level documentation](crate::assert) for details.
+#[macro_export]
+macro_rules! riot_assert {
+    ($cond:expr $(,)?) => {{
+        if $crate::assert::ENABLED && !($cond) {
+            $crate::assert::fail(concat!(file!(), "\0").as_bytes(), line!());
+        }
+    }};
+}
+
+/// Report a failed assertion and halt
+///
+/// This is an implementation detail of [riot_assert!](crate::riot_assert); `file` needs to be
+/// null terminated.
+///
+/// This is always inlined: Without `DEBUG_ASSERT_VERBOSE`, `_assert_panic()` reports the address it
+/// is called from, which is thus the address of the assertion.
+#[doc(hidden)]
+#[inline(always)]
+pub fn fail(file: &'static [u8], line: u32) -> ! {
+    #[cfg(riot_debug_assert_verbose)]
+    {
+        // unsafe: OK per C API; the file name is null terminated.
+        unsafe { riot_sys::_assert_failure(file.as_ptr() as *const _, line as _) }
+    }
+    #[cfg(not(riot_debug_assert_verbose))]
+    {
+        let _ = (file, line);
+        // unsafe: OK per C API
+        unsafe { riot_sys::_assert_panic() }
+    }
+}
+
+/// Stack size for a thread that runs code with debug output, given its size without
+///
+/// This mirrors `DEBUG_EXTRA_STACKSIZE` of RIOT's `debug.h`: When Rust debug assertions are
+/// enabled (which is when debug-only formatting code tends to run), the extra stack that printing
+/// needs on the platform is added.
+pub const fn debug_stacksize(base: usize) -> usize {
+    if cfg!(debug_assertions) {
+        base + riot_sys::THREAD_EXTRA_STACKSIZE_PRINTF as usize
+    } else {
+        base
+    }
+}
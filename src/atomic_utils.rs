@@ -0,0 +1,222 @@
+//! Wrappers for [atomic utilities](https://doc.riot-os.org/group__sys__atomic__utils.html)
+//!
+//! The types in here provide atomic access to integers of 8 to 64 bits on every platform RIOT
+//! supports. Unlike [core::sync::atomic], which lacks types on some platforms (eg.
+//! read-modify-write operations on Cortex-M0, or 64 bit types on 32 bit platforms), RIOT falls
+//! back to disabling interrupts where the hardware does not provide suitable instructions.
+//!
+//! All operations are sequentially consistent. The methods are named after their counterparts in
+//! [core::sync::atomic] (without the ordering argument), and call the C functions of the same name
+//! and width (eg. [AtomicU16::fetch_add] calls `atomic_fetch_add_u16`).
+//!
+//! ```no_run
+//! # #![no_std]
+//! use riot_wrappers::atomic_utils::AtomicU32;
+//!
+//! static EVENTS: AtomicU32 = AtomicU32::new(0);
+//!
+//! // in an interrupt handler
+//! EVENTS.fetch_add(1);
+//!
+//! // in a thread
+//! let seen = EVENTS.load();
+//! ```
+//!
+//! Besides the regular read-modify-write operations, RIOT offers "semi-atomic" variants (here
+//! named `semi_fetch_*`): They are only atomic with respect to concurrent loads, and are cheaper
+//! on some platforms. They can be used when there is only a single writer.
+
+use core::cell::UnsafeCell;
+
+macro_rules! atomic_type {
+    (
+        $name:ident,
+        $t:ty,
+        $load:ident,
+        $store:ident,
+        $fetch_add:ident,
+        $fetch_sub:ident,
+        $fetch_or:ident,
+        $fetch_xor:ident,
+        $fetch_and:ident,
+        $semi_fetch_add:ident,
+        $semi_fetch_sub:ident,
+        $semi_fetch_or:ident,
+        $semi_fetch_xor:ident,
+        $semi_fetch_and:ident
+    ) => {
+        #[doc = concat!("An [`", stringify!($t), "`] that is accessed atomically")]
+        ///
+        /// See the [module level documentation](self) for details.
+        #[repr(transparent)]
+        pub struct $name(UnsafeCell<$t>);
+
+        // unsafe: The value is only accessed through atomic_utils
+        unsafe impl Sync for $name {}
+
+        impl $name {
+            pub const fn new(value: $t) -> Self {
+                $name(UnsafeCell::new(value))
+            }
+
+            pub fn load(&self) -> $t {
+                // unsafe: OK per C API; the pointer is valid, and all accesses are atomic.
+                unsafe { riot_sys::inline::$load(self.0.get()) }
+            }
+
+            pub fn store(&self, value: $t) {
+                // unsafe: See load
+                unsafe { riot_sys::inline::$store(self.0.get(), value) }
+            }
+
+            /// Add to the value (wrapping around on overflow), returning the previous value
+            pub fn fetch_add(&self, summand: $t) -> $t {
+                // unsafe: See load
+                unsafe { riot_sys::inline::$fetch_add(self.0.get(), summand) }
+            }
+
+            /// Subtract from the value (wrapping around on overflow), returning the previous value
+            pub fn fetch_sub(&self, subtrahend: $t) -> $t {
+                // unsafe: See load
+                unsafe { riot_sys::inline::$fetch_sub(self.0.get(), subtrahend) }
+            }
+
+            /// Bitwise "or" to the value, returning the previous value
+            pub fn fetch_or(&self, value: $t) -> $t {
+                // unsafe: See load
+                unsafe { riot_sys::inline::$fetch_or(self.0.get(), value) }
+            }
+
+            /// Bitwise "xor" to the value, returning the previous value
+            pub fn fetch_xor(&self, value: $t) -> $t {
+                // unsafe: See load
+                unsafe { riot_sys::inline::$fetch_xor(self.0.get(), value) }
+            }
+
+            /// Bitwise "and" to the value, returning the previous value
+            pub fn fetch_and(&self, value: $t) -> $t {
+                // unsafe: See load
+                unsafe { riot_sys::inline::$fetch_and(self.0.get(), value) }
+            }
+
+            /// Like [fetch_add](Self::fetch_add), but only safe against concurrent loads
+            pub fn semi_fetch_add(&self, summand: $t) -> $t {
+                // unsafe: See load. Concurrent writes are not unsound, merely lose updates.
+                unsafe { riot_sys::inline::$semi_fetch_add(self.0.get(), summand) }
+            }
+
+            /// Like [fetch_sub](Self::fetch_sub), but only safe against concurrent loads
+            pub fn semi_fetch_sub(&self, subtrahend: $t) -> $t {
+                // unsafe: See semi_fetch_add
+                unsafe { riot_sys::inline::$semi_fetch_sub(self.0.get(), subtrahend) }
+            }
+
+            /// Like [fetch_or](Self::fetch_or), but only safe against concurrent loads
+            pub fn semi_fetch_or(&self, value: $t) -> $t {
+                // unsafe: See semi_fetch_add
+                unsafe { riot_sys::inline::$semi_fetch_or(self.0.get(), value) }
+            }
+
+            /// Like [fetch_xor](Self::fetch_xor), but only safe against concurrent loads
+            pub fn semi_fetch_xor(&self, value: $t) -> $t {
+                // unsafe: See semi_fetch_add
+                unsafe { riot_sys::inline::$semi_fetch_xor(self.0.get(), value) }
+            }
+
+            /// Like [fetch_and](Self::fetch_and), but only safe against concurrent loads
+            pub fn semi_fetch_and(&self, value: $t) -> $t {
+                // unsafe: See semi_fetch_add
+                unsafe { riot_sys::inline::$semi_fetch_and(self.0.get(), value) }
+            }
+
+            /// Access the value through an exclusive reference, which needs no synchronization
+            pub fn get_mut(&mut self) -> &mut $t {
+                self.0.get_mut()
+            }
+
+            pub fn into_inner(self) -> $t {
+                self.0.into_inner()
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new(0)
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.load(), f)
+            }
+        }
+    };
+}
+
+atomic_type!(
+    AtomicU8,
+    u8,
+    atomic_load_u8,
+    atomic_store_u8,
+    atomic_fetch_add_u8,
+    atomic_fetch_sub_u8,
+    atomic_fetch_or_u8,
+    atomic_fetch_xor_u8,
+    atomic_fetch_and_u8,
+    semi_atomic_fetch_add_u8,
+    semi_atomic_fetch_sub_u8,
+    semi_atomic_fetch_or_u8,
+    semi_atomic_fetch_xor_u8,
+    semi_atomic_fetch_and_u8
+);
+
+atomic_type!(
+    AtomicU16,
+    u16,
+    atomic_load_u16,
+    atomic_store_u16,
+    atomic_fetch_add_u16,
+    atomic_fetch_sub_u16,
+    atomic_fetch_or_u16,
+    atomic_fetch_xor_u16,
+    atomic_fetch_and_u16,
+    semi_atomic_fetch_add_u16,
+    semi_atomic_fetch_sub_u16,
+    semi_atomic_fetch_or_u16,
+    semi_atomic_fetch_xor_u16,
+    semi_atomic_fetch_and_u16
+);
+
+atomic_type!(
+    AtomicU32,
+    u32,
+    atomic_load_u32,
+    atomic_store_u32,
+    atomic_fetch_add_u32,
+    atomic_fetch_sub_u32,
+    atomic_fetch_or_u32,
+    atomic_fetch_xor_u32,
+    atomic_fetch_and_u32,
+    semi_atomic_fetch_add_u32,
+    semi_atomic_fetch_sub_u32,
+    semi_atomic_fetch_or_u32,
+    semi_atomic_fetch_xor_u32,
+    semi_atomic_fetch_and_u32
+);
+
+atomic_type!(
+    AtomicU64,
+    u64,
+    atomic_load_u64,
+    atomic_store_u64,
+    atomic_fetch_add_u64,
+    atomic_fetch_sub_u64,
+    atomic_fetch_or_u64,
+    atomic_fetch_xor_u64,
+    atomic_fetch_and_u64,
+    semi_atomic_fetch_add_u64,
+    semi_atomic_fetch_sub_u64,
+    semi_atomic_fetch_or_u64,
+    semi_atomic_fetch_xor_u64,
+    semi_atomic_fetch_and_u64
+);
@@ -111,3 +111,8 @@ where
         response.set_payload(b"");
     }
 }
+
+#[cfg(any(riot_module_vfs, riot_module_suit_worker))]
+pub(crate) mod block;
+#[cfg(feature = "with_minicbor")]
+pub mod diagnostics;
@@ -0,0 +1,51 @@
+//! Helpers for block-wise transfer (RFC 7959)
+
+/// Block option value (Block1 or Block2)
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Block {
+    pub(crate) num: u32,
+    pub(crate) more: bool,
+    pub(crate) szx: u8,
+}
+
+impl Block {
+    pub(crate) fn parse(value: &[u8]) -> Option<Self> {
+        if value.len() > 3 {
+            return None;
+        }
+        let value = value.iter().fold(0u32, |acc, b| (acc << 8) | *b as u32);
+        let szx = (value & 0x7) as u8;
+        if szx == 7 {
+            // Reserved (or BERT, which is not supported here)
+            return None;
+        }
+        Some(Block {
+            num: value >> 4,
+            more: value & 0x8 != 0,
+            szx,
+        })
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        1 << (self.szx + 4)
+    }
+
+    pub(crate) fn offset(&self) -> usize {
+        self.num as usize * self.size()
+    }
+
+    /// Encode into the option value, returning the buffer and the used length
+    pub(crate) fn encode(&self) -> ([u8; 3], usize) {
+        let value = (self.num << 4) | ((self.more as u32) << 3) | self.szx as u32;
+        let bytes = value.to_be_bytes();
+        let len = match value {
+            0 => 0,
+            1..=0xff => 1,
+            0x100..=0xffff => 2,
+            _ => 3,
+        };
+        let mut result = [0; 3];
+        result[..len].copy_from_slice(&bytes[4 - len..]);
+        (result, len)
+    }
+}
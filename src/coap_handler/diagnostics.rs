@@ -0,0 +1,240 @@
+//! A CoAP resource that reports the node's state as a CBOR document
+//!
+//! [Diagnostics] answers GET requests with what the introspection APIs of this crate can tell
+//! about the running system, for collection by fleet monitoring tools:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gcoap::{self, SingleHandlerListener};
+//! # use riot_wrappers::coap_handler::GcoapHandler;
+//! use riot_wrappers::coap_handler::diagnostics::Diagnostics;
+//!
+//! let mut handler = GcoapHandler(Diagnostics);
+//! let mut listener =
+//!     SingleHandlerListener::new(cstr::cstr!("/diag"), riot_sys::COAP_GET, &mut handler);
+//! gcoap::scope(|greg| {
+//!     greg.register(&mut listener);
+//!     // ...
+//! });
+//! ```
+//!
+//! The document is a map with text keys, containing those of the following entries the
+//! configured modules allow:
+//!
+//! * `"uptime_ms"`: Time on the millisecond clock (with `ztimer_msec`)
+//! * `"threads"`: An array of maps with `"pid"`, `"name"`, `"status"` and `"prio"`, and (with
+//!   `DEVELHELP`) `"stack_size"` and `"stack_free"`
+//! * `"heap"`: A map with the `"current"` and `"max"` allocated bytes (with `malloc_monitor`)
+//! * `"pktbuf"`: A map with the packet buffer [`"allocations"` and
+//!   `"failures"`](crate::gnrc::pktbuf::Stats) (with `gnrc_pktbuf`)
+//! * `"netifs"`: An array of maps with the interface's `"pid"`, and its `"l2"` and `"ipv6"`
+//!   [statistics](crate::gnrc::Netstats) (with `netstats_l2` and `netstats_ipv6`, respectively)
+//!
+//! Consumers should ignore keys they do not know; more may be added.
+//!
+//! This needs the `with_minicbor` feature. If the document does not fit in the response, the
+//! request fails with 5.00 Internal Server Error.
+
+use core::convert::TryInto;
+use core::fmt::Write;
+
+use coap_message::{MutableWritableMessage, ReadableMessage};
+use coap_numbers::{code, option};
+use minicbor::encode::write::{Cursor, Write as CborWrite};
+use minicbor::Encoder;
+
+use crate::thread::KernelPID;
+
+/// Content-Format of application/cbor
+const APPLICATION_CBOR: u8 = 60;
+
+type EncodeResult<W> = Result<(), minicbor::encode::Error<<W as CborWrite>::Error>>;
+
+/// Writer that only counts the bytes of the document
+struct Length(usize);
+
+impl CborWrite for Length {
+    type Error = core::convert::Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0 += buf.len();
+        Ok(())
+    }
+}
+
+/// The diagnostics resource; see the [module level documentation](self) for its output
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Diagnostics;
+
+fn encode_threads<W: CborWrite>(encoder: &mut Encoder<W>) -> EncodeResult<W> {
+    encoder.str("threads")?.begin_array()?;
+    for pid in KernelPID::all_pids() {
+        // Threads that end while this runs are skipped
+        let (status, prio) = match (pid.status(), pid.priority()) {
+            (Ok(status), Ok(prio)) => (status, prio),
+            _ => continue,
+        };
+        let mut status_name = heapless::String::<16>::new();
+        let _ = write!(status_name, "{:?}", status);
+
+        encoder.begin_map()?;
+        encoder.str("pid")?.i16(pid.into())?;
+        if let Some(name) = pid.get_name() {
+            encoder.str("name")?.str(name)?;
+        }
+        encoder.str("status")?.str(&status_name)?;
+        encoder.str("prio")?.u8(prio)?;
+        if let Ok(stack) = pid.stack_stats() {
+            encoder.str("stack_size")?.u32(stack.size() as _)?;
+            encoder.str("stack_free")?.u32(stack.free() as _)?;
+        }
+        encoder.end()?;
+    }
+    encoder.end()?;
+    Ok(())
+}
+
+#[cfg(any(riot_module_netstats_l2, riot_module_netstats_ipv6))]
+fn encode_netstats<W: CborWrite>(
+    encoder: &mut Encoder<W>,
+    stats: &crate::gnrc::Netstats,
+) -> EncodeResult<W> {
+    encoder
+        .map(7)?
+        .str("tx_unicast")?
+        .u32(stats.tx_unicast_count)?
+        .str("tx_mcast")?
+        .u32(stats.tx_mcast_count)?
+        .str("tx_success")?
+        .u32(stats.tx_success)?
+        .str("tx_failed")?
+        .u32(stats.tx_failed)?
+        .str("tx_bytes")?
+        .u32(stats.tx_bytes)?
+        .str("rx_count")?
+        .u32(stats.rx_count)?
+        .str("rx_bytes")?
+        .u32(stats.rx_bytes)?;
+    Ok(())
+}
+
+#[cfg(riot_module_gnrc)]
+fn encode_netifs<W: CborWrite>(encoder: &mut Encoder<W>) -> EncodeResult<W> {
+    encoder.str("netifs")?.begin_array()?;
+    for netif in crate::gnrc::Netif::all() {
+        encoder.begin_map()?;
+        encoder.str("pid")?.i16(netif.pid().into())?;
+        #[cfg(riot_module_netstats_l2)]
+        if let Ok(stats) = netif.stats(crate::gnrc::NetstatsLayer::L2) {
+            encoder.str("l2")?;
+            encode_netstats(encoder, &stats)?;
+        }
+        #[cfg(riot_module_netstats_ipv6)]
+        if let Ok(stats) = netif.stats(crate::gnrc::NetstatsLayer::Ipv6) {
+            encoder.str("ipv6")?;
+            encode_netstats(encoder, &stats)?;
+        }
+        encoder.end()?;
+    }
+    encoder.end()?;
+    Ok(())
+}
+
+/// Write the diagnostics document
+fn encode<W: CborWrite>(encoder: &mut Encoder<W>) -> EncodeResult<W> {
+    encoder.begin_map()?;
+
+    #[cfg(riot_module_ztimer_msec)]
+    encoder
+        .str("uptime_ms")?
+        .u32(crate::ztimer::Clock::msec().now().0)?;
+
+    encode_threads(encoder)?;
+
+    #[cfg(riot_module_malloc_monitor)]
+    {
+        // unsafe: OK per C API
+        let (current, max) = unsafe {
+            (
+                riot_sys::malloc_monitor_get_usage_current(),
+                riot_sys::malloc_monitor_get_usage_high_watermark(),
+            )
+        };
+        encoder
+            .str("heap")?
+            .map(2)?
+            .str("current")?
+            .u32(current as _)?
+            .str("max")?
+            .u32(max as _)?;
+    }
+
+    #[cfg(riot_module_gnrc_pktbuf)]
+    {
+        let stats = crate::gnrc::pktbuf::stats();
+        encoder
+            .str("pktbuf")?
+            .map(2)?
+            .str("allocations")?
+            .u32(stats.allocations)?
+            .str("failures")?
+            .u32(stats.failures)?;
+    }
+
+    #[cfg(riot_module_gnrc)]
+    encode_netifs(encoder)?;
+
+    encoder.end()?;
+    Ok(())
+}
+
+impl coap_handler::Handler for Diagnostics {
+    type RequestData = Result<(), u8>;
+
+    fn extract_request_data(&mut self, request: &impl ReadableMessage) -> Self::RequestData {
+        let method: u8 = request.code().into();
+        if method != code::GET {
+            return Err(code::METHOD_NOT_ALLOWED);
+        }
+        Ok(())
+    }
+
+    fn estimate_length(&mut self, _request: &Self::RequestData) -> usize {
+        // A few threads and interfaces; larger documents need larger (gcoap) buffers anyway
+        512
+    }
+
+    fn build_response(
+        &mut self,
+        response: &mut impl MutableWritableMessage,
+        request: Self::RequestData,
+    ) {
+        let result = request.and_then(|()| {
+            // Measuring first, so that an oversized document fails cleanly
+            let mut length = Encoder::new(Length(0));
+            let _ = encode(&mut length);
+            // Allowing for the Content-Format option, and for values that grow in the meantime
+            if length.into_writer().0 + 8 > response.available_space() {
+                return Err(code::INTERNAL_SERVER_ERROR);
+            }
+
+            response.set_code(code::CONTENT.try_into().map_err(|_| ()).unwrap());
+            response.add_option(
+                option::CONTENT_FORMAT
+                    .try_into()
+                    .map_err(|_| "Message type can't express option")
+                    .unwrap(),
+                &[APPLICATION_CBOR],
+            );
+            let mut encoder = Encoder::new(Cursor::new(response.payload_mut()));
+            encode(&mut encoder).map_err(|_| code::INTERNAL_SERVER_ERROR)?;
+            let len = encoder.into_writer().position();
+            response.truncate(len);
+            Ok(())
+        });
+        if let Err(code) = result {
+            response.set_code(code.try_into().map_err(|_| ()).unwrap());
+            response.set_payload(b"");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Color types compatible with RIOT's [color](https://doc.riot-os.org/group__sys__color.html)
+//! module
+//!
+//! The types are usable without the C module; conversions between them and to the C types need
+//! the `color` module.
+
+/// A color given in 8-bit red, green and blue components
+///
+/// This has the same memory layout as `color_rgb_t`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(C)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Self = Rgb::new(0, 0, 0);
+    pub const WHITE: Self = Rgb::new(255, 255, 255);
+    pub const RED: Self = Rgb::new(255, 0, 0);
+    pub const GREEN: Self = Rgb::new(0, 255, 0);
+    pub const BLUE: Self = Rgb::new(0, 0, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb { r, g, b }
+    }
+
+    /// Scale all components by `brightness`/255
+    pub const fn dimmed(self, brightness: u8) -> Self {
+        const fn scale(c: u8, f: u8) -> u8 {
+            ((c as u16 * f as u16) / 255) as u8
+        }
+        Rgb {
+            r: scale(self.r, brightness),
+            g: scale(self.g, brightness),
+            b: scale(self.b, brightness),
+        }
+    }
+}
+
+#[cfg(riot_module_color)]
+impl From<riot_sys::color_rgb_t> for Rgb {
+    fn from(c: riot_sys::color_rgb_t) -> Self {
+        Rgb::new(c.r, c.g, c.b)
+    }
+}
+
+#[cfg(riot_module_color)]
+impl From<Rgb> for riot_sys::color_rgb_t {
+    fn from(c: Rgb) -> Self {
+        riot_sys::color_rgb_t {
+            r: c.r,
+            g: c.g,
+            b: c.b,
+        }
+    }
+}
+
+/// A color given in hue (in degrees, 0 to 360), saturation and value (0 to 1)
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+#[cfg(riot_module_color)]
+impl From<Hsv> for Rgb {
+    #[doc(alias = "color_hsv2rgb")]
+    fn from(hsv: Hsv) -> Self {
+        let hsv = riot_sys::color_hsv_t {
+            h: hsv.h,
+            s: hsv.s,
+            v: hsv.v,
+        };
+        let mut rgb = riot_sys::color_rgb_t { r: 0, g: 0, b: 0 };
+        // unsafe: OK per C API
+        unsafe { riot_sys::color_hsv2rgb(&hsv, &mut rgb) };
+        rgb.into()
+    }
+}
+
+#[cfg(riot_module_color)]
+impl From<Rgb> for Hsv {
+    #[doc(alias = "color_rgb2hsv")]
+    fn from(rgb: Rgb) -> Self {
+        let rgb: riot_sys::color_rgb_t = rgb.into();
+        let mut hsv = riot_sys::color_hsv_t {
+            h: 0.0,
+            s: 0.0,
+            v: 0.0,
+        };
+        // unsafe: OK per C API
+        unsafe { riot_sys::color_rgb2hsv(&rgb, &mut hsv) };
+        Hsv {
+            h: hsv.h,
+            s: hsv.s,
+            v: hsv.v,
+        }
+    }
+}
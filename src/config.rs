@@ -0,0 +1,18 @@
+//! Build time configuration values of RIOT
+//!
+//! This module makes numeric configuration values of the RIOT build (such as buffer sizes set
+//! through Kconfig or `CFLAGS`) available as typed constants, so that Rust code can size its
+//! buffers consistently with the C side:
+//!
+//! ```no_run
+//! # #![no_std]
+//! let mut response = [0u8; riot_wrappers::config::GCOAP_PDU_BUF_SIZE];
+//! ```
+//!
+//! Only values that are relevant to Rust code are exposed (see `CONFIG_VALUES` in the build
+//! script); any `CONFIG_` prefix of the C name is removed. A value is only present if it is
+//! defined in the RIOT build as a plain number, which depends on the modules that are used (eg.
+//! there are no GCOAP values if gcoap is not in use). For every value that is present, the
+//! `riot_config_<name in lowercase>` cfg is set, so that code can fall back to a default.
+
+include!(concat!(env!("OUT_DIR"), "/config.rs"));
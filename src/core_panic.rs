@@ -0,0 +1,54 @@
+//! Halting the system through RIOT's [core_panic](https://doc.riot-os.org/panic_8h.html)
+//!
+//! This is what RIOT does on unrecoverable errors (failed assertions, hardware faults): Depending
+//! on the build, the system prints the message and halts, reboots or hands control to a crash
+//! handler (eg. `DEVELHELP`'s debugger breakpoint, or the `panic_notify` hooks).
+//!
+//! With the `set_panic_handler` and `panic_handler_crash` features, Rust panics are reported
+//! through this as [PanicCode::GeneralError].
+
+use core::ffi::CStr;
+
+/// Reason for a system halt, as passed to `core_panic`
+///
+/// Only the codes that are available on all platforms are represented here.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum PanicCode {
+    /// Unspecified unrecoverable error (`PANIC_GENERAL_ERROR`)
+    GeneralError,
+    /// Requested soft reboot (`PANIC_SOFT_REBOOT`)
+    SoftReboot,
+    /// Requested hard reboot (`PANIC_HARD_REBOOT`)
+    HardReboot,
+    /// Failed assertion (`PANIC_ASSERT_FAIL`)
+    AssertFail,
+    /// Failed expectation (`PANIC_EXPECT_FAIL`)
+    ExpectFail,
+    /// Undefined state (`PANIC_UNDEFINED`)
+    Undefined,
+}
+
+impl PanicCode {
+    /// The C value of the code
+    pub fn to_c(self) -> riot_sys::core_panic_t {
+        match self {
+            PanicCode::GeneralError => riot_sys::core_panic_t_PANIC_GENERAL_ERROR,
+            PanicCode::SoftReboot => riot_sys::core_panic_t_PANIC_SOFT_REBOOT,
+            PanicCode::HardReboot => riot_sys::core_panic_t_PANIC_HARD_REBOOT,
+            PanicCode::AssertFail => riot_sys::core_panic_t_PANIC_ASSERT_FAIL,
+            PanicCode::ExpectFail => riot_sys::core_panic_t_PANIC_EXPECT_FAIL,
+            PanicCode::Undefined => riot_sys::core_panic_t_PANIC_UNDEFINED,
+        }
+    }
+}
+
+/// Halt the system, reporting the code and message
+///
+/// This does not return; what happens instead depends on the RIOT configuration.
+#[doc(alias = "core_panic")]
+pub fn core_panic(code: PanicCode, message: &CStr) -> ! {
+    // unsafe: OK per C API
+    unsafe { riot_sys::core_panic(code.to_c(), message.as_ptr() as _) }
+}
@@ -0,0 +1,96 @@
+//! Helpers for handling secret data
+//!
+//! These are the equivalents of RIOT's `crypto_equals` and `crypto_secure_wipe` (from
+//! `crypto/helper.h`): A comparison whose duration does not depend on where the inputs differ
+//! (for checking MACs or tokens), and wiping of memory that the compiler may not optimize out
+//! (for key material that is about to go out of scope).
+//!
+//! ```no_run
+//! # #![no_std]
+//! use riot_wrappers::crypto::{equals, Wiped};
+//!
+//! # fn compute_mac(_key: &[u8], _data: &[u8]) -> [u8; 16] { [0; 16] }
+//! # let (data, received_mac) = (b"", [0u8; 16]);
+//! let key = Wiped::new([0u8; 32]);
+//! // ... load the key ...
+//! let mac = compute_mac(&key[..], data);
+//! if !equals(&mac, &received_mac) {
+//!     // reject
+//! }
+//! // key is wiped when it goes out of scope here
+//! ```
+//!
+//! They are implemented in Rust (using volatile accesses the same way RIOT's implementations
+//! do), and are thus available independently of whether the `crypto` module is built.
+
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// Compare two byte slices in time that only depends on their length
+///
+/// Slices of different length are never equal; that they differ in length is not hidden.
+#[doc(alias = "crypto_equals")]
+pub fn equals(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (a, b) in a.iter().zip(b.iter()) {
+        // unsafe: Reading from a valid reference. The volatile read keeps the compiler from
+        // short-cutting the loop once a difference is found.
+        diff |= unsafe { core::ptr::read_volatile(a) } ^ unsafe { core::ptr::read_volatile(b) };
+    }
+    diff == 0
+}
+
+/// Overwrite the memory with zeros in a way that is not optimized out
+///
+/// Use this on key material before its memory is released or reused.
+#[doc(alias = "crypto_secure_wipe")]
+pub fn secure_wipe(buf: &mut [u8]) {
+    for byte in buf.iter_mut() {
+        // unsafe: Writing to a valid exclusive reference
+        unsafe { core::ptr::write_volatile(byte, 0) };
+    }
+    // Keep later accesses (eg. releasing the memory) from being reordered before the wipe
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// A buffer of secret data that is wiped with [secure_wipe] when dropped
+///
+/// Note that this can not wipe copies that were made when the buffer was moved; keep the
+/// `Wiped` in one place (eg. by creating it where it is used) to avoid leaving copies behind.
+pub struct Wiped<T: AsMut<[u8]>>(T);
+
+impl<T: AsMut<[u8]>> Wiped<T> {
+    pub const fn new(inner: T) -> Self {
+        Wiped(inner)
+    }
+}
+
+impl<T: AsMut<[u8]>> Deref for Wiped<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> DerefMut for Wiped<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> Drop for Wiped<T> {
+    fn drop(&mut self) {
+        secure_wipe(self.0.as_mut());
+    }
+}
+
+impl<T: AsMut<[u8]>> core::fmt::Debug for Wiped<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Not showing the secret, not even in debug output
+        f.write_str("Wiped(..)")
+    }
+}
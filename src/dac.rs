@@ -6,6 +6,7 @@ pub struct DACLine {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum DACError {
     /// The given dac_t line did not exist
@@ -63,3 +64,6 @@ impl DACLine {
         unsafe { riot_sys::dac_poweroff(self.line) }
     }
 }
+
+#[cfg(riot_module_dac_dds)]
+pub mod dds;
@@ -0,0 +1,115 @@
+//! Playback of sample buffers on a DAC at a fixed rate through the
+//! [dac_dds](https://doc.riot-os.org/group__drivers__dac__dds.html) module
+//!
+//! A [Dds] plays buffers of samples; while one buffer is playing, another one can be queued,
+//! which is then played without a gap. A callback notifies when the queued buffer has started
+//! playing, ie. when the next buffer can be queued.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::dac::dds::{Dds, SampleFormat};
+//! // A square wave of 1 kHz at 8 kHz sample rate
+//! static BEEP: [u8; 8] = [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0];
+//!
+//! let mut dds = Dds::new(0, 8000, SampleFormat::Bits8);
+//! for _ in 0..100 {
+//!     dds.play(&BEEP);
+//! }
+//! ```
+
+/// Format of the samples in the buffers passed to [Dds::play]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// Unsigned 8 bit samples
+    Bits8,
+    /// Unsigned 16 bit samples in native byte order
+    Bits16,
+}
+
+/// Result of [Dds::play]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Playback {
+    /// The buffer is played right away, either because nothing was playing or because the
+    /// previous buffers ran out
+    Started,
+    /// The buffer was queued, and will be played when the current buffer ends
+    Queued,
+}
+
+/// A DAC that plays sample buffers
+#[derive(Debug)]
+pub struct Dds {
+    dac: riot_sys::dac_dds_t,
+}
+
+unsafe extern "C" fn callback<F: FnMut() + Send>(arg: *mut riot_sys::libc::c_void) {
+    let f = &mut *(arg as *mut F);
+    f();
+}
+
+impl Dds {
+    /// Initialize the DDS channel of the given index for the sample rate (in Hz) and format
+    ///
+    /// Panics if the index is not a valid DDS channel of the board (see `DAC_DDS_NUMOF`).
+    #[doc(alias = "dac_dds_init")]
+    pub fn new(index: u8, sample_rate: u16, format: SampleFormat) -> Self {
+        assert!(
+            (index as u32) < riot_sys::DAC_DDS_NUMOF as u32,
+            "No such DAC DDS channel"
+        );
+        let flags = match format {
+            SampleFormat::Bits8 => riot_sys::DAC_FLAG_8BIT,
+            SampleFormat::Bits16 => riot_sys::DAC_FLAG_16BIT,
+        };
+        // unsafe: OK per C API; the index is valid.
+        unsafe {
+            riot_sys::dac_dds_init(
+                index as _,
+                sample_rate,
+                flags as _,
+                None,
+                core::ptr::null_mut(),
+            )
+        };
+        Dds { dac: index as _ }
+    }
+
+    /// Set a callback that is called whenever a queued buffer starts playing, ie. when the next
+    /// buffer can be queued
+    ///
+    /// The callback is called in interrupt context.
+    #[doc(alias = "dac_dds_set_cb")]
+    pub fn set_callback<F: FnMut() + Send>(&mut self, cb: &'static mut F) {
+        // unsafe: OK per C API; the callback argument is valid for as long as it may be called.
+        unsafe { riot_sys::dac_dds_set_cb(self.dac, Some(callback::<F>), cb as *mut F as *mut _) };
+    }
+
+    /// Remove the callback set with [set_callback](Self::set_callback)
+    pub fn clear_callback(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::dac_dds_set_cb(self.dac, None, core::ptr::null_mut()) };
+    }
+
+    /// Play a buffer of samples in the configured [SampleFormat]
+    ///
+    /// If a buffer is already playing, this one is queued to be played after it; if there is a
+    /// queued buffer already, this blocks until that started playing.
+    ///
+    /// The buffer needs to be static, as the DAC keeps reading from it in the background.
+    #[doc(alias = "dac_dds_play")]
+    pub fn play(&mut self, buf: &'static [u8]) -> Playback {
+        // unsafe: OK per C API; the buffer outlives the playback.
+        match unsafe { riot_sys::dac_dds_play(self.dac, buf.as_ptr() as *const _, buf.len()) } {
+            true => Playback::Queued,
+            false => Playback::Started,
+        }
+    }
+
+    /// Stop playing, discarding any queued buffer
+    #[doc(alias = "dac_dds_stop")]
+    pub fn stop(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::dac_dds_stop(self.dac) };
+    }
+}
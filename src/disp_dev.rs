@@ -0,0 +1,212 @@
+//! Drawing on displays through RIOT's [generic display
+//! API](https://doc.riot-os.org/group__drivers__disp__dev.html)
+//!
+//! A [Display] implements the [embedded_graphics] [DrawTarget], so any display with a `disp_dev`
+//! driver can be drawn on with the embedded-graphics primitives, fonts and images:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::disp_dev::Display;
+//! use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Circle, style::*};
+//!
+//! let mut display: Display<64> = Display::from_screen_id(0).unwrap();
+//! display.clear(Rgb565::BLACK).unwrap();
+//! Circle::new(Point::new(40, 40), 20)
+//!     .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+//!     .draw(&mut display)
+//!     .unwrap();
+//! ```
+//!
+//! Pixels are collected in a staging buffer of `N` pixels, and are sent to the device whenever a
+//! run of horizontally adjacent pixels ends or the buffer is full. Pixels drawn through
+//! [DrawTarget::draw_iter] (which is what most drawables use) are on the device when the call
+//! returns; after individual [DrawTarget::draw_pixel] calls, [Display::flush] needs to be called
+//! (or the display dropped).
+
+use embedded_graphics::{
+    drawable::Pixel,
+    geometry::{Point, Size},
+    pixelcolor::raw::{RawData, RawU16},
+    pixelcolor::Rgb565,
+    DrawTarget,
+};
+
+use crate::Never;
+
+/// A display registered with disp_dev, with a staging buffer of `N` pixels
+pub struct Display<const N: usize> {
+    dev: *mut riot_sys::disp_dev_t,
+    staging: [u16; N],
+    /// Position of the first pixel in the staging buffer
+    start: Point,
+    /// Number of pixels in the staging buffer
+    len: usize,
+}
+
+impl<const N: usize> Display<N> {
+    /// Use the display that was registered under the given screen ID
+    ///
+    /// Displays are registered by their drivers' auto-initialization through `disp_dev_reg`, with
+    /// screen IDs starting at 0.
+    #[doc(alias = "disp_dev_reg_find_screen")]
+    pub fn from_screen_id(screen_id: u8) -> Option<Self> {
+        // unsafe: OK per C API
+        let reg = unsafe { riot_sys::disp_dev_reg_find_screen(screen_id) };
+        if reg.is_null() {
+            return None;
+        }
+        // unsafe: Registrations are static, and their dev pointers valid
+        Some(unsafe { Self::from_ptr((*reg).dev) })
+    }
+
+    /// Use a display given by its C device
+    ///
+    /// # Safety
+    ///
+    /// The device needs to be initialized and valid for the lifetime of the Display, and no other
+    /// component may draw on it concurrently.
+    pub unsafe fn from_ptr(dev: *mut riot_sys::disp_dev_t) -> Self {
+        Display {
+            dev,
+            staging: [0; N],
+            start: Point::zero(),
+            len: 0,
+        }
+    }
+
+    /// Width of the display in pixels
+    #[doc(alias = "disp_dev_width")]
+    pub fn width(&self) -> u16 {
+        // unsafe: OK per C API
+        unsafe { riot_sys::disp_dev_width(self.dev) }
+    }
+
+    /// Height of the display in pixels
+    #[doc(alias = "disp_dev_height")]
+    pub fn height(&self) -> u16 {
+        // unsafe: OK per C API
+        unsafe { riot_sys::disp_dev_height(self.dev) }
+    }
+
+    /// Invert the display's colors
+    #[doc(alias = "disp_dev_set_invert")]
+    pub fn set_invert(&mut self, invert: bool) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::disp_dev_set_invert(self.dev, invert) }
+    }
+
+    /// Send any staged pixels to the device
+    pub fn flush(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+        self.map(self.start, self.len as u16, 1, &self.staging[..self.len]);
+        self.len = 0;
+    }
+
+    /// Send a rectangle of `width` times `height` pixels with the top left corner at `start`
+    ///
+    /// The colors need to cover the full rectangle.
+    #[doc(alias = "disp_dev_map")]
+    fn map(&self, start: Point, width: u16, height: u16, colors: &[u16]) {
+        debug_assert!(colors.len() >= width as usize * height as usize);
+        let area = riot_sys::disp_dev_area_t {
+            x1: start.x as u16,
+            x2: start.x as u16 + width - 1,
+            y1: start.y as u16,
+            y2: start.y as u16 + height - 1,
+        };
+        // unsafe: OK per C API; the colors are sufficient for the area and are not retained.
+        unsafe { riot_sys::disp_dev_map(self.dev, &area, colors.as_ptr()) }
+    }
+
+    /// Put a pixel into the staging buffer, flushing if it does not continue the current run
+    fn stage(&mut self, point: Point, color: Rgb565) {
+        let continues =
+            self.len > 0 && point.y == self.start.y && point.x == self.start.x + self.len as i32;
+        let raw = RawU16::from(color).into_inner();
+        if !continues || self.len == N {
+            self.flush();
+            self.start = point;
+        }
+        if N == 0 {
+            self.map(point, 1, 1, &[raw]);
+            return;
+        }
+        self.staging[self.len] = raw;
+        self.len += 1;
+    }
+}
+
+impl<const N: usize> DrawTarget<Rgb565> for Display<N> {
+    type Error = Never;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), Never> {
+        let Pixel(point, color) = pixel;
+        if point.x < 0
+            || point.y < 0
+            || point.x >= self.width() as i32
+            || point.y >= self.height() as i32
+        {
+            return Ok(());
+        }
+        self.stage(point, color);
+        Ok(())
+    }
+
+    fn draw_iter<T>(&mut self, item: T) -> Result<(), Never>
+    where
+        T: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        for pixel in item {
+            self.draw_pixel(pixel)?;
+        }
+        self.flush();
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        Size::new(self.width().into(), self.height().into())
+    }
+
+    fn clear(&mut self, color: Rgb565) -> Result<(), Never> {
+        self.flush();
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        if N == 0 || width == 0 {
+            return Ok(());
+        }
+        self.staging = [RawU16::from(color).into_inner(); N];
+        // Send whole lines at a time if they fit, otherwise chunks of a line
+        let lines = N / width;
+        if lines > 0 {
+            for y in (0..height).step_by(lines) {
+                let count = lines.min(height - y);
+                self.map(
+                    Point::new(0, y as i32),
+                    width as u16,
+                    count as u16,
+                    &self.staging,
+                );
+            }
+        } else {
+            for y in 0..height {
+                for x in (0..width).step_by(N) {
+                    let count = N.min(width - x);
+                    self.map(
+                        Point::new(x as i32, y as i32),
+                        count as u16,
+                        1,
+                        &self.staging,
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> Drop for Display<N> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
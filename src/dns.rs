@@ -0,0 +1,123 @@
+//! Name resolution through [sock_dns](https://doc.riot-os.org/group__net__sock__dns.html) and
+//! its [cache](https://doc.riot-os.org/group__net__dns__cache.html)
+//!
+//! When the `dns_cache` module is enabled, every resolution performed by RIOT (be it through
+//! [query()], or through DNS over CoAP) is entered into the cache with the TTL of the response,
+//! and later queries for the same name are answered from there until the TTL expires. The [cache]
+//! module allows inspecting and manipulating the cache directly.
+
+use core::ffi::CStr;
+
+#[cfg(riot_module_sock_dns)]
+use crate::error::{Errno, NegativeErrorExt, NumericError};
+
+/// An address a name was resolved to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResolvedAddress {
+    Ipv4([u8; 4]),
+    Ipv6([u8; 16]),
+}
+
+impl ResolvedAddress {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            ResolvedAddress::Ipv4(a) => a,
+            ResolvedAddress::Ipv6(a) => a,
+        }
+    }
+
+    /// Build an address from the output of a query function, given the length it reported
+    fn from_buffer(buf: &[u8; 16], len: usize) -> Option<Self> {
+        match len {
+            4 => Some(ResolvedAddress::Ipv4(buf[..4].try_into().unwrap())),
+            16 => Some(ResolvedAddress::Ipv6(*buf)),
+            _ => None,
+        }
+    }
+}
+
+/// Address family to ask for in a query
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Family {
+    Ipv4,
+    Ipv6,
+    /// Accept any family
+    Any,
+}
+
+impl Family {
+    fn to_c(self) -> i32 {
+        (match self {
+            Family::Ipv4 => riot_sys::AF_INET,
+            Family::Ipv6 => riot_sys::AF_INET6,
+            Family::Any => riot_sys::AF_UNSPEC,
+        }) as _
+    }
+}
+
+/// Resolve a host name using the configured DNS server
+///
+/// This blocks until a response arrives or the query times out. With the `dns_cache` module, the
+/// result is answered from and entered into the cache.
+#[cfg(riot_module_sock_dns)]
+#[doc(alias = "sock_dns_query")]
+pub fn query(name: &CStr, family: Family) -> Result<ResolvedAddress, NumericError> {
+    let mut buf = [0u8; 16];
+    // unsafe: OK per C API; the buffer is large enough for any address family.
+    let len = unsafe {
+        riot_sys::sock_dns_query(name.as_ptr(), buf.as_mut_ptr() as *mut _, family.to_c())
+    }
+    .negative_to_error()?;
+    ResolvedAddress::from_buffer(&buf, len as _).ok_or_else(|| Errno::Inval.into())
+}
+
+/// Direct access to the DNS cache
+#[cfg(riot_module_dns_cache)]
+pub mod cache {
+    use super::{CStr, Family, ResolvedAddress};
+
+    /// Enter an address for the name into the cache, valid for `ttl` seconds
+    ///
+    /// This replaces any cached address of the same family for that name.
+    #[doc(alias = "dns_cache_add")]
+    pub fn add(name: &CStr, address: &ResolvedAddress, ttl: u32) {
+        let bytes = address.as_bytes();
+        // unsafe: OK per C API; the name is copied into the cache.
+        unsafe {
+            riot_sys::dns_cache_add(
+                name.as_ptr(),
+                bytes.as_ptr() as *const _,
+                bytes.len() as _,
+                ttl,
+            )
+        }
+    }
+
+    /// Look up a name in the cache without sending any query
+    ///
+    /// Returns None if no unexpired entry of the requested family is present.
+    #[doc(alias = "dns_cache_query")]
+    pub fn query(name: &CStr, family: Family) -> Option<ResolvedAddress> {
+        let mut buf = [0u8; 16];
+        // unsafe: OK per C API; the buffer is large enough for any address family.
+        let len = unsafe {
+            riot_sys::dns_cache_query(name.as_ptr(), buf.as_mut_ptr() as *mut _, family.to_c())
+        };
+        if len <= 0 {
+            return None;
+        }
+        ResolvedAddress::from_buffer(&buf, len as _)
+    }
+
+    /// Remove any cached addresses for the name
+    ///
+    /// The C cache has no removal function; this overwrites the entries with ones that expire
+    /// right away, so that the next query for the name goes out to the network again.
+    pub fn invalidate(name: &CStr) {
+        for family in [Family::Ipv4, Family::Ipv6] {
+            if let Some(address) = query(name, family) {
+                add(name, &address, 0);
+            }
+        }
+    }
+}
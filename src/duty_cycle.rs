@@ -0,0 +1,161 @@
+//! Duty-cycled sampling for battery powered sensor nodes
+//!
+//! A [DutyCycle] runs the typical cycle of a node that spends most of its life asleep: wake up,
+//! power on the sensors, read them through [SAUL](crate::saul), transmit the readings, power the
+//! sensors back down and sleep until the next period starts. The application plugs into each of
+//! those steps by implementing [Phases]:
+//!
+//! ```no_run
+//! # #![no_std]
+//! use riot_wrappers::duty_cycle::{DutyCycle, Phases};
+//! use riot_wrappers::error::NumericError;
+//! use riot_wrappers::saul::{Phydat, RegistryEntry};
+//! use riot_wrappers::ztimer::{Clock, Ticks};
+//!
+//! struct Node {
+//!     readings: heapless::Vec<Phydat, 4>,
+//! }
+//!
+//! impl Phases for Node {
+//!     fn sample(&mut self, _entry: &RegistryEntry, reading: Result<Phydat, NumericError>) {
+//!         if let Ok(reading) = reading {
+//!             let _ = self.readings.push(reading);
+//!         }
+//!     }
+//!
+//!     fn transmit(&mut self) {
+//!         // send self.readings, eg. through a CoAP request
+//!         self.readings.clear();
+//!     }
+//! }
+//!
+//! let mut node = Node { readings: heapless::Vec::new() };
+//! DutyCycle::new(Clock::sec(), Ticks(600)).run(&mut node);
+//! ```
+//!
+//! Sleeping is done by blocking the thread on the given clock; when no thread has anything to do,
+//! RIOT enters the deepest [power mode](crate::pm) that is not blocked (blocking a mode also
+//! rules out all deeper ones). For this to be an actual deep sleep, the clock needs to keep
+//! running in that mode, which is typically the case for `ZTIMER_SEC` and `ZTIMER_MSEC` when
+//! backed by an RTT or RTC, but not for `ZTIMER_USEC`.
+//! Conversely, when sensors or the radio need the CPU to stay out of some modes while they are
+//! active, [block_during_activity](DutyCycle::block_during_activity) keeps these modes blocked
+//! only from waking up to transmitting.
+
+use crate::error::NumericError;
+use crate::saul::{Phydat, RegistryEntry};
+use crate::ztimer::{Clock, Ticks};
+
+/// Application hooks for the steps of a [DutyCycle]
+///
+/// In every cycle, the hooks are called in the order of their declaration here. Only sampling and
+/// transmitting need to be implemented.
+pub trait Phases {
+    /// Called right after the node woke up
+    fn wake(&mut self) {}
+
+    /// Power on the sensors, eg. by switching a GPIO that supplies them
+    ///
+    /// If the sensors need time to settle, this can sleep (on a clock fine enough for the
+    /// purpose) before returning.
+    fn power_on(&mut self) {}
+
+    /// Decide whether a SAUL entry is to be read in this cycle
+    ///
+    /// By default, all entries are read.
+    fn selects(&mut self, _entry: &RegistryEntry) -> bool {
+        true
+    }
+
+    /// Process the reading of a selected SAUL entry
+    fn sample(&mut self, entry: &RegistryEntry, reading: Result<Phydat, NumericError>);
+
+    /// Power the sensors back down
+    fn power_off(&mut self) {}
+
+    /// Send out what was sampled in this cycle
+    fn transmit(&mut self);
+
+    /// Called before the node goes back to sleep
+    ///
+    /// This is not called if the cycle took longer than the period, as the next cycle starts
+    /// right away then.
+    fn sleep(&mut self) {}
+}
+
+/// Driver of a periodic wake / sample / transmit / sleep cycle
+///
+/// See the [module level documentation](self) for usage.
+#[derive(Copy, Clone)]
+pub struct DutyCycle<const HZ: u32> {
+    clock: Clock<HZ>,
+    period: Ticks<HZ>,
+    #[cfg(riot_module_pm_layered)]
+    active_mode: Option<u8>,
+}
+
+impl<const HZ: u32> DutyCycle<HZ> {
+    /// Create a duty cycle that starts a new cycle every `period` on `clock`
+    pub fn new(clock: Clock<HZ>, period: Ticks<HZ>) -> Self {
+        DutyCycle {
+            clock,
+            period,
+            #[cfg(riot_module_pm_layered)]
+            active_mode: None,
+        }
+    }
+
+    /// Keep the given power mode (and all deeper ones) blocked while the node is awake
+    ///
+    /// The mode is blocked before [Phases::wake] and unblocked after [Phases::transmit].
+    ///
+    /// Panics if the mode is not a valid mode of the CPU.
+    #[cfg(riot_module_pm_layered)]
+    pub fn block_during_activity(self, mode: u8) -> Self {
+        assert!(mode < crate::pm::NUM_MODES, "Invalid power mode");
+        DutyCycle {
+            active_mode: Some(mode),
+            ..self
+        }
+    }
+
+    /// Run a single cycle, without the sleep at the end
+    ///
+    /// This is useful when waking up is driven by something else, eg. by an external interrupt.
+    pub fn cycle(&self, phases: &mut impl Phases) {
+        #[cfg(riot_module_pm_layered)]
+        let _block = self.active_mode.map(crate::pm::Block::new);
+
+        phases.wake();
+        phases.power_on();
+        for entry in RegistryEntry::all() {
+            if phases.selects(&entry) {
+                let reading = entry.read();
+                phases.sample(&entry, reading);
+            }
+        }
+        phases.power_off();
+        phases.transmit();
+    }
+
+    /// Run cycles forever, starting right away
+    ///
+    /// Cycles start at multiples of the period, so the time spent in the cycles does not make the
+    /// schedule drift. If a cycle takes longer than the period, the next one starts immediately,
+    /// and the schedule continues from there.
+    pub fn run(&self, phases: &mut impl Phases) -> ! {
+        let mut start = self.clock.now().0;
+        loop {
+            self.cycle(phases);
+            let elapsed = self.clock.now().0.wrapping_sub(start);
+            start = match self.period.0.checked_sub(elapsed) {
+                Some(remaining) => {
+                    phases.sleep();
+                    self.clock.sleep_ticks(remaining);
+                    start.wrapping_add(self.period.0)
+                }
+                None => start.wrapping_add(elapsed),
+            };
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Running an [embassy-executor](https://docs.rs/embassy-executor) on a RIOT thread
+//!
+//! An [Executor] takes over the thread it is run in: It polls its tasks whenever any of them are
+//! woken, and otherwise waits for the [EXECUTOR_FLAG] thread flag to be set, which is what its
+//! wakers do. As thread flags may be set from interrupts, futures may be woken from ISRs (eg. from
+//! a timer or GPIO callback) as well as from other threads.
+//!
+//! Any future that uses the regular [core::task::Waker] mechanism runs on the executor; this
+//! includes the futures provided by riot-wrappers, such as
+//! [`Clock::sleep_async()`](crate::ztimer::Clock::sleep_async).
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::embassy::Executor;
+//! #[embassy_executor::task]
+//! async fn blink() {
+//!     let clock = riot_wrappers::ztimer::Clock::msec();
+//!     loop {
+//!         // ... toggle some LED ...
+//!         clock.sleep_async(riot_wrappers::ztimer::Ticks(500)).await;
+//!     }
+//! }
+//!
+//! fn main() -> ! {
+//!     static mut EXECUTOR: Option<Executor> = None;
+//!     // unsafe: main is only run once
+//!     let executor = unsafe { EXECUTOR.insert(Executor::new()) };
+//!     executor.run(|spawner| spawner.spawn(blink()).unwrap())
+//! }
+//! ```
+//!
+//! This module is only available with the `with_embassy` feature, and requires the
+//! core_thread_flags module.
+
+use core::marker::PhantomData;
+
+use crate::thread::KernelPID;
+
+/// Thread flag set by an [Executor]'s wakers to indicate that tasks are ready to be polled
+///
+/// Threads that run an executor should not use this flag for other purposes.
+///
+/// See the [flags module](crate::thread::flags) for the other flags used by this crate.
+pub const EXECUTOR_FLAG: riot_sys::thread_flags_t = 1 << 12;
+
+/// Called by embassy-executor whenever a task of an executor is woken
+///
+/// The context is the PID of the executor's thread, as set up in [Executor::new].
+#[export_name = "__pender"]
+fn __pender(context: *mut ()) {
+    let pid = KernelPID(context as usize as riot_sys::kernel_pid_t);
+    // This may be used from ISRs and other threads alike. If the executor's thread is gone,
+    // there is nobody left to poll the task.
+    let _ = crate::thread::flags::set(pid, crate::thread::flags::Flags::from_bits(EXECUTOR_FLAG));
+}
+
+/// An async executor that runs in a RIOT thread and sleeps on thread flags while idle
+pub struct Executor {
+    inner: embassy_executor::raw::Executor,
+    thread: KernelPID,
+    // The executor is bound to the thread it was created in, as its wakers signal that thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Executor {
+    /// Create an executor bound to the current thread
+    pub fn new() -> Self {
+        let pid = crate::thread::get_pid();
+        Self {
+            inner: embassy_executor::raw::Executor::new(pid.0 as usize as *mut ()),
+            thread: pid,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Run the executor
+    ///
+    /// The `init` closure is called once with a [Spawner](embassy_executor::Spawner) that is used
+    /// to start the initial tasks. After that, this polls tasks when they are woken, and blocks
+    /// the thread otherwise.
+    ///
+    /// # Panics
+    ///
+    /// ... if not called from the thread the executor was created in.
+    #[doc(alias = "thread_flags_wait_any")]
+    pub fn run(&'static mut self, init: impl FnOnce(embassy_executor::Spawner)) -> ! {
+        // The raw executor's API works on shared references
+        let this: &'static Self = self;
+
+        assert!(
+            crate::thread::get_pid() == this.thread,
+            "Executor must be run in the thread it was created in"
+        );
+        init(this.inner.spawner());
+
+        loop {
+            // unsafe: We're in the thread the executor was created in (and which its pender
+            // wakes), and poll is not called reentrantly.
+            unsafe { this.inner.poll() };
+            // unsafe: OK per C API
+            unsafe { riot_sys::thread_flags_wait_any(EXECUTOR_FLAG) };
+        }
+    }
+}
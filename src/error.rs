@@ -1,6 +1,6 @@
 //! Common error handling components for the RIOT operating system
 
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 
 pub trait NegativeErrorExt {
     type Out;
@@ -16,7 +16,10 @@ pub trait NegativeErrorExt {
 /// represent `Result<positive_usize, NumericError>` as just the isize it originally was. For the
 /// time being, this works well enough, and performance evaluation can later be done against a
 /// manually implemented newtype around isize that'd be used to represent the Result.
-#[derive(Debug)]
+///
+/// Well-known error numbers can be inspected through [`.errno()`](NumericError::errno).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct NumericError {
     pub number: isize,
 }
@@ -54,20 +57,266 @@ impl NumericError {
     /// Convert the error into an [nb::Error] that is [nb::Error::WouldBlock] if the error is
     /// `-EAGAIN`, and an actual error otherwise.
     pub fn again_is_wouldblock(self) -> nb::Error<Self> {
-        match -self.number as u32 {
-            riot_sys::EAGAIN => nb::Error::WouldBlock,
+        match self.errno() {
+            Some(Errno::Again) => nb::Error::WouldBlock,
             _ => nb::Error::Other(self),
         }
     }
 }
 
-// Would be nice, but there's no strerror
-//
-// impl core::fmt::Display for NumericError {
-//     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
-//         write!(f, "Error {} ({})", self.number, ...)
-//     }
-// }
+impl NumericError {
+    /// Look up the error as one of the well-known [Errno] values
+    ///
+    /// This returns None if the number is not negative, or if it does not correspond to any errno
+    /// known to riot-wrappers.
+    pub fn errno(&self) -> Option<Errno> {
+        if self.number >= 0 {
+            return None;
+        }
+        Errno::from_constant(-self.number as _)
+    }
+
+    /// Convert the error back into the negative number as which it is used in RIOT APIs
+    ///
+    /// This is useful when a Rust function implements a C callback, and the error needs to be
+    /// passed back into C.
+    pub fn to_negative<T: TryFrom<isize> + num_traits::Bounded>(&self) -> T {
+        self.number.try_into().unwrap_or(T::min_value())
+    }
+}
+
+impl From<Errno> for NumericError {
+    fn from(errno: Errno) -> Self {
+        NumericError::from_constant(errno.to_constant() as _)
+    }
+}
+
+impl PartialEq<Errno> for NumericError {
+    fn eq(&self, other: &Errno) -> bool {
+        self.errno() == Some(*other)
+    }
+}
+
+impl core::fmt::Display for NumericError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        // There's no strerror in RIOT, but the symbolic names are way more useful than just the
+        // numbers.
+        match self.errno() {
+            Some(errno) => write!(f, "{} ({})", errno, self.number),
+            None => write!(f, "Error {}", self.number),
+        }
+    }
+}
+
+#[cfg(feature = "core_error")]
+impl core::error::Error for NumericError {}
+
+/// Symbolic names for the errors that are commonly returned by RIOT functions
+///
+/// These can be obtained from a [NumericError] through its [`.errno()`](NumericError::errno)
+/// method, and turned into one using `.into()`. They allow matching on meaningful names rather
+/// than comparing error numbers:
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize {
+/// # use riot_wrappers::error::{Errno, NumericError};
+/// # let result: Result<(), NumericError> = Err(Errno::Again.into());
+/// match result.map_err(|e| e.errno()) {
+///     Ok(()) => (),
+///     Err(Some(Errno::Again)) => (), // try again later
+///     Err(_) => panic!("Unexpected error"),
+/// }
+/// # 0
+/// # }
+/// ```
+///
+/// The Display implementation shows the C name (eg. `ENOMEM`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Errno {
+    /// Operation not permitted (`EPERM`)
+    Perm,
+    /// No such file or directory (`ENOENT`)
+    NoEnt,
+    /// Interrupted system call (`EINTR`)
+    Intr,
+    /// I/O error (`EIO`)
+    Io,
+    /// No such device or address (`ENXIO`)
+    NxIo,
+    /// Bad file descriptor (`EBADF`)
+    BadF,
+    /// Resource temporarily unavailable (`EAGAIN`)
+    Again,
+    /// Out of memory (`ENOMEM`)
+    NoMem,
+    /// Permission denied (`EACCES`)
+    Acces,
+    /// Bad address (`EFAULT`)
+    Fault,
+    /// Device or resource busy (`EBUSY`)
+    Busy,
+    /// File exists (`EEXIST`)
+    Exist,
+    /// No such device (`ENODEV`)
+    NoDev,
+    /// Not a directory (`ENOTDIR`)
+    NotDir,
+    /// Is a directory (`EISDIR`)
+    IsDir,
+    /// Invalid argument (`EINVAL`)
+    Inval,
+    /// No space left on device (`ENOSPC`)
+    NoSpc,
+    /// Read-only file system (`EROFS`)
+    RoFs,
+    /// Numerical argument out of domain (`EDOM`)
+    Dom,
+    /// Result out of range (`ERANGE`)
+    Range,
+    /// Function not implemented (`ENOSYS`)
+    NoSys,
+    /// Message too long (`EMSGSIZE`)
+    MsgSize,
+    /// Protocol not supported (`EPROTONOSUPPORT`)
+    ProtoNoSupport,
+    /// Operation not supported (`ENOTSUP`)
+    NotSup,
+    /// Operation not supported on socket (`EOPNOTSUPP`)
+    ///
+    /// On some platforms, this has the same value as [Errno::NotSup]; in that case, the error is
+    /// always recognized as `NotSup`.
+    OpNotSupp,
+    /// Address family not supported (`EAFNOSUPPORT`)
+    AfNoSupport,
+    /// Address already in use (`EADDRINUSE`)
+    AddrInUse,
+    /// Address not available (`EADDRNOTAVAIL`)
+    AddrNotAvail,
+    /// Network is down (`ENETDOWN`)
+    NetDown,
+    /// Network unreachable (`ENETUNREACH`)
+    NetUnreach,
+    /// Connection aborted (`ECONNABORTED`)
+    ConnAborted,
+    /// Connection reset by peer (`ECONNRESET`)
+    ConnReset,
+    /// No buffer space available (`ENOBUFS`)
+    NoBufs,
+    /// Socket is connected (`EISCONN`)
+    IsConn,
+    /// Socket is not connected (`ENOTCONN`)
+    NotConn,
+    /// Timed out (`ETIMEDOUT`)
+    TimedOut,
+    /// Connection refused (`ECONNREFUSED`)
+    ConnRefused,
+    /// Host is unreachable (`EHOSTUNREACH`)
+    HostUnreach,
+    /// Operation already in progress (`EALREADY`)
+    Already,
+    /// Operation in progress (`EINPROGRESS`)
+    InProgress,
+    /// Value too large for defined data type (`EOVERFLOW`)
+    Overflow,
+    /// Operation canceled (`ECANCELED`)
+    Canceled,
+    /// Bad message (`EBADMSG`)
+    BadMsg,
+}
+
+macro_rules! errno_table {
+    ($($variant:ident = $constant:ident,)*) => {
+        impl Errno {
+            /// Find the errno corresponding to a positive [riot_sys] constant
+            ///
+            /// Returns None for numbers that are not known to riot-wrappers.
+            pub fn from_constant(number: u32) -> Option<Self> {
+                // Not a match as some constants may coincide (eg. ENOTSUP and EOPNOTSUPP on some
+                // platforms), which would make later branches unreachable.
+                $(
+                    if number == riot_sys::$constant {
+                        return Some(Errno::$variant);
+                    }
+                )*
+                None
+            }
+
+            /// The positive [riot_sys] constant of the errno
+            pub fn to_constant(self) -> u32 {
+                match self {
+                    $(Errno::$variant => riot_sys::$constant,)*
+                }
+            }
+
+            /// The name of the errno as known from C (eg. "ENOMEM")
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Errno::$variant => stringify!($constant),)*
+                }
+            }
+        }
+    }
+}
+
+errno_table! {
+    Perm = EPERM,
+    NoEnt = ENOENT,
+    Intr = EINTR,
+    Io = EIO,
+    NxIo = ENXIO,
+    BadF = EBADF,
+    Again = EAGAIN,
+    NoMem = ENOMEM,
+    Acces = EACCES,
+    Fault = EFAULT,
+    Busy = EBUSY,
+    Exist = EEXIST,
+    NoDev = ENODEV,
+    NotDir = ENOTDIR,
+    IsDir = EISDIR,
+    Inval = EINVAL,
+    NoSpc = ENOSPC,
+    RoFs = EROFS,
+    Dom = EDOM,
+    Range = ERANGE,
+    NoSys = ENOSYS,
+    MsgSize = EMSGSIZE,
+    ProtoNoSupport = EPROTONOSUPPORT,
+    NotSup = ENOTSUP,
+    OpNotSupp = EOPNOTSUPP,
+    AfNoSupport = EAFNOSUPPORT,
+    AddrInUse = EADDRINUSE,
+    AddrNotAvail = EADDRNOTAVAIL,
+    NetDown = ENETDOWN,
+    NetUnreach = ENETUNREACH,
+    ConnAborted = ECONNABORTED,
+    ConnReset = ECONNRESET,
+    NoBufs = ENOBUFS,
+    IsConn = EISCONN,
+    NotConn = ENOTCONN,
+    TimedOut = ETIMEDOUT,
+    ConnRefused = ECONNREFUSED,
+    HostUnreach = EHOSTUNREACH,
+    Already = EALREADY,
+    InProgress = EINPROGRESS,
+    Overflow = EOVERFLOW,
+    Canceled = ECANCELED,
+    BadMsg = EBADMSG,
+}
+
+impl core::fmt::Display for Errno {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(feature = "core_error")]
+impl core::error::Error for Errno {}
 
 impl<T> NegativeErrorExt for T
 where
@@ -85,3 +334,45 @@ where
         }
     }
 }
+
+#[cfg(feature = "with_embedded_storage")]
+impl embedded_storage::nor_flash::NorFlashError for NumericError {
+    fn kind(&self) -> embedded_storage::nor_flash::NorFlashErrorKind {
+        use embedded_storage::nor_flash::NorFlashErrorKind::*;
+        // These are the errors reported by mtd.h
+        match self.errno() {
+            Some(Errno::Dom) => NotAligned,
+            Some(Errno::Overflow) => OutOfBounds,
+            _ => Other,
+        }
+    }
+}
+
+#[cfg(feature = "with_embedded_io")]
+impl embedded_io::Error for NumericError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        use embedded_io::ErrorKind::*;
+        match self.errno() {
+            Some(Errno::NoEnt) => NotFound,
+            Some(Errno::Perm) | Some(Errno::Acces) | Some(Errno::RoFs) => PermissionDenied,
+            Some(Errno::ConnRefused) => ConnectionRefused,
+            Some(Errno::ConnReset) => ConnectionReset,
+            Some(Errno::ConnAborted) => ConnectionAborted,
+            Some(Errno::NotConn) => NotConnected,
+            Some(Errno::AddrInUse) => AddrInUse,
+            Some(Errno::AddrNotAvail) => AddrNotAvailable,
+            Some(Errno::Exist) => AlreadyExists,
+            Some(Errno::Inval) => InvalidInput,
+            Some(Errno::BadMsg) => InvalidData,
+            Some(Errno::TimedOut) => TimedOut,
+            Some(Errno::Intr) => Interrupted,
+            Some(Errno::NotSup)
+            | Some(Errno::OpNotSupp)
+            | Some(Errno::NoSys)
+            | Some(Errno::AfNoSupport)
+            | Some(Errno::ProtoNoSupport) => Unsupported,
+            Some(Errno::NoMem) | Some(Errno::NoBufs) => OutOfMemory,
+            _ => Other,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Providing hardware addresses to network interfaces from Rust
+//!
+//! RIOT's [EUI provider](https://doc.riot-os.org/group__sys__eui__provider.html) mechanism lets
+//! network interfaces obtain their EUI-48 or EUI-64 from a board specific source (eg. an EEPROM
+//! with a preprogrammed MAC address) instead of generating a locally administered one. Providers
+//! are configured at build time through `EUI48_PROVIDER_FUNC` / `EUI64_PROVIDER_FUNC` (and the
+//! accompanying `_TYPE` and `_INDEX` settings, see `eui_provider_params.h`).
+//!
+//! The [eui48_provider!] and [eui64_provider!] macros export a Rust function under a name that
+//! can be set there, so the address can be read by a driver written in Rust without any C glue:
+//!
+//! ```no_run
+//! # #![no_std]
+//! fn read_mac() -> Option<[u8; 6]> {
+//!     // eg. read the EUI-48 from an AT24MAC's read-only area
+//!     # None
+//! }
+//! riot_wrappers::eui48_provider!(rust_eui48, read_mac);
+//! ```
+//!
+//! with the application's Makefile containing `CFLAGS += -DEUI48_PROVIDER_FUNC=rust_eui48`.
+//!
+//! If the function returns None, the interface falls back to the next provider, and eventually to
+//! a generated address.
+
+/// Write an EUI-48 produced by `f` into the C output argument
+///
+/// This is an implementation detail of [eui48_provider!].
+#[doc(hidden)]
+pub unsafe fn provide_eui48(
+    addr: *mut riot_sys::eui48_t,
+    f: impl FnOnce() -> Option<[u8; 6]>,
+) -> core::ffi::c_int {
+    match f() {
+        Some(value) => {
+            // unsafe: eui48_t is a (union around a) byte array of that size, and the pointer is
+            // valid per the provider callback's contract
+            core::ptr::write(addr as *mut [u8; 6], value);
+            0
+        }
+        None => -(riot_sys::ENOENT as core::ffi::c_int),
+    }
+}
+
+/// Write an EUI-64 produced by `f` into the C output argument
+///
+/// This is an implementation detail of [eui64_provider!].
+#[doc(hidden)]
+pub unsafe fn provide_eui64(
+    addr: *mut riot_sys::eui64_t,
+    f: impl FnOnce() -> Option<[u8; 8]>,
+) -> core::ffi::c_int {
+    match f() {
+        Some(value) => {
+            // unsafe: eui64_t is a (union around a) byte array of that size, and the pointer is
+            // valid per the provider callback's contract
+            core::ptr::write(addr as *mut [u8; 8], value);
+            0
+        }
+        None => -(riot_sys::ENOENT as core::ffi::c_int),
+    }
+}
+
+/// Export a function `fn() -> Option<[u8; 6]>` as an EUI-48 provider callback named `$name`
+///
+/// The callback's `arg` (from the provider configuration) is not passed on; a Rust driver is
+/// expected to find its device on its own.
+#[macro_export]
+macro_rules! eui48_provider {
+    ($name:ident, $func:path) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(
+            _arg: *const core::ffi::c_void,
+            addr: *mut $crate::riot_sys::eui48_t,
+        ) -> core::ffi::c_int {
+            $crate::eui_provider::provide_eui48(addr, $func)
+        }
+    };
+}
+
+/// Export a function `fn() -> Option<[u8; 8]>` as an EUI-64 provider callback named `$name`
+///
+/// The callback's `arg` (from the provider configuration) is not passed on; a Rust driver is
+/// expected to find its device on its own.
+#[macro_export]
+macro_rules! eui64_provider {
+    ($name:ident, $func:path) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(
+            _arg: *const core::ffi::c_void,
+            addr: *mut $crate::riot_sys::eui64_t,
+        ) -> core::ffi::c_int {
+            $crate::eui_provider::provide_eui64(addr, $func)
+        }
+    };
+}
@@ -7,6 +7,17 @@ use riot_sys::{coap_optpos_t, coap_pkt_t, gcoap_listener_t};
 
 use riot_sys::coap_resource_t;
 
+#[cfg(riot_module_sock)]
+pub mod client;
+#[cfg(all(riot_module_gcoap_forward_proxy, marker_coap_request_ctx_t))]
+pub mod forward_proxy;
+#[cfg(all(
+    riot_module_saul,
+    riot_module_ztimer_msec,
+    feature = "with_coap_handler"
+))]
+pub mod observe;
+
 #[cfg(marker_coap_request_ctx_t)]
 type HandlerArg4 = riot_sys::coap_request_ctx_t;
 #[cfg(not(marker_coap_request_ctx_t))]
@@ -56,6 +67,19 @@ where
     scope.register(listener);
 }
 
+/// Start gcoap's server thread
+///
+/// This is usually done by auto_init. Listeners are matched in the order they were registered,
+/// with those registered by gcoap itself (eg. the forward proxy's) registered when gcoap starts.
+/// Applications whose listeners need to take precedence over those disable the `auto_init_gcoap`
+/// module, register their listeners, and then call this.
+#[doc(alias = "gcoap_init")]
+pub fn init() -> Result<crate::thread::KernelPID, crate::error::NumericError> {
+    // unsafe: OK per C API; returns an error if gcoap was already started
+    let pid = unsafe { riot_sys::gcoap_init() }.negative_to_error()?;
+    Ok(crate::thread::KernelPID(pid as _))
+}
+
 /// Lifetimed helper through which registrations can happen
 ///
 /// For explanations of the `'env`' and `'id` lifetimes, see
@@ -83,11 +107,122 @@ impl<'env, 'id> RegistrationScope<'env, 'id> {
         unsafe { gcoap_register_listener(listener.get_listener() as *mut _) };
     }
 
+    /// Register a [SingleHandlerListener] like [register](Self::register), and obtain a
+    /// [Notifier] through which observers of its resource can be notified of changes
+    ///
+    /// gcoap registers observers of the resource on its own when they send a GET request with
+    /// Observe option (and adds the Observe option to the response); this only provides the means
+    /// to send the later notifications.
+    pub fn register_observable<H>(
+        &mut self,
+        listener: &'env mut SingleHandlerListener<'env, H>,
+    ) -> Notifier<'env>
+    where
+        H: 'env + Handler,
+    {
+        // The listener is exclusively borrowed for 'env, so this stays valid (and in place).
+        let resource = &listener.resource as *const _;
+        self.register(listener);
+        Notifier {
+            resource,
+            _phantom: PhantomData,
+        }
+    }
+
     fn deregister_all(&mut self) {
         panic!("Registration callback returned, but Gcoap does not allow deregistration.");
     }
 }
 
+/// A handle through which notifications are sent to the observers of a resource
+///
+/// This is obtained through [RegistrationScope::register_observable].
+#[derive(Copy, Clone, Debug)]
+pub struct Notifier<'env> {
+    resource: *const coap_resource_t,
+    _phantom: PhantomData<&'env ()>,
+}
+
+// The resource is only ever read through the pointer, and gcoap serializes access to its observe
+// state internally.
+unsafe impl<'env> Send for Notifier<'env> {}
+unsafe impl<'env> Sync for Notifier<'env> {}
+
+/// Outcome of [Notifier::notify]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NotifyOutcome {
+    /// The notification was sent
+    Sent,
+    /// No observer is registered for the resource, so nothing was sent
+    NoObserver,
+}
+
+impl<'env> Notifier<'env> {
+    /// Send a notification with the given payload and optional Content-Format option to the
+    /// resource's observer
+    ///
+    /// The notification is built in `buf`, which needs to be large enough for the CoAP header,
+    /// the Observe and Content-Format options and the payload.
+    #[doc(alias = "gcoap_obs_init")]
+    #[doc(alias = "gcoap_obs_send")]
+    pub fn notify(
+        &self,
+        buf: &mut [u8],
+        content_format: Option<u16>,
+        payload: &[u8],
+    ) -> Result<NotifyOutcome, crate::error::NumericError> {
+        let mut pdu = MaybeUninit::uninit();
+        // unsafe: OK per C API; the pdu is initialized by this if it returns OK.
+        let init = unsafe {
+            riot_sys::gcoap_obs_init(
+                pdu.as_mut_ptr(),
+                buf.as_mut_ptr(),
+                buf.len() as _,
+                self.resource,
+            )
+        };
+        // GCOAP_OBS_INIT_UNUSED is negative, and thus the only one of them that is an i32
+        match init as i32 {
+            ok if ok == riot_sys::GCOAP_OBS_INIT_OK as i32 => (),
+            riot_sys::GCOAP_OBS_INIT_UNUSED => return Ok(NotifyOutcome::NoObserver),
+            _ => return Err(crate::error::Errno::NoBufs.into()),
+        }
+        // unsafe: Initialized by gcoap_obs_init
+        let mut pdu = unsafe { pdu.assume_init() };
+        if let Some(format) = content_format {
+            // unsafe: OK per C API
+            unsafe {
+                coap_opt_add_uint(
+                    &mut pdu,
+                    riot_sys::COAP_OPT_CONTENT_FORMAT as _,
+                    format as _,
+                )
+            }
+            .negative_to_error()?;
+        }
+        let flags = if payload.is_empty() {
+            riot_sys::COAP_OPT_FINISH_NONE
+        } else {
+            riot_sys::COAP_OPT_FINISH_PAYLOAD
+        };
+        // unsafe: OK per C API
+        let len = unsafe { riot_sys::coap_opt_finish(&mut pdu, flags as _) }.negative_to_error()?
+            as usize;
+        let end = len + payload.len();
+        if end > buf.len() {
+            return Err(crate::error::Errno::NoSpc.into());
+        }
+        buf[len..end].copy_from_slice(payload);
+        // unsafe: OK per C API; the message is copied.
+        let sent = unsafe { riot_sys::gcoap_obs_send(buf.as_ptr(), end as _, self.resource) };
+        match sent {
+            0 => Err(crate::error::Errno::NoBufs.into()),
+            _ => Ok(NotifyOutcome::Sent),
+        }
+    }
+}
+
 pub trait ListenerProvider {
     /// Provide an exclusive reference to the underlying gcoap listener. The function is marked
     /// unsafe as the returned value contains raw pointers that will later be dereferenced, and
@@ -186,11 +321,17 @@ where
         #[cfg(not(marker_coap_request_ctx_t))]
         let h = context as *mut H;
 
+        #[cfg(marker_coap_request_ctx_t)]
+        let remote = riot_sys::coap_request_ctx_get_remote_udp(context);
+        #[cfg(not(marker_coap_request_ctx_t))]
+        let remote = core::ptr::null();
+
         let h = &mut *h;
         let mut pb = PacketBuffer {
             pkt,
             buf,
             len: len.try_into().unwrap(),
+            remote,
         };
         H::handle(h, &mut pb).try_into().unwrap()
     }
@@ -364,6 +505,8 @@ pub struct PacketBuffer {
     pkt: *mut coap_pkt_t,
     buf: *mut u8,
     len: usize,
+    /// Endpoint the request came from, or null if the RIOT version does not tell
+    remote: *const riot_sys::sock_udp_ep_t,
 }
 
 impl PacketBuffer {
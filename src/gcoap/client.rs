@@ -0,0 +1,498 @@
+//! Sending CoAP requests through gcoap
+//!
+//! A [Request] is built in a caller provided buffer, and sent with [send]; the response (or the
+//! failure to get one) is reported to a [ResponseHandler] in gcoap's thread.
+//!
+//! Requests can be sent [Confirmable](Reliability::Confirmable), in which case gcoap retransmits
+//! them until they are acknowledged, or [NonConfirmable](Reliability::NonConfirmable), in which
+//! case they are sent once (which suits periodic telemetry, where a lost value is superseded by
+//! the next one anyway). The retransmission parameters themselves (`CONFIG_COAP_ACK_TIMEOUT_MS`,
+//! `CONFIG_COAP_MAX_RETRANSMIT`) are build time settings of gcoap that apply to all confirmable
+//! requests; their values are available in [crate::config].
+//!
+//! Where those values are not available (eg. with old versions of riot-sys), the progress
+//! estimates of [Tracked] use the defaults of RFC 7252 and gcoap.
+//!
+//! Requests whose progress needs to be watched (eg. because their results are only useful within
+//! a deadline) can be sent through a [Tracked] slot instead, which reports their state and allows
+//! cancelling them.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gcoap::client::*;
+//! # use riot_wrappers::gcoap::PacketBuffer;
+//! # let server: riot_wrappers::socket::UdpEp = unimplemented!();
+//! struct PrintCode;
+//! impl ResponseHandler for PrintCode {
+//!     fn response(&mut self, outcome: Outcome<'_>) {
+//!         if let Outcome::Response(response) = outcome {
+//!             riot_wrappers::println!("Response code {}", response.get_code_raw());
+//!         }
+//!     }
+//! }
+//! static mut HANDLER: PrintCode = PrintCode;
+//!
+//! let mut buf = [0; 128];
+//! let mut request = Request::new(
+//!     &mut buf,
+//!     riot_sys::COAP_METHOD_POST as _,
+//!     cstr::cstr!("/temperature"),
+//!     Reliability::NonConfirmable,
+//! )
+//! .unwrap();
+//! let len = request.finish(b"21.5").unwrap();
+//! // unsafe: HANDLER is not accessed anywhere else
+//! send(&buf[..len], &server, unsafe { &mut HANDLER }).unwrap();
+//! ```
+
+use core::ffi::CStr;
+use core::mem::MaybeUninit;
+
+use super::PacketBuffer;
+use crate::error::{Errno, NegativeErrorExt, NumericError};
+use crate::socket::UdpEp;
+
+/// Transmission parameters of gcoap, from the build configuration if available
+mod params {
+    #[cfg(riot_config_coap_ack_timeout_ms)]
+    pub(super) const ACK_TIMEOUT_MS: u32 = crate::config::COAP_ACK_TIMEOUT_MS;
+    #[cfg(not(riot_config_coap_ack_timeout_ms))]
+    pub(super) const ACK_TIMEOUT_MS: u32 = 2000;
+
+    #[cfg(riot_config_coap_max_retransmit)]
+    pub(super) const MAX_RETRANSMIT: u8 = crate::config::COAP_MAX_RETRANSMIT;
+    #[cfg(not(riot_config_coap_max_retransmit))]
+    pub(super) const MAX_RETRANSMIT: u8 = 4;
+
+    #[cfg(riot_config_coap_random_factor_1000)]
+    pub(super) const RANDOM_FACTOR_1000: u32 = crate::config::COAP_RANDOM_FACTOR_1000;
+    #[cfg(not(riot_config_coap_random_factor_1000))]
+    pub(super) const RANDOM_FACTOR_1000: u32 = 1500;
+
+    #[cfg(riot_config_gcoap_non_timeout_msec)]
+    pub(super) const NON_TIMEOUT_MSEC: u32 = crate::config::GCOAP_NON_TIMEOUT_MSEC;
+    #[cfg(not(riot_config_gcoap_non_timeout_msec))]
+    pub(super) const NON_TIMEOUT_MSEC: u32 = 5000;
+}
+
+/// Message type of a request
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Reliability {
+    /// The request is retransmitted until acknowledged (CON)
+    Confirmable,
+    /// The request is sent once (NON)
+    NonConfirmable,
+}
+
+impl Reliability {
+    fn to_c(self) -> u32 {
+        match self {
+            Reliability::Confirmable => riot_sys::COAP_TYPE_CON,
+            Reliability::NonConfirmable => riot_sys::COAP_TYPE_NON,
+        }
+    }
+}
+
+/// A request under construction
+pub struct Request<'b> {
+    pdu: riot_sys::coap_pkt_t,
+    buf: &'b mut [u8],
+}
+
+impl<'b> Request<'b> {
+    /// Start a request with the given method code (eg. `COAP_METHOD_GET`) and Uri-Path
+    ///
+    /// The message ID and token are set by gcoap.
+    #[doc(alias = "gcoap_req_init")]
+    pub fn new(
+        buf: &'b mut [u8],
+        method: u8,
+        path: &CStr,
+        reliability: Reliability,
+    ) -> Result<Self, NumericError> {
+        let mut pdu = MaybeUninit::uninit();
+        // unsafe: OK per C API; the pdu is initialized by this.
+        unsafe {
+            riot_sys::gcoap_req_init(
+                pdu.as_mut_ptr(),
+                buf.as_mut_ptr(),
+                buf.len() as _,
+                method as _,
+                path.as_ptr(),
+            )
+        }
+        .negative_to_error()?;
+        let mut request = Request {
+            // unsafe: Initialized by gcoap_req_init
+            pdu: unsafe { pdu.assume_init() },
+            buf,
+        };
+        request.set_reliability(reliability);
+        Ok(request)
+    }
+
+    /// Change whether the request is sent confirmable or non-confirmable
+    #[doc(alias = "coap_hdr_set_type")]
+    pub fn set_reliability(&mut self, reliability: Reliability) {
+        // unsafe: OK per C API; the header was initialized in new().
+        unsafe { riot_sys::coap_hdr_set_type(self.pdu.hdr, reliability.to_c() as _) }
+    }
+
+    /// Add an integer value as an option
+    ///
+    /// Options need to be added in ascending order of their numbers.
+    #[doc(alias = "coap_opt_add_uint")]
+    pub fn opt_add_uint(&mut self, optnum: u16, value: u32) -> Result<(), NumericError> {
+        // unsafe: OK per C API
+        unsafe { riot_sys::coap_opt_add_uint(&mut self.pdu, optnum, value) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    /// Add an opaque value as an option
+    ///
+    /// Options need to be added in ascending order of their numbers.
+    #[doc(alias = "coap_opt_add_opaque")]
+    pub fn opt_add_opaque(&mut self, optnum: u16, data: &[u8]) -> Result<(), NumericError> {
+        // unsafe: OK per C API; the data is copied.
+        unsafe {
+            riot_sys::coap_opt_add_opaque(
+                &mut self.pdu,
+                optnum,
+                data.as_ptr() as *const _,
+                data.len() as _,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    /// Finish the options, append the payload, and return the length of the complete message
+    ///
+    /// The first that many bytes of the buffer are then what is passed to [send].
+    #[doc(alias = "coap_opt_finish")]
+    pub fn finish(&mut self, payload: &[u8]) -> Result<usize, NumericError> {
+        let flags = if payload.is_empty() {
+            riot_sys::COAP_OPT_FINISH_NONE
+        } else {
+            riot_sys::COAP_OPT_FINISH_PAYLOAD
+        };
+        // unsafe: OK per C API
+        let len = unsafe { riot_sys::coap_opt_finish(&mut self.pdu, flags as _) }
+            .negative_to_error()? as usize;
+        let end = len + payload.len();
+        if end > self.buf.len() {
+            return Err(Errno::NoSpc.into());
+        }
+        self.buf[len..end].copy_from_slice(payload);
+        Ok(end)
+    }
+}
+
+/// Result of a request, as reported to a [ResponseHandler]
+pub enum Outcome<'a> {
+    /// A response was received
+    Response(&'a PacketBuffer),
+    /// No response arrived in time (for confirmable requests: after all retransmissions)
+    Timeout,
+    /// The request failed otherwise, or the response could not be processed
+    Error,
+}
+
+/// Recipient of the [Outcome] of a request sent with [send]
+///
+/// The handler is called exactly once per request, in gcoap's thread.
+pub trait ResponseHandler {
+    fn response(&mut self, outcome: Outcome<'_>);
+}
+
+/// Run the closure on the outcome described by the arguments of a gcoap response handler
+unsafe fn with_outcome(
+    memo: *const riot_sys::gcoap_request_memo_t,
+    pdu: *mut riot_sys::coap_pkt_t,
+    remote: *const riot_sys::sock_udp_ep_t,
+    f: impl FnOnce(Outcome<'_>),
+) {
+    match (*memo).state as u32 {
+        riot_sys::GCOAP_MEMO_RESP => {
+            let buf = (*pdu).hdr as *mut u8;
+            let header_len = (*pdu).payload.offset_from(buf) as usize;
+            let response = PacketBuffer {
+                pkt: pdu,
+                buf,
+                len: header_len + (*pdu).payload_len as usize,
+                remote,
+            };
+            f(Outcome::Response(&response));
+        }
+        riot_sys::GCOAP_MEMO_TIMEOUT => f(Outcome::Timeout),
+        _ => f(Outcome::Error),
+    }
+}
+
+unsafe extern "C" fn resp_handler<H: ResponseHandler>(
+    memo: *const riot_sys::gcoap_request_memo_t,
+    pdu: *mut riot_sys::coap_pkt_t,
+    remote: *const riot_sys::sock_udp_ep_t,
+) {
+    let handler = &mut *((*memo).context as *mut H);
+    with_outcome(memo, pdu, remote, |outcome| handler.response(outcome));
+}
+
+/// Send a request built with [Request] to the given server
+///
+/// The handler is moved into gcoap until the request has completed; it is then called from
+/// gcoap's thread.
+#[doc(alias = "gcoap_req_send")]
+pub fn send<H: ResponseHandler + Send>(
+    message: &[u8],
+    remote: &UdpEp,
+    handler: &'static mut H,
+) -> Result<(), NumericError> {
+    // unsafe: OK per C API; the message is copied into gcoap's memo (and for confirmable requests
+    // its retransmission buffer), and the handler is valid for as long as gcoap may call it.
+    let sent = unsafe {
+        riot_sys::gcoap_req_send(
+            message.as_ptr(),
+            message.len() as _,
+            &remote.0,
+            core::ptr::null(),
+            Some(resp_handler::<H>),
+            handler as *mut H as *mut _,
+            riot_sys::gcoap_socket_type_t_GCOAP_SOCKET_TYPE_UNDEF,
+        )
+    };
+    match sent.negative_to_error()? {
+        // gcoap reports most failures to send (eg. no free memo) as 0
+        0 => Err(Errno::NoBufs.into()),
+        _ => Ok(()),
+    }
+}
+
+/// State of a [Tracked] request
+#[cfg(riot_module_ztimer_msec)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Status {
+    /// No request was sent yet (or the last one could not be sent)
+    Idle,
+    /// The request is waiting for a response
+    Pending {
+        /// Time since the request was sent, in milliseconds
+        elapsed_ms: u32,
+        /// Number of retransmissions gcoap may have sent by now
+        ///
+        /// As gcoap randomizes its timeouts, this is an upper bound; it is always 0 for
+        /// non-confirmable requests.
+        retransmissions: u8,
+        /// Time after which gcoap gives up on the request at the latest, in milliseconds
+        remaining_ms: u32,
+    },
+    /// The request was cancelled, but gcoap has not released it yet
+    Cancelled,
+    /// The request has completed, and its outcome was passed to the handler
+    Done {
+        /// Number of retransmissions that were sent
+        retransmissions: u8,
+    },
+}
+
+#[cfg(riot_module_ztimer_msec)]
+enum Phase {
+    Idle,
+    Pending {
+        sent: crate::ztimer::Ticks<1000>,
+        confirmable: bool,
+    },
+    Cancelled {
+        confirmable: bool,
+    },
+    Done {
+        retransmissions: u8,
+    },
+}
+
+#[cfg(riot_module_ztimer_msec)]
+struct TrackedState<H> {
+    handler: H,
+    phase: Phase,
+}
+
+/// A [ResponseHandler] in a slot that allows inspecting and cancelling its request
+///
+/// Unlike with [send], the handler stays accessible while the request is in flight: Its
+/// [status](Self::status) tells how long the request has been waiting, how many retransmissions
+/// were sent and how long gcoap will wait at most, and the request can be
+/// [cancelled](Self::cancel) when its result would come too late to be useful.
+///
+/// The handler is called in gcoap's thread while the slot is locked, so it must not call any of
+/// the slot's methods.
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::gcoap::client::*;
+/// # struct PrintCode;
+/// # impl ResponseHandler for PrintCode { fn response(&mut self, _: Outcome<'_>) {} }
+/// # let server: riot_wrappers::socket::UdpEp = unimplemented!();
+/// # let message: &[u8] = unimplemented!();
+/// static SLOT: Tracked<PrintCode> = Tracked::new(PrintCode);
+///
+/// SLOT.send(message, &server).unwrap();
+/// // ...
+/// if let Status::Pending { elapsed_ms, .. } = SLOT.status() {
+///     if elapsed_ms > 500 {
+///         SLOT.cancel();
+///     }
+/// }
+/// ```
+#[cfg(riot_module_ztimer_msec)]
+pub struct Tracked<H> {
+    state: crate::mutex::Mutex<TrackedState<H>>,
+}
+
+#[cfg(riot_module_ztimer_msec)]
+unsafe extern "C" fn tracked_resp_handler<H: ResponseHandler>(
+    memo: *const riot_sys::gcoap_request_memo_t,
+    pdu: *mut riot_sys::coap_pkt_t,
+    remote: *const riot_sys::sock_udp_ep_t,
+) {
+    let tracked = &*((*memo).context as *const Tracked<H>);
+    let mut state = tracked.state.lock();
+    let (confirmable, cancelled) = match state.phase {
+        Phase::Pending { confirmable, .. } => (confirmable, false),
+        Phase::Cancelled { confirmable } => (confirmable, true),
+        // Can't happen: The slot is not resent while gcoap holds it
+        _ => return,
+    };
+    // gcoap counts the remaining transmissions down from the configured maximum; for
+    // non-confirmable requests, this is a negative marker value.
+    let retransmissions = if confirmable {
+        let remaining = (*memo).send_limit.max(0) as u8;
+        params::MAX_RETRANSMIT.saturating_sub(remaining)
+    } else {
+        0
+    };
+    state.phase = Phase::Done { retransmissions };
+    if !cancelled {
+        let handler = &mut state.handler;
+        with_outcome(memo, pdu, remote, |outcome| handler.response(outcome));
+    }
+}
+
+#[cfg(riot_module_ztimer_msec)]
+impl<H: ResponseHandler + Send> Tracked<H> {
+    pub const fn new(handler: H) -> Self {
+        Tracked {
+            state: crate::mutex::Mutex::new(TrackedState {
+                handler,
+                phase: Phase::Idle,
+            }),
+        }
+    }
+
+    /// Send a request built with [Request] to the given server
+    ///
+    /// This fails with `EBUSY` if a previous request of this slot is still pending or cancelled
+    /// (for gcoap still holds a reference to the slot then).
+    #[doc(alias = "gcoap_req_send")]
+    pub fn send(&'static self, message: &[u8], remote: &UdpEp) -> Result<(), NumericError> {
+        let mut state = self.state.lock();
+        if matches!(state.phase, Phase::Pending { .. } | Phase::Cancelled { .. }) {
+            return Err(Errno::Busy.into());
+        }
+        // Type is in bits 4 and 5 of the first byte of the header
+        let confirmable = message
+            .first()
+            .map(|b| (b >> 4) & 0x3 == riot_sys::COAP_TYPE_CON as u8)
+            .unwrap_or(false);
+        state.phase = Phase::Pending {
+            sent: crate::ztimer::Clock::msec().now(),
+            confirmable,
+        };
+        // The response handler can only run once the state is unlocked at the end of this
+        // function.
+        // unsafe: OK per C API; the message is copied, and the slot is static.
+        let sent = unsafe {
+            riot_sys::gcoap_req_send(
+                message.as_ptr(),
+                message.len() as _,
+                &remote.0,
+                core::ptr::null(),
+                Some(tracked_resp_handler::<H>),
+                self as *const Self as *mut _,
+                riot_sys::gcoap_socket_type_t_GCOAP_SOCKET_TYPE_UNDEF,
+            )
+        };
+        let result = match sent.negative_to_error() {
+            Ok(0) => Err(Errno::NoBufs.into()),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            state.phase = Phase::Idle;
+        }
+        result
+    }
+
+    /// Report the state of the slot's latest request
+    pub fn status(&self) -> Status {
+        let state = self.state.lock();
+        match state.phase {
+            Phase::Idle => Status::Idle,
+            Phase::Cancelled { .. } => Status::Cancelled,
+            Phase::Done { retransmissions } => Status::Done { retransmissions },
+            Phase::Pending { sent, confirmable } => {
+                let now = crate::ztimer::Clock::msec().now();
+                let elapsed = now.0.wrapping_sub(sent.0);
+                let (retransmissions, lifetime) = if confirmable {
+                    let ack_timeout = params::ACK_TIMEOUT_MS as u64;
+                    let max_retransmit = params::MAX_RETRANSMIT;
+                    // Retransmission k happens no earlier than ack_timeout * (2^k - 1)
+                    let retransmissions = (1..=max_retransmit)
+                        .take_while(|k| ack_timeout * ((1 << k) - 1) <= elapsed as u64)
+                        .count() as u8;
+                    // Waiting ends after the last retransmission's timeout, with all timeouts
+                    // randomized up to the random factor
+                    let lifetime = ack_timeout
+                        * params::RANDOM_FACTOR_1000 as u64
+                        * ((1 << (max_retransmit + 1)) - 1)
+                        / 1000;
+                    (retransmissions, lifetime)
+                } else {
+                    (0, params::NON_TIMEOUT_MSEC as u64)
+                };
+                Status::Pending {
+                    elapsed_ms: elapsed,
+                    retransmissions,
+                    remaining_ms: lifetime
+                        .saturating_sub(elapsed as u64)
+                        .try_into()
+                        .unwrap_or(u32::MAX),
+                }
+            }
+        }
+    }
+
+    /// Stop waiting for the pending request
+    ///
+    /// The handler will not be called for the request. Returns false if no request was pending.
+    ///
+    /// gcoap provides no way to abort a request, so it keeps retransmitting it and occupies a
+    /// request memo (see `CONFIG_GCOAP_REQ_WAITING_MAX`) until the request's lifetime ends; until
+    /// then, the slot can not be used to send a new request.
+    pub fn cancel(&self) -> bool {
+        let mut state = self.state.lock();
+        match state.phase {
+            Phase::Pending { confirmable, .. } => {
+                state.phase = Phase::Cancelled { confirmable };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Run a closure on the handler, eg. to read out the last response's data
+    pub fn with_handler<R>(&self, f: impl FnOnce(&mut H) -> R) -> R {
+        self.state.lock_and(|state| f(&mut state.handler))
+    }
+}
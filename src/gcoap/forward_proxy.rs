@@ -0,0 +1,167 @@
+//! A CoAP forward proxy with its policy implemented in Rust
+//!
+//! RIOT's `gcoap_forward_proxy` module forwards any request that carries a Proxy-Uri option to
+//! the server named there, and relays the response back to the client. This module provides a
+//! [ForwardProxy] handler that performs the same forwarding, but first asks a [Policy] whether
+//! the request should be forwarded at all.
+//!
+//! As the C module registers its own (policy-less) listener when gcoap starts, and gcoap uses the
+//! first matching listener, the Rust listener needs to be registered before that happens: Disable
+//! the `auto_init_gcoap` module, register a listener around a [ForwardProxy] created with
+//! [SingleHandlerListener::new_forward_proxy], and then start gcoap using [super::init()]. That
+//! listener only claims requests that carry a Proxy-Uri option, so all other requests (including
+//! any using Proxy-Scheme, which the C module does not support) still reach the node's own
+//! resources.
+//!
+//! Responses are cached if the `nanocoap_cache` module is enabled; that is handled on the C side.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gcoap::{self, SingleHandlerListener, forward_proxy::{AllowedHosts, ForwardProxy}};
+//! static mut PROXY: ForwardProxy<AllowedHosts> =
+//!     ForwardProxy::new(AllowedHosts(&["sensor.example.com", "[2001:db8::1]"]));
+//! static mut LISTENER: Option<SingleHandlerListener<'static, ForwardProxy<AllowedHosts>>> = None;
+//! // unsafe: Only run once in main, before anything else could access the statics
+//! let listener = unsafe { LISTENER.insert(SingleHandlerListener::new_forward_proxy(&mut PROXY)) };
+//! gcoap::register(listener);
+//! gcoap::init().unwrap();
+//! ```
+
+use super::{Handler, PacketBuffer, SingleHandlerListener};
+
+/// CoAP option number of Proxy-Uri
+const PROXY_URI: u16 = 35;
+
+// Response codes as used by the C module's own handler
+const BAD_REQUEST: u8 = 4 << 5;
+const BAD_OPTION: u8 = (4 << 5) | 2;
+const FORBIDDEN: u8 = (4 << 5) | 3;
+const INTERNAL_SERVER_ERROR: u8 = 5 << 5;
+const PROXYING_NOT_SUPPORTED: u8 = (5 << 5) | 5;
+
+/// Decision maker for which requests a [ForwardProxy] forwards
+pub trait Policy {
+    /// Decide whether a request with the given Proxy-Uri is forwarded
+    ///
+    /// Requests that are not allowed are answered with 4.03 Forbidden.
+    fn allow(&mut self, proxy_uri: &str) -> bool;
+}
+
+/// A [Policy] that forwards requests only to hosts on a list
+///
+/// Hosts are compared literally to the host part of the Proxy-Uri, which is case sensitive and
+/// includes the brackets of IPv6 addresses.
+pub struct AllowedHosts(pub &'static [&'static str]);
+
+impl Policy for AllowedHosts {
+    fn allow(&mut self, proxy_uri: &str) -> bool {
+        match uri_host(proxy_uri) {
+            Some(host) => self.0.iter().any(|allowed| *allowed == host),
+            None => false,
+        }
+    }
+}
+
+/// Extract the host part (without port) from an absolute URI
+fn uri_host(uri: &str) -> Option<&str> {
+    let (_scheme, rest) = uri.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    // Strip any userinfo
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, a)| a);
+    if authority.starts_with('[') {
+        let end = authority.find(']')?;
+        Some(&authority[..=end])
+    } else {
+        authority.split(':').next()
+    }
+}
+
+/// A gcoap [Handler] that forwards requests carrying a Proxy-Uri as allowed by its policy
+///
+/// Requests without a Proxy-Uri (which the [listener](SingleHandlerListener::new_forward_proxy)
+/// does not pass in) are answered with 5.05 Proxying Not Supported, as is any request the C module
+/// can not forward (eg. because of an unsupported scheme). Proxy-Uri options the C module can not
+/// parse are answered with 4.02 Bad Option.
+pub struct ForwardProxy<P: Policy> {
+    policy: P,
+}
+
+impl<P: Policy> ForwardProxy<P> {
+    pub const fn new(policy: P) -> Self {
+        ForwardProxy { policy }
+    }
+
+    /// Access the policy, eg. to change the set of allowed hosts at runtime
+    pub fn policy(&mut self) -> &mut P {
+        &mut self.policy
+    }
+
+    fn respond(pkt: &mut PacketBuffer, code: u8) -> isize {
+        match pkt.resp_init(code) {
+            Ok(()) => pkt.get_length(0) as isize,
+            Err(e) => e.number,
+        }
+    }
+}
+
+impl<P: Policy> Handler for ForwardProxy<P> {
+    #[doc(alias = "gcoap_forward_proxy_request_process")]
+    fn handle(&mut self, pkt: &mut PacketBuffer) -> isize {
+        let uri = pkt
+            .opt_iter()
+            .find(|(number, _)| *number == PROXY_URI)
+            .map(|(_, value)| core::str::from_utf8(value));
+        let allowed = match uri {
+            None => return Self::respond(pkt, PROXYING_NOT_SUPPORTED),
+            Some(Err(_)) => return Self::respond(pkt, BAD_REQUEST),
+            Some(Ok(uri)) => self.policy.allow(uri),
+        };
+        if !allowed {
+            return Self::respond(pkt, FORBIDDEN);
+        }
+
+        if pkt.remote.is_null() {
+            return Self::respond(pkt, INTERNAL_SERVER_ERROR);
+        }
+        // unsafe: OK per C API; the remote pointer was provided by gcoap for this request.
+        let result = unsafe { riot_sys::gcoap_forward_proxy_request_process(pkt.pkt, pkt.remote) };
+        match result {
+            // Forwarded; the response is sent when the origin server's response arrives
+            0 => 0,
+            e if e == -(riot_sys::ENOMEM as i32) => Self::respond(pkt, INTERNAL_SERVER_ERROR),
+            e if e == -(riot_sys::EINVAL as i32) => Self::respond(pkt, BAD_OPTION),
+            e if e == -(riot_sys::EPERM as i32) => Self::respond(pkt, PROXYING_NOT_SUPPORTED),
+            e => e as _,
+        }
+    }
+}
+
+impl<'a, P: Policy> SingleHandlerListener<'a, ForwardProxy<P>> {
+    /// Create a listener that passes all proxy requests to the given forward proxy
+    ///
+    /// Unlike a [catch-all](SingleHandlerListener::new_catch_all) listener, this only matches
+    /// requests that carry a Proxy-Uri option, regardless of their path, and leaves all others to
+    /// the listeners registered after it.
+    pub fn new_forward_proxy(handler: &'a mut ForwardProxy<P>) -> Self {
+        let mut listener = Self::new_catch_all(handler);
+        listener.listener.request_matcher = Some(match_proxy_request);
+        listener
+    }
+}
+
+/// gcoap request matcher that claims any request with a Proxy-Uri for the listener's resource
+unsafe extern "C" fn match_proxy_request(
+    listener: *mut riot_sys::gcoap_listener_t,
+    resource: *mut *const riot_sys::coap_resource_t,
+    pdu: *mut riot_sys::coap_pkt_t,
+) -> i32 {
+    // unsafe: OK per C API; the packet is valid for the duration of the call.
+    let is_proxied = !unsafe { riot_sys::coap_find_option(pdu, PROXY_URI as _) }.is_null();
+    if !is_proxied {
+        return riot_sys::GCOAP_RESOURCE_NO_PATH as _;
+    }
+    // unsafe: The listener was set up by SingleHandlerListener::get_listener, and points to its
+    // single resource.
+    *resource = unsafe { (*listener).resources };
+    riot_sys::GCOAP_RESOURCE_FOUND as _
+}
@@ -0,0 +1,206 @@
+//! Observable CoAP resources that publish sensor values
+//!
+//! A [SensorResource] periodically samples a [Source] (typically a SAUL
+//! [RegistryEntry](crate::saul::RegistryEntry), or a closure), serves the latest value on GET,
+//! and notifies observers when the value changed by more than a threshold. This ties together
+//! what many sensor nodes do with SAUL, ztimer and gcoap:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gcoap::{self, SingleHandlerListener};
+//! # use riot_wrappers::coap_handler::GcoapHandler;
+//! # use riot_wrappers::saul::RegistryEntry;
+//! use riot_wrappers::gcoap::observe::SensorResource;
+//! use riot_wrappers::ztimer::Ticks;
+//!
+//! let sensor = RegistryEntry::nth(0).unwrap();
+//! // Notify when the value changes by more than 5 (at the sensor's scale, eg. 0.05°C)
+//! let resource = SensorResource::new(sensor, 5);
+//! let mut handler = GcoapHandler(&resource);
+//! let mut listener =
+//!     SingleHandlerListener::new(cstr::cstr!("/temp"), riot_sys::COAP_GET, &mut handler);
+//! gcoap::scope(|greg| {
+//!     let notifier = greg.register_observable(&mut listener);
+//!     resource.run(&notifier, Ticks(10_000));
+//! });
+//! ```
+//!
+//! Values are represented as text (in the format of [Phydat]'s Display implementation).
+
+use core::fmt::Write;
+
+use coap_message::{MutableWritableMessage, ReadableMessage};
+use coap_numbers::code;
+
+use super::{Notifier, NotifyOutcome};
+use crate::mutex::Mutex;
+use crate::saul::{Phydat, RegistryEntry};
+use crate::ztimer::{Clock, Ticks};
+
+/// Content-Format of text/plain;charset=utf-8
+const TEXT_PLAIN: u16 = 0;
+
+/// Longest textual representation of a value that is served
+type Text = heapless::String<64>;
+
+/// Something that produces sensor values for a [SensorResource]
+pub trait Source: Send {
+    /// Take a sample, or return None if no value is available right now
+    fn sample(&mut self) -> Option<Phydat>;
+}
+
+impl Source for RegistryEntry {
+    fn sample(&mut self) -> Option<Phydat> {
+        self.read().ok()
+    }
+}
+
+impl<F: FnMut() -> Option<Phydat> + Send> Source for F {
+    fn sample(&mut self) -> Option<Phydat> {
+        self()
+    }
+}
+
+struct State<S> {
+    source: S,
+    current: Option<Phydat>,
+    notified: Option<Phydat>,
+}
+
+/// A CoAP resource that serves (and notifies observers about) the values of a [Source]
+///
+/// This is used as a [coap_handler::Handler] through a shared reference, while a thread drives
+/// the sampling through [run](Self::run) or [poll](Self::poll).
+pub struct SensorResource<S: Source> {
+    state: Mutex<State<S>>,
+    threshold: u16,
+}
+
+/// Whether a change from `old` to `new` is large enough to notify about
+fn significant(old: &Phydat, new: &Phydat, threshold: u16) -> bool {
+    if old.unit() != new.unit() || old.scale() != new.scale() {
+        return true;
+    }
+    let (old, new) = (old.value(), new.value());
+    old.len() != new.len()
+        || old
+            .iter()
+            .zip(new.iter())
+            .any(|(o, n)| (*o as i32 - *n as i32).unsigned_abs() > threshold as u32)
+}
+
+fn render(value: &Phydat) -> Text {
+    let mut text = Text::new();
+    // If it does not fit, a truncated value is better than none
+    let _ = write!(text, "{}", value);
+    text
+}
+
+impl<S: Source> SensorResource<S> {
+    /// Create a resource for the source
+    ///
+    /// Observers are notified when any dimension of the value changed by more than `threshold`
+    /// (in units of the value at its scale), or when the value's unit or scale changed.
+    pub const fn new(source: S, threshold: u16) -> Self {
+        SensorResource {
+            state: Mutex::new(State {
+                source,
+                current: None,
+                notified: None,
+            }),
+            threshold,
+        }
+    }
+
+    /// Sample the source once, and notify the observers if the value changed significantly
+    ///
+    /// Returns whether a notification was sent.
+    pub fn poll(&self, notifier: &Notifier<'_>) -> bool {
+        let mut state = self.state.lock();
+        let value = match state.source.sample() {
+            Some(value) => value,
+            None => return false,
+        };
+        state.current = Some(value);
+        let due = match &state.notified {
+            None => true,
+            Some(notified) => significant(notified, &value, self.threshold),
+        };
+        drop(state);
+        if !due {
+            return false;
+        }
+
+        let text = render(&value);
+        let mut buf = [0; 96];
+        match notifier.notify(&mut buf, Some(TEXT_PLAIN), text.as_bytes()) {
+            Ok(outcome) => {
+                // Without observers, the value is still remembered as notified: New observers get
+                // the current value in their registration response anyway.
+                self.state.lock().notified = Some(value);
+                outcome == NotifyOutcome::Sent
+            }
+            // Retried at the next poll
+            Err(_) => false,
+        }
+    }
+
+    /// Sample the source at the given interval forever, notifying observers of changes
+    pub fn run(&self, notifier: &Notifier<'_>, interval: Ticks<1000>) -> ! {
+        let clock = Clock::msec();
+        loop {
+            self.poll(notifier);
+            clock.sleep_ticks(interval.0);
+        }
+    }
+}
+
+impl<'a, S: Source> coap_handler::Handler for &'a SensorResource<S> {
+    type RequestData = Result<Text, u8>;
+
+    fn extract_request_data(&mut self, request: &impl ReadableMessage) -> Self::RequestData {
+        let method: u8 = request.code().into();
+        if method != code::GET {
+            return Err(code::METHOD_NOT_ALLOWED);
+        }
+        let mut state = self.state.lock();
+        if state.current.is_none() {
+            state.current = state.source.sample();
+        }
+        state
+            .current
+            .as_ref()
+            .map(render)
+            .ok_or(code::SERVICE_UNAVAILABLE)
+    }
+
+    fn estimate_length(&mut self, _request: &Self::RequestData) -> usize {
+        Text::new().capacity() + 8
+    }
+
+    fn build_response(
+        &mut self,
+        response: &mut impl MutableWritableMessage,
+        request: Self::RequestData,
+    ) {
+        use core::convert::TryInto;
+        match request {
+            Ok(text) => {
+                response.set_code(code::CONTENT.try_into().map_err(|_| ()).unwrap());
+                // TEXT_PLAIN is 0, which is encoded as an empty option value
+                response.add_option(
+                    coap_numbers::option::CONTENT_FORMAT
+                        .try_into()
+                        .map_err(|_| ())
+                        .unwrap(),
+                    &[],
+                );
+                response.set_payload(text.as_bytes());
+            }
+            Err(code) => {
+                response.set_code(code.try_into().map_err(|_| ()).unwrap());
+                response.set_payload(b"");
+            }
+        }
+    }
+}
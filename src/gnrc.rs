@@ -1,3 +1,5 @@
+#[cfg(riot_module_gnrc_netif_bus)]
+pub mod bus;
 #[cfg(riot_module_gnrc_icmpv6)]
 pub mod icmpv6;
 #[cfg(riot_module_ipv6)]
@@ -6,9 +8,15 @@ pub mod ipv6;
 pub mod netapi;
 pub mod netreg;
 pub mod pktbuf;
+pub mod pktdump;
+#[cfg(riot_module_gnrc_netif_pktq)]
+pub mod pktq;
+#[cfg(riot_module_gnrc_sixlowpan_frag_stats)]
+pub mod sixlowpan_frag;
 
 use riot_sys::{gnrc_netif_iter, gnrc_netif_t};
 
+use crate::error::{Errno, NegativeErrorExt, NumericError};
 use crate::thread::KernelPID;
 use core::iter::Iterator;
 
@@ -63,4 +71,203 @@ impl Netif {
     pub fn l2addr(&self) -> &[u8] {
         unsafe { &(*self.0).l2addr[..(*self.0).l2addr_len as usize] }
     }
+
+    /// Set a network option through the interface's netapi
+    ///
+    /// Like all netapi operations, this must not be called from the interface's own thread.
+    fn netapi_set<T>(&self, opt: riot_sys::netopt_t, value: &T) -> Result<(), NumericError> {
+        // unsafe: OK per C API; the data is only read for the duration of the call.
+        unsafe {
+            riot_sys::_gnrc_netapi_get_set(
+                self.pid().into(),
+                opt,
+                0,
+                value as *const T as *mut _,
+                core::mem::size_of::<T>() as _,
+                riot_sys::GNRC_NETAPI_MSG_TYPE_SET as _,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    /// Query a network option through the interface's netapi
+    fn netapi_get<T: Default>(&self, opt: riot_sys::netopt_t) -> Result<T, NumericError> {
+        let mut value = T::default();
+        self.netapi_get_into(opt, 0, &mut value)?;
+        Ok(value)
+    }
+
+    /// Query a network option with a context value through the interface's netapi
+    ///
+    /// The option's type needs to be plain data that can be written to by the C side.
+    fn netapi_get_into<T>(
+        &self,
+        opt: riot_sys::netopt_t,
+        context: u16,
+        value: &mut T,
+    ) -> Result<(), NumericError> {
+        // unsafe: OK per C API; the option types used here are plain integers or pointers.
+        unsafe {
+            riot_sys::_gnrc_netapi_get_set(
+                self.pid().into(),
+                opt,
+                context,
+                value as *mut T as *mut _,
+                core::mem::size_of::<T>() as _,
+                riot_sys::GNRC_NETAPI_MSG_TYPE_GET as _,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    /// Read the interface's packet statistics at the given layer
+    ///
+    /// The counters are maintained by the network stack, and are read without synchronization;
+    /// values of different counters may thus be off by a packet that is being processed.
+    #[cfg(any(riot_module_netstats_l2, riot_module_netstats_ipv6))]
+    #[doc(alias = "NETOPT_STATS")]
+    pub fn stats(&self, layer: NetstatsLayer) -> Result<Netstats, NumericError> {
+        let mut stats: *const riot_sys::netstats_t = core::ptr::null();
+        self.netapi_get_into(riot_sys::netopt_t_NETOPT_STATS, layer as u16, &mut stats)?;
+        if stats.is_null() {
+            return Err(Errno::NotSup.into());
+        }
+        // unsafe: The statistics live in the netif (or its device) for as long as the netif,
+        // which is registered. Reading them volatile as they are updated in the netif's thread.
+        let stats = unsafe { core::ptr::read_volatile(stats) };
+        Ok(Netstats {
+            tx_unicast_count: stats.tx_unicast_count,
+            tx_mcast_count: stats.tx_mcast_count,
+            tx_success: stats.tx_success,
+            tx_failed: stats.tx_failed,
+            tx_bytes: stats.tx_bytes,
+            rx_count: stats.rx_count,
+            rx_bytes: stats.rx_bytes,
+        })
+    }
+
+    /// Administratively change the interface's state
+    ///
+    /// This is what `ifconfig <if> up` / `down` do in the shell: [Up](State::Up) and
+    /// [Down](State::Down) enable or disable the link (`NETOPT_LINK`), [Sleep](State::Sleep) puts
+    /// the device into its low-power state (`NETOPT_STATE`).
+    #[doc(alias = "gnrc_netapi_set")]
+    pub fn set_state(&self, state: State) -> Result<(), NumericError> {
+        match state {
+            State::Up => self.netapi_set(
+                riot_sys::netopt_t_NETOPT_LINK,
+                &riot_sys::netopt_enable_t_NETOPT_ENABLE,
+            ),
+            State::Down => self.netapi_set(
+                riot_sys::netopt_t_NETOPT_LINK,
+                &riot_sys::netopt_enable_t_NETOPT_DISABLE,
+            ),
+            State::Sleep => self.netapi_set(
+                riot_sys::netopt_t_NETOPT_STATE,
+                &riot_sys::netopt_state_t_NETOPT_STATE_SLEEP,
+            ),
+        }
+    }
+
+    /// Whether the interface's link is currently up
+    ///
+    /// This errs if the device does not report its link state.
+    ///
+    /// GNRC does not announce link changes; the events it does announce are available through
+    /// the interface's [message buses](bus).
+    #[doc(alias = "gnrc_netapi_get")]
+    pub fn is_link_up(&self) -> Result<bool, NumericError> {
+        let link: riot_sys::netopt_enable_t = self.netapi_get(riot_sys::netopt_t_NETOPT_LINK)?;
+        Ok(link == riot_sys::netopt_enable_t_NETOPT_ENABLE)
+    }
+
+    /// Set the IEEE 802.15.4 channel
+    ///
+    /// Valid channels are 0 to 10 (sub-GHz) and 11 to 26 (2.4 GHz); which ones are supported
+    /// depends on the radio, which reports unsupported channels as errors.
+    #[doc(alias = "NETOPT_CHANNEL")]
+    pub fn set_channel(&self, channel: u16) -> Result<(), NumericError> {
+        if channel > 26 {
+            return Err(Errno::Inval.into());
+        }
+        self.netapi_set(riot_sys::netopt_t_NETOPT_CHANNEL, &channel)
+    }
+
+    /// Set the IEEE 802.15.4 PAN ID
+    ///
+    /// The broadcast PAN ID 0xffff is rejected.
+    #[doc(alias = "NETOPT_NID")]
+    pub fn set_pan_id(&self, pan_id: u16) -> Result<(), NumericError> {
+        if pan_id == 0xffff {
+            return Err(Errno::Inval.into());
+        }
+        self.netapi_set(riot_sys::netopt_t_NETOPT_NID, &pan_id)
+    }
+
+    /// Set the IEEE 802.15.4 short address
+    ///
+    /// The reserved addresses 0xfffe ("no short address") and 0xffff (broadcast) are rejected.
+    #[doc(alias = "NETOPT_ADDRESS")]
+    pub fn set_short_addr(&self, addr: u16) -> Result<(), NumericError> {
+        if addr >= 0xfffe {
+            return Err(Errno::Inval.into());
+        }
+        // Addresses are passed in network byte order
+        self.netapi_set(riot_sys::netopt_t_NETOPT_ADDRESS, &addr.to_be_bytes())
+    }
+
+    /// Set the transmit power in dBm
+    ///
+    /// Values outside -40 to 20 dBm (beyond what any supported radio offers) are rejected;
+    /// within that, the radio reports an error for values it does not support.
+    #[doc(alias = "NETOPT_TX_POWER")]
+    pub fn set_tx_power_dbm(&self, dbm: i16) -> Result<(), NumericError> {
+        if !(-40..=20).contains(&dbm) {
+            return Err(Errno::Inval.into());
+        }
+        self.netapi_set(riot_sys::netopt_t_NETOPT_TX_POWER, &dbm)
+    }
+}
+
+/// Layer of the network stack at which [Netif::stats] are counted
+#[cfg(any(riot_module_netstats_l2, riot_module_netstats_ipv6))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NetstatsLayer {
+    /// Statistics of the link layer (needs the `netstats_l2` module)
+    L2 = riot_sys::NETSTATS_LAYER2 as isize,
+    /// Statistics of the IPv6 layer (needs the `netstats_ipv6` module)
+    Ipv6 = riot_sys::NETSTATS_IPV6 as isize,
+}
+
+/// Packet statistics of an interface, see [Netif::stats]
+#[cfg(any(riot_module_netstats_l2, riot_module_netstats_ipv6))]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct Netstats {
+    /// Number of unicast packets sent
+    pub tx_unicast_count: u32,
+    /// Number of multicast (and broadcast) packets sent
+    pub tx_mcast_count: u32,
+    /// Number of successful transmissions
+    pub tx_success: u32,
+    /// Number of failed transmissions
+    pub tx_failed: u32,
+    /// Number of bytes sent
+    pub tx_bytes: u32,
+    /// Number of packets received
+    pub rx_count: u32,
+    /// Number of bytes received
+    pub rx_bytes: u32,
+}
+
+/// Administrative state of an interface, as set through [Netif::set_state]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum State {
+    Up,
+    Down,
+    Sleep,
 }
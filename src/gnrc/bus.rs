@@ -0,0 +1,161 @@
+//! Subscribing to network interface events through the [message
+//! bus](https://doc.riot-os.org/group__sys__msg__bus.html) of a GNRC network interface
+//!
+//! GNRC publishes some events of an interface on per-interface message buses; a thread can
+//! [attach](Subscription::attach) to such a bus, [subscribe](Subscription::subscribe) to
+//! individual events, and then receives them as messages alongside its other messages.
+//! [Subscription::decode] recognizes those among the received messages.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gnrc::{Netif, bus::{Bus, Event, Subscription}};
+//! # use riot_wrappers::msg::OpaqueMsg;
+//! # let netif: Netif = unimplemented!();
+//! let subscription = Subscription::new();
+//! pin_utils::pin_mut!(subscription);
+//! subscription.attach(&netif, Bus::Ipv6);
+//! subscription.subscribe(Event::IPV6_ADDR_VALID);
+//! loop {
+//!     let msg = OpaqueMsg::receive();
+//!     if let Some(Event::Ipv6AddrValid(addr)) = subscription.decode(&msg) {
+//!         riot_wrappers::println!("New address: {:?}", addr);
+//!     }
+//! }
+//! ```
+//!
+//! Which events are published depends on the RIOT version; RIOT currently only publishes the
+//! validity of IPv6 addresses (eg. once duplicate address detection completed), but not eg.
+//! changes in RPL parents.
+
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+
+use crate::msg::WrapsMsgT;
+
+/// A message bus of a network interface
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Bus {
+    /// The IPv6 bus, see [Event::IPV6_ADDR_VALID]
+    Ipv6,
+}
+
+impl Bus {
+    fn to_c(self) -> riot_sys::gnrc_netif_bus_t {
+        match self {
+            Bus::Ipv6 => riot_sys::gnrc_netif_bus_t_GNRC_NETIF_BUS_IPV6,
+        }
+    }
+}
+
+/// An event received from a message bus
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Event {
+    /// An IPv6 address of the interface became valid
+    #[cfg(riot_module_ipv6)]
+    Ipv6AddrValid(super::ipv6::Address),
+    /// An event of the given number that has no dedicated representation here
+    Other(u8),
+}
+
+impl Event {
+    /// Event number of [Event::Ipv6AddrValid] on the [Bus::Ipv6] bus
+    pub const IPV6_ADDR_VALID: u8 = riot_sys::gnrc_ipv6_event_t_GNRC_IPV6_EVENT_ADDR_VALID as _;
+}
+
+/// Membership of the current thread in a network interface's message bus
+///
+/// As the bus keeps a reference to the subscription, it is used in pinned form; it is detached
+/// from the bus when dropped.
+pub struct Subscription {
+    entry: riot_sys::msg_bus_entry_t,
+    bus: Option<(*mut riot_sys::msg_bus_t, Bus)>,
+    _phantom: PhantomPinned,
+}
+
+impl Subscription {
+    pub fn new() -> Self {
+        Subscription {
+            entry: Default::default(),
+            bus: None,
+            _phantom: PhantomPinned,
+        }
+    }
+
+    /// Attach the current thread to the given bus of the interface
+    ///
+    /// Events on the bus are only sent once they are [subscribed](Self::subscribe) to. Any bus
+    /// the subscription was previously attached to is detached from.
+    #[doc(alias = "msg_bus_attach")]
+    #[doc(alias = "gnrc_netif_get_bus")]
+    pub fn attach(self: &mut Pin<&mut Self>, netif: &super::Netif, bus: Bus) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        s.detach();
+        // unsafe: OK per C API; the netif is registered, and its buses live as long as it does.
+        let bus_ptr = unsafe { riot_sys::gnrc_netif_get_bus(netif.0 as *mut _, bus.to_c()) };
+        // unsafe: OK per C API; the entry is pinned and detached before it is dropped.
+        unsafe { riot_sys::msg_bus_attach(bus_ptr, &mut s.entry) };
+        s.bus = Some((bus_ptr, bus));
+    }
+
+    /// Start receiving events of the given number
+    #[doc(alias = "msg_bus_subscribe")]
+    pub fn subscribe(self: &mut Pin<&mut Self>, event: u8) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        // unsafe: OK per C API
+        unsafe { riot_sys::inline::msg_bus_subscribe(crate::inline_cast_mut(&mut s.entry), event) };
+    }
+
+    /// Stop receiving events of the given number
+    #[doc(alias = "msg_bus_unsubscribe")]
+    pub fn unsubscribe(self: &mut Pin<&mut Self>, event: u8) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        // unsafe: OK per C API
+        unsafe {
+            riot_sys::inline::msg_bus_unsubscribe(crate::inline_cast_mut(&mut s.entry), event)
+        };
+    }
+
+    fn detach(&mut self) {
+        if let Some((bus, _)) = self.bus.take() {
+            // unsafe: OK per C API; the entry was attached to that bus.
+            unsafe { riot_sys::msg_bus_detach(bus, &mut self.entry) };
+        }
+    }
+
+    /// Recognize a message that was received from the bus this is attached to
+    ///
+    /// Returns None if the message did not come from the bus.
+    pub fn decode(&self, msg: &impl WrapsMsgT) -> Option<Event> {
+        let (bus, kind) = self.bus?;
+        let msg = msg.view();
+        // unsafe: OK per C API; these only look at the message's type and the bus's ID.
+        let from_bus = unsafe {
+            riot_sys::inline::msg_is_from_bus(crate::inline_cast(bus), crate::inline_cast(msg))
+        };
+        if !from_bus {
+            return None;
+        }
+        // unsafe: OK per C API
+        let event = unsafe { riot_sys::inline::msg_bus_get_type(crate::inline_cast(msg)) } as u8;
+        Some(match (kind, event) {
+            #[cfg(riot_module_ipv6)]
+            (Bus::Ipv6, Event::IPV6_ADDR_VALID) => {
+                // unsafe: Events of this type carry a pointer to the address
+                let addr = unsafe { msg.content.ptr } as *const riot_sys::ipv6_addr_t;
+                Event::Ipv6AddrValid(super::ipv6::Address::clone_from_ptr(addr))
+            }
+            (_, event) => Event::Other(event),
+        })
+    }
+}
+
+impl Drop for Subscription {
+    #[doc(alias = "msg_bus_detach")]
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
@@ -39,8 +39,9 @@ impl<'a> Pktsnip<Writable> {
             )
         };
         if snip == 0 as *mut _ {
-            Err(NotEnoughSpace)
+            Err(super::pktbuf::allocation_failed())
         } else {
+            super::pktbuf::allocation_succeeded();
             unsafe { Ok(Pktsnip::<Writable>::from_ptr(snip)) }
         }
     }
@@ -4,8 +4,8 @@ use core::mem::MaybeUninit;
 
 use riot_sys::{ipv6_addr_from_str, ipv6_addr_t, kernel_pid_t};
 
-use super::pktbuf::{Mode, NotEnoughSpace, Pktsnip, Writable};
-use crate::error::{NegativeErrorExt, NumericError};
+use super::pktbuf::{Mode, NotEnoughSpace, Pktsnip, Shared, Writable};
+use crate::error::{Errno, NegativeErrorExt, NumericError};
 
 impl super::Netif {
     pub fn ipv6_addrs(
@@ -26,6 +26,50 @@ impl super::Netif {
         addrs.len = (result.negative_to_error()? as usize) / core::mem::size_of::<Address>();
         Ok(addrs)
     }
+
+    /// The address of this interface that RIOT would use as a source when sending to `remote`
+    ///
+    /// This follows the source address selection rules of RFC 6724 (preferring eg. addresses of
+    /// matching scope, non-deprecated addresses and longer common prefixes), and is thus the
+    /// address to advertise to a peer that is reached through `remote`. Tentative addresses are
+    /// never selected.
+    ///
+    /// Returns None if the interface has no suitable address.
+    #[doc(alias = "gnrc_netif_ipv6_addr_best_src")]
+    pub fn best_source_for(&self, remote: &Address) -> Option<Address> {
+        // unsafe: OK per C API; the function locks the netif while it selects. The resulting
+        // pointer into the netif's address table is copied out right away.
+        let best = unsafe {
+            riot_sys::gnrc_netif_ipv6_addr_best_src(self.0 as *mut _, remote.as_ptr(), false)
+        };
+        if best.is_null() {
+            None
+        } else {
+            Some(Address::clone_from_ptr(best))
+        }
+    }
+}
+
+/// The global address that the system would use as a source towards the Internet
+///
+/// This picks the [best source](super::Netif::best_source_for) for a global destination on the
+/// first interface that has a global address. It is the address to advertise in places where
+/// there is no particular peer yet, eg. when registering at a resource directory whose address is
+/// not known.
+///
+/// Returns None if no interface has a global address (eg. while still waiting for a router
+/// advertisement).
+pub fn preferred_global_address() -> Option<Address> {
+    // Any global unicast address works here, the destination only determines the scope. This is
+    // 2001:db8::1, from the documentation prefix.
+    let global = Address {
+        inner: ipv6_addr_t {
+            u8_: [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        },
+    };
+    super::Netif::all()
+        .filter_map(|netif| netif.best_source_for(&global))
+        .find(|addr| !addr.is_link_local() && !addr.is_unspecified() && !addr.is_loopback())
 }
 
 /// Helper for [super::Netif::ipv6_addrs]: As the [riot_sys::gnrc_netif_ipv6_addrs_get] function requires
@@ -126,6 +170,34 @@ impl ::core::fmt::Debug for Address {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Address {
+    fn format(&self, f: defmt::Formatter) {
+        let as_u8 = self.raw();
+        defmt::write!(
+            f,
+            "{:02x}{:02x}:{:02x}{:02x}:{:02x}{:02x}:{:02x}{:02x}:{:02x}{:02x}:{:02x}{:02x}:\
+             {:02x}{:02x}:{:02x}{:02x}",
+            as_u8[0],
+            as_u8[1],
+            as_u8[2],
+            as_u8[3],
+            as_u8[4],
+            as_u8[5],
+            as_u8[6],
+            as_u8[7],
+            as_u8[8],
+            as_u8[9],
+            as_u8[10],
+            as_u8[11],
+            as_u8[12],
+            as_u8[13],
+            as_u8[14],
+            as_u8[15],
+        )
+    }
+}
+
 impl Address {
     pub fn raw(&self) -> &[u8; 16] {
         unsafe { &self.inner.u8_ }
@@ -235,14 +307,70 @@ impl<M: Mode> Pktsnip<M> {
         let dst = dst.map(|d| unsafe { d.as_ptr() }).unwrap_or(0 as *mut _);
         let snip = unsafe { riot_sys::gnrc_ipv6_hdr_build(self.ptr, src, dst) };
         if snip == 0 as *mut _ {
-            Err(NotEnoughSpace)
+            Err(super::pktbuf::allocation_failed())
         } else {
             core::mem::forget(self);
+            super::pktbuf::allocation_succeeded();
             Ok(unsafe { Pktsnip::<Writable>::from_ptr(snip) })
         }
     }
 }
 
+/// Build a raw IPv6 packet around the given payload, for sending with [send]
+///
+/// The IPv6 header's next header field is set to `next_header`, which allows prototyping
+/// transport protocols that GNRC has no support for. The payload snip is of type
+/// `GNRC_NETTYPE_UNDEF`, so GNRC does not try to interpret or update it on the way down.
+///
+/// If `src` is None, the source address is selected by `gnrc_ipv6` when the packet is sent. If
+/// `netif` is given, a netif header is prepended that sends the packet out on that interface,
+/// which is necessary for link-local destinations on nodes with several interfaces.
+#[doc(alias = "gnrc_ipv6_hdr_build")]
+pub fn build_raw(
+    payload: &[u8],
+    next_header: u8,
+    src: Option<&Address>,
+    dst: &Address,
+    netif: Option<&super::Netif>,
+) -> Result<Pktsnip<Shared>, NotEnoughSpace> {
+    let payload =
+        Pktsnip::<Writable>::allocate_from(payload, riot_sys::gnrc_nettype_t_GNRC_NETTYPE_UNDEF)?;
+    let ip = payload.ipv6_hdr_build(src, Some(dst))?;
+    // unsafe: The first snip is the IPv6 header that was just built, and as it is writable, no
+    // one else is looking at it.
+    unsafe { (*((*ip.ptr).data as *mut riot_sys::ipv6_hdr_t)).nh = next_header };
+
+    match netif {
+        None => Ok(ip.into()),
+        Some(netif) => {
+            let mut pkt = ip.netif_hdr_build(None, None)?;
+            // unsafe: The first snip is the netif header that was just built
+            let hdr: &mut riot_sys::gnrc_netif_hdr_t =
+                unsafe { &mut *(pkt.data_mut().as_mut_ptr() as *mut _) };
+            hdr.if_pid = netif.pid().into();
+            Ok(pkt.into())
+        }
+    }
+}
+
+/// Hand a packet (typically built with [build_raw]) to `gnrc_ipv6` for sending
+///
+/// This fails with `ENETDOWN` if no IPv6 thread is running to take the packet; errors that happen
+/// later (eg. when no route is found) are only reported through the packet buffer's error
+/// reporting, and not visible here.
+#[doc(alias = "gnrc_netapi_dispatch_send")]
+pub fn send(pkt: Pktsnip<Shared>) -> Result<(), NumericError> {
+    let recipients = super::netapi::dispatch_send(
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_IPV6,
+        riot_sys::GNRC_NETREG_DEMUX_CTX_ALL,
+        pkt,
+    );
+    match recipients {
+        0 => Err(Errno::NetDown.into()),
+        _ => Ok(()),
+    }
+}
+
 /// A transparent wrapper around ``ipv6_hdr_t`` that provides idiomatically typed fields
 #[repr(transparent)]
 #[doc(alias = "ipv6_hdr_t")]
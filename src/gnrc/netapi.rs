@@ -1,6 +1,27 @@
+//! Passing packets between GNRC modules through the [network
+//! API](https://doc.riot-os.org/group__net__gnrc__netapi.html)
+//!
+//! Packets can either be dispatched to all modules registered for a type (through the
+//! [netreg](super::netreg)), as with [dispatch_send] and [dispatch_receive], or handed to one
+//! particular module's thread, as with [send] and [receive]. The latter allows a protocol layer
+//! implemented in Rust to inject packets at a particular layer, eg. directly into a 6LoWPAN
+//! interface:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gnrc::{netapi, Netif};
+//! # use riot_wrappers::gnrc::pktbuf::Pktsnip;
+//! # let packet: Pktsnip<riot_wrappers::gnrc::pktbuf::Shared> = unimplemented!();
+//! let netif = Netif::all().next().unwrap();
+//! if let Err(_packet) = netapi::send(netif.pid(), packet) {
+//!     // The interface's queue is full; the packet is released when dropped here.
+//! }
+//! ```
+
 use riot_sys::gnrc_nettype_t;
 
 use crate::gnrc::pktbuf::{Pktsnip, Shared};
+use crate::thread::KernelPID;
 
 /// Dispatch a packet to all listeners of the given nettype and demux context.
 ///
@@ -21,3 +42,63 @@ pub fn dispatch_send(
     }
     subscribers
 }
+
+/// Dispatch a packet that was received to all listeners of the given nettype and demux context.
+///
+/// This is the receiving direction counterpart of [dispatch_send]; it is used by a layer that
+/// has processed an incoming packet and passes it on upwards.
+#[doc(alias = "gnrc_netapi_dispatch_receive")]
+pub fn dispatch_receive(
+    nettype: gnrc_nettype_t,
+    demux_ctx: u32,
+    pkt: impl Into<Pktsnip<Shared>>,
+) -> i32 {
+    let pkt = unsafe { pkt.into().to_ptr() };
+    let subscribers = unsafe {
+        riot_sys::gnrc_netapi_dispatch_receive(nettype, demux_ctx, crate::inline_cast_mut(pkt))
+    };
+    if subscribers == 0 {
+        unsafe { riot_sys::inline::gnrc_pktbuf_release(crate::inline_cast_mut(pkt)) };
+    }
+    subscribers
+}
+
+/// Common implementation of [send] and [receive]
+fn hand_over(
+    pid: KernelPID,
+    pkt: Pktsnip<Shared>,
+    f: unsafe fn(riot_sys::kernel_pid_t, *mut riot_sys::gnrc_pktsnip_t) -> i32,
+) -> Result<(), Pktsnip<Shared>> {
+    let pkt = unsafe { pkt.to_ptr() };
+    // unsafe: OK per C API; the packet's reference is passed on.
+    match unsafe { f(pid.into(), pkt) } {
+        1 => Ok(()),
+        // unsafe: On failure, the reference was not consumed.
+        _ => Err(unsafe { Pktsnip::<Shared>::from_ptr(pkt) }),
+    }
+}
+
+/// Hand a packet to a particular module's thread for sending (eg. to a network interface, or to
+/// the IPv6 thread)
+///
+/// If the thread does not accept the packet (because its message queue is full, or the PID is
+/// not a thread), the packet is returned.
+#[doc(alias = "gnrc_netapi_send")]
+pub fn send(pid: KernelPID, pkt: impl Into<Pktsnip<Shared>>) -> Result<(), Pktsnip<Shared>> {
+    unsafe fn raw(pid: riot_sys::kernel_pid_t, pkt: *mut riot_sys::gnrc_pktsnip_t) -> i32 {
+        riot_sys::gnrc_netapi_send(pid, crate::inline_cast_mut(pkt))
+    }
+    hand_over(pid, pkt.into(), raw)
+}
+
+/// Hand a received packet to a particular module's thread for processing
+///
+/// If the thread does not accept the packet (because its message queue is full, or the PID is
+/// not a thread), the packet is returned.
+#[doc(alias = "gnrc_netapi_receive")]
+pub fn receive(pid: KernelPID, pkt: impl Into<Pktsnip<Shared>>) -> Result<(), Pktsnip<Shared>> {
+    unsafe fn raw(pid: riot_sys::kernel_pid_t, pkt: *mut riot_sys::gnrc_pktsnip_t) -> i32 {
+        riot_sys::gnrc_netapi_receive(pid, crate::inline_cast_mut(pkt))
+    }
+    hand_over(pid, pkt.into(), raw)
+}
@@ -17,8 +17,104 @@ use riot_sys::{
 
 /// Error type for pktsnip operations that need free buffer space
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct NotEnoughSpace;
 
+/// Usage statistics of the packet buffer, see [stats()]
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct Stats {
+    /// Number of successful allocations through the wrappers
+    ///
+    /// This counts newly allocated snips (including built headers) as well as copies made by
+    /// [Pktsnip::start_write]; a resize through [Pktsnip::realloc_data] is not counted.
+    pub allocations: u32,
+    /// Number of operations through the wrappers that failed for lack of buffer space
+    pub failures: u32,
+}
+
+struct StatsCell(core::cell::UnsafeCell<Stats>);
+
+// unsafe: Only accessed with interrupts disabled
+unsafe impl Sync for StatsCell {}
+
+static STATS: StatsCell = StatsCell(core::cell::UnsafeCell::new(Stats {
+    allocations: 0,
+    failures: 0,
+}));
+
+type ExhaustionCallback = &'static mut (dyn FnMut() + Send);
+
+static EXHAUSTION_CALLBACK: crate::mutex::Mutex<Option<ExhaustionCallback>> =
+    crate::mutex::Mutex::new(None);
+
+fn update_stats(f: impl FnOnce(&mut Stats)) {
+    crate::interrupt::free(|_| {
+        // unsafe: Interrupts are disabled, and the reference does not leave the closure
+        f(unsafe { &mut *STATS.0.get() })
+    })
+}
+
+/// Account for a successful allocation
+pub(crate) fn allocation_succeeded() {
+    update_stats(|s| s.allocations = s.allocations.wrapping_add(1));
+}
+
+/// Account for a failed operation, and produce the error for it
+pub(crate) fn allocation_failed() -> NotEnoughSpace {
+    update_stats(|s| s.failures = s.failures.wrapping_add(1));
+    // Not blocking: This may be called from an interrupt, or while the callback is being replaced
+    if let Some(mut callback) = EXHAUSTION_CALLBACK.try_lock() {
+        if let Some(callback) = callback.as_mut() {
+            callback();
+        }
+    }
+    NotEnoughSpace
+}
+
+/// Statistics of packet buffer usage through the wrappers
+///
+/// Only operations performed through these wrappers are counted: Allocations that fail in C code
+/// (eg. in the network stack when receiving packets) are not visible here.
+pub fn stats() -> Stats {
+    let mut result = Stats::default();
+    update_stats(|s| result = *s);
+    result
+}
+
+/// Set a callback that is run whenever an operation through the wrappers fails for lack of buffer
+/// space
+///
+/// This allows applications to detect buffer exhaustion and shed load (eg. by not accepting
+/// further requests for a while) rather than having packets dropped silently.
+///
+/// The callback may be called in any thread that performs packet buffer operations (and from
+/// interrupts if they do), so it should be brief; typically, it just sets a flag.
+pub fn set_exhaustion_callback(callback: ExhaustionCallback) {
+    *EXHAUSTION_CALLBACK.lock() = Some(callback);
+}
+
+/// Remove any callback set with [set_exhaustion_callback]
+pub fn clear_exhaustion_callback() {
+    *EXHAUSTION_CALLBACK.lock() = None;
+}
+
+/// True if no snips are allocated in the packet buffer
+#[doc(alias = "gnrc_pktbuf_is_empty")]
+pub fn is_empty() -> bool {
+    // unsafe: OK per C API
+    unsafe { riot_sys::gnrc_pktbuf_is_empty() }
+}
+
+/// Print the packet buffer's internal usage statistics to stdout
+#[cfg(riot_develhelp)]
+#[doc(alias = "gnrc_pktbuf_stats")]
+pub fn print_stats() {
+    // unsafe: OK per C API
+    unsafe { riot_sys::gnrc_pktbuf_stats() }
+}
+
 #[derive(Debug)]
 pub struct PktsnipPart<'a> {
     pub data: &'a [u8],
@@ -141,9 +237,10 @@ impl<M: Mode> Pktsnip<M> {
         let snip = unsafe { gnrc_udp_hdr_build(self.ptr, src.into(), dst.into()) };
         if snip == 0 as *mut _ {
             // All other errors are caught by the signature
-            Err(NotEnoughSpace)
+            Err(allocation_failed())
         } else {
             forget(self);
+            allocation_succeeded();
             Ok(unsafe { Pktsnip::<Writable>::from_ptr(snip) })
         }
     }
@@ -166,8 +263,9 @@ impl<M: Mode> Pktsnip<M> {
             gnrc_netif_hdr_build(src as *mut _, src_len as u8, dst as *mut _, dst_len as u8)
         };
         if snip == 0 as *mut _ {
-            Err(NotEnoughSpace)
+            Err(allocation_failed())
         } else {
+            allocation_succeeded();
             unsafe {
                 (*snip).next = self.to_ptr();
                 Ok(Pktsnip::<Writable>::from_ptr(snip))
@@ -205,10 +303,15 @@ impl<'a> Pktsnip<Shared> {
     pub fn start_write(self) -> Result<Pktsnip<Writable>, NotEnoughSpace> {
         // unsafe: The C functions justify the new type
         unsafe {
-            let new = riot_sys::gnrc_pktbuf_start_write(self.to_ptr());
+            let old = self.to_ptr();
+            let new = riot_sys::gnrc_pktbuf_start_write(old);
             if new == 0 as _ {
-                Err(NotEnoughSpace)
+                Err(allocation_failed())
             } else {
+                // Unshared snips are returned as they are
+                if new != old {
+                    allocation_succeeded();
+                }
                 Ok(Pktsnip::<Writable>::from_ptr(new))
             }
         }
@@ -257,9 +360,10 @@ impl<'a> Pktsnip<Writable> {
         let snip =
             unsafe { gnrc_pktbuf_add(next, data as *const _, size.try_into().unwrap(), nettype) };
         if snip == 0 as *mut _ {
-            return Err(NotEnoughSpace);
+            return Err(allocation_failed());
         }
         forget(next);
+        allocation_succeeded();
         Ok(unsafe { Pktsnip::<Writable>::from_ptr(snip) })
     }
 
@@ -277,7 +381,7 @@ impl<'a> Pktsnip<Writable> {
         if result == 0 {
             Ok(())
         } else {
-            Err(NotEnoughSpace)
+            Err(allocation_failed())
         }
     }
 }
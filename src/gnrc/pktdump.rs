@@ -0,0 +1,238 @@
+//! Debug output of GNRC packets, similar to RIOT's `gnrc_pktdump` module
+//!
+//! The main component here is [Dump], obtained from [Pktsnip::dump()], which displays a packet
+//! snip by snip: Headers of types known to this module are decoded, and the remaining data is
+//! shown in a hex dump.
+//!
+//! ```text
+//! ~~ SNIP  0 - size:   4 byte, type: UNDEF (0)
+//! 00000000 68 69 21 0a
+//! ~~ SNIP  1 - size:   8 byte, type: UDP (6)
+//! UDP: src port: 5683, dst port: 5683, length: 12, checksum: 0x4a2e
+//! ~~ SNIP  2 - size:  40 byte, type: IPV6 (2)
+//! IPv6: Header { ... }
+//! ~~ PKT    -  3 snips, total size:  52 byte
+//! ```
+//!
+//! With the `with_msg_v2` feature, [run_sink] provides a thread main loop that registers for a
+//! nettype and prints everything that arrives, just like the C module does when it is
+//! auto-initialized.
+
+use core::fmt;
+
+use riot_sys::gnrc_nettype_t;
+
+use super::pktbuf::{Mode, Pktsnip, PktsnipPart};
+
+/// Display adapter for a [Pktsnip]; see [module level documentation](self)
+pub struct Dump<'a, M: Mode>(&'a Pktsnip<M>);
+
+impl<M: Mode> Pktsnip<M> {
+    /// Produce a [Display](core::fmt::Display)able pretty-printed version of the packet
+    pub fn dump(&self) -> Dump<'_, M> {
+        Dump(self)
+    }
+}
+
+/// Name of a nettype as used by `gnrc_pktdump`
+fn nettype_name(type_: gnrc_nettype_t) -> &'static str {
+    match type_ {
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_UNDEF => "UNDEF",
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_NETIF => "NETIF",
+        #[cfg(riot_module_gnrc_nettype_sixlowpan)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_SIXLOWPAN => "SIXLOWPAN",
+        #[cfg(riot_module_gnrc_nettype_ipv6)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_IPV6 => "IPV6",
+        #[cfg(riot_module_gnrc_nettype_icmpv6)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_ICMPV6 => "ICMPV6",
+        #[cfg(riot_module_gnrc_nettype_udp)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_UDP => "UDP",
+        #[cfg(riot_module_gnrc_nettype_tcp)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_TCP => "TCP",
+        _ => "unknown",
+    }
+}
+
+/// Write a single snip's header line and decoded content
+fn dump_snip(f: &mut fmt::Formatter, index: usize, snip: &PktsnipPart) -> fmt::Result {
+    writeln!(
+        f,
+        "~~ SNIP {:2} - size: {:3} byte, type: {} ({})",
+        index,
+        snip.data.len(),
+        nettype_name(snip.type_),
+        snip.type_
+    )?;
+
+    let decoded = match snip.type_ {
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_NETIF => dump_netif(f, snip.data)?,
+        #[cfg(riot_module_ipv6)]
+        #[cfg(riot_module_gnrc_nettype_ipv6)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_IPV6 => dump_ipv6(f, snip.data)?,
+        #[cfg(riot_module_gnrc_nettype_udp)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_UDP => dump_udp(f, snip.data)?,
+        #[cfg(riot_module_gnrc_nettype_icmpv6)]
+        riot_sys::gnrc_nettype_t_GNRC_NETTYPE_ICMPV6 => dump_icmpv6(f, snip.data)?,
+        _ => false,
+    };
+
+    if !decoded {
+        hexdump(f, snip.data)?;
+    }
+    Ok(())
+}
+
+/// Write a hex dump of the data, 16 bytes per line, prefixed with the offset
+pub fn hexdump(f: &mut impl fmt::Write, data: &[u8]) -> fmt::Result {
+    for (i, line) in data.chunks(16).enumerate() {
+        write!(f, "{:08x}", i * 16)?;
+        for byte in line {
+            write!(f, " {:02x}", byte)?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+fn write_l2addr(f: &mut fmt::Formatter, addr: &[u8]) -> fmt::Result {
+    for (i, byte) in addr.iter().enumerate() {
+        if i != 0 {
+            write!(f, ":")?;
+        }
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+// The decoders return Ok(false) if the data is not long enough to contain the header they expect,
+// in which case it is hexdumped instead.
+
+fn dump_netif(f: &mut fmt::Formatter, data: &[u8]) -> Result<bool, fmt::Error> {
+    let hdr_len = core::mem::size_of::<riot_sys::gnrc_netif_hdr_t>();
+    if data.len() < hdr_len {
+        return Ok(false);
+    }
+    // unsafe: Length was checked, and snips of the NETIF type are netif headers by the Pktsnip
+    // construction requirements. Copying out rather than referencing as the data may be unaligned.
+    let hdr: riot_sys::gnrc_netif_hdr_t =
+        unsafe { core::ptr::read_unaligned(data.as_ptr() as *const _) };
+    let src_len = hdr.src_l2addr_len as usize;
+    let dst_len = hdr.dst_l2addr_len as usize;
+    if data.len() < hdr_len + src_len + dst_len {
+        return Ok(false);
+    }
+    let src = &data[hdr_len..hdr_len + src_len];
+    let dst = &data[hdr_len + src_len..hdr_len + src_len + dst_len];
+
+    write!(
+        f,
+        "NETIF: if_pid: {}, rssi: {}, lqi: {}, src_l2addr: ",
+        hdr.if_pid, hdr.rssi, hdr.lqi
+    )?;
+    write_l2addr(f, src)?;
+    write!(f, ", dst_l2addr: ")?;
+    write_l2addr(f, dst)?;
+    writeln!(f)?;
+    Ok(true)
+}
+
+#[cfg(riot_module_ipv6)]
+#[cfg(riot_module_gnrc_nettype_ipv6)]
+fn dump_ipv6(f: &mut fmt::Formatter, data: &[u8]) -> Result<bool, fmt::Error> {
+    if data.len() < core::mem::size_of::<super::ipv6::Header>() {
+        return Ok(false);
+    }
+    // unsafe: Length was checked, and IPv6 snips contain an IPv6 header by the Pktsnip
+    // construction requirements. Copying out as the data may be unaligned.
+    let header: super::ipv6::Header =
+        unsafe { core::ptr::read_unaligned(data.as_ptr() as *const _) };
+    writeln!(f, "IPv6: {:?}", header)?;
+    Ok(true)
+}
+
+#[cfg(riot_module_gnrc_nettype_udp)]
+fn dump_udp(f: &mut fmt::Formatter, data: &[u8]) -> Result<bool, fmt::Error> {
+    if data.len() < 8 {
+        return Ok(false);
+    }
+    let field = |i: usize| u16::from_be_bytes([data[i], data[i + 1]]);
+    writeln!(
+        f,
+        "UDP: src port: {}, dst port: {}, length: {}, checksum: {:#06x}",
+        field(0),
+        field(2),
+        field(4),
+        field(6)
+    )?;
+    Ok(true)
+}
+
+#[cfg(riot_module_gnrc_nettype_icmpv6)]
+fn dump_icmpv6(f: &mut fmt::Formatter, data: &[u8]) -> Result<bool, fmt::Error> {
+    if data.len() < 4 {
+        return Ok(false);
+    }
+    writeln!(
+        f,
+        "ICMPv6: type: {}, code: {}, checksum: {:#06x}",
+        data[0],
+        data[1],
+        u16::from_be_bytes([data[2], data[3]])
+    )?;
+    hexdump(f, &data[4..])?;
+    Ok(true)
+}
+
+impl<'a, M: Mode> fmt::Display for Dump<'a, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut count = 0;
+        for (i, snip) in self.0.iter_snips().enumerate() {
+            dump_snip(f, i, &snip)?;
+            count += 1;
+        }
+        writeln!(
+            f,
+            "~~ PKT    - {:2} snips, total size: {:3} byte",
+            count,
+            self.0.len()
+        )
+    }
+}
+
+/// Port through which GNRC sends received packets to a thread, as used in [run_sink]
+#[cfg(feature = "with_msg_v2")]
+pub type ReceivedPacketsPort = crate::msg::v2::ReceivePort<
+    Pktsnip<super::pktbuf::Shared>,
+    { riot_sys::GNRC_NETAPI_MSG_TYPE_RCV as _ },
+>;
+
+/// Register the current thread for packets of the given type and context, and print each of them
+/// on stdio as they arrive.
+///
+/// This is the equivalent of the C `gnrc_pktdump` thread; it is typically started in a thread of
+/// its own with all its message semantics dedicated to it. Messages of other types than received
+/// packets are dropped according to the semantics.
+///
+/// Use `riot_sys::GNRC_NETREG_DEMUX_CTX_ALL` as `demux_ctx` to receive all packets of the type.
+#[cfg(feature = "with_msg_v2")]
+pub fn run_sink<S: crate::msg::v2::MessageSemantics>(
+    semantics: S,
+    port: ReceivedPacketsPort,
+    grant: crate::msg::v2::SendPort<
+        Pktsnip<super::pktbuf::Shared>,
+        { riot_sys::GNRC_NETAPI_MSG_TYPE_RCV as _ },
+    >,
+    nettype: gnrc_nettype_t,
+    demux_ctx: u32,
+) -> ! {
+    use crate::stdio::println;
+
+    super::netreg::register_for_messages(grant, nettype, demux_ctx, || loop {
+        let message = semantics.receive();
+        // Anything not decoded is dropped by the semantics
+        let _ = message.decode(&port, |_sender, packet| {
+            println!("PKTDUMP: data received:");
+            println!("{}", packet.dump());
+        });
+    })
+}
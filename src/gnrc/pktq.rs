@@ -0,0 +1,58 @@
+//! Utilization of the [send queues](https://doc.riot-os.org/group__net__gnrc__netif__pktq.html)
+//! of network interfaces
+//!
+//! With the `gnrc_netif_pktq` module, interfaces queue packets that can not be sent right away
+//! (eg. because the medium is busy). All interfaces share one pool of queue entries; when it runs
+//! full, packets that can not be sent right away are dropped. Watching the utilization allows
+//! supervisory code to detect congestion before that happens:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gnrc::pktq;
+//! if pktq::usage() * 4 > pktq::capacity() * 3 {
+//!     // More than 3/4 of the queue are in use: Back off from sending
+//! }
+//! ```
+//!
+//! RIOT does not count the packets that were dropped for lack of queue space; an interface whose
+//! queue stays non-empty over several polls is a sign that it can not keep up.
+
+/// Number of packets currently queued on all interfaces
+#[doc(alias = "gnrc_netif_pktq_usage")]
+pub fn usage() -> usize {
+    // unsafe: OK per C API
+    unsafe { riot_sys::gnrc_netif_pktq_usage() as _ }
+}
+
+/// Number of packets that can be queued on all interfaces together
+pub fn capacity() -> usize {
+    crate::config::GNRC_NETIF_PKTQ_POOL_SIZE
+}
+
+impl super::Netif {
+    /// True if no packets are queued for sending on the interface
+    #[doc(alias = "gnrc_netif_pktq_empty")]
+    pub fn pktq_is_empty(&self) -> bool {
+        // unsafe: OK per C API; the interface is registered.
+        unsafe { riot_sys::inline::gnrc_netif_pktq_empty(crate::inline_cast_mut(self.0 as *mut _)) }
+    }
+
+    /// Number of packets queued for sending on the interface
+    ///
+    /// The queue is modified by the interface's thread without synchronization, so this is a
+    /// snapshot that may be off by the packets that are being queued or sent right now.
+    pub fn pktq_len(&self) -> usize {
+        crate::interrupt::free(|_| {
+            // unsafe: The interface is registered, and queue entries are initialized before they
+            // are linked in. Entries that are removed while this runs may lead to miscounts,
+            // which are bounded by limiting the walk to the pool size.
+            let mut entry = unsafe { (*self.0).send.queue };
+            let mut len = 0;
+            while !entry.is_null() && len < capacity() {
+                len += 1;
+                entry = unsafe { (*entry).next };
+            }
+            len
+        })
+    }
+}
@@ -0,0 +1,45 @@
+//! Statistics of [6LoWPAN fragmentation](https://doc.riot-os.org/group__net__gnrc__sixlowpan__frag.html)
+//!
+//! Datagrams that exceed a link layer frame (eg. large CoAP responses over IEEE 802.15.4) are sent
+//! in fragments, which use entries of the fragmentation buffer on the sender, and of the
+//! reassembly buffer (or, on forwarding nodes with `gnrc_sixlowpan_frag_vrb`, the virtual
+//! reassembly buffer) on the receiver. When these run full, datagrams are lost as a whole; the
+//! counters of this module show whether that happens.
+//!
+//! The buffer sizes and timeouts are build time settings; they are available in [crate::config]
+//! (eg. as `GNRC_SIXLOWPAN_FRAG_RBUF_SIZE` and `GNRC_SIXLOWPAN_FRAG_RBUF_TIMEOUT_US`), and can not
+//! be changed at runtime. Counters that keep increasing indicate that they need to be raised (or,
+//! for timeouts, lowered, so that incomplete datagrams free their entries sooner).
+
+/// Counters of fragmentation buffer exhaustion, see [stats()]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct Stats {
+    /// Number of times a fragment could not be reassembled because the reassembly buffer was
+    /// full
+    pub rbuf_full: u32,
+    /// Number of times a datagram could not be fragmented because no fragmentation buffer entry
+    /// was available
+    pub frag_full: u32,
+    /// Number of times a fragment could not be forwarded because the virtual reassembly buffer
+    /// was full
+    #[cfg(riot_module_gnrc_sixlowpan_frag_vrb)]
+    pub vrb_full: u32,
+}
+
+/// Read the counters of fragmentation buffer exhaustion since boot
+#[doc(alias = "gnrc_sixlowpan_frag_stats_get")]
+pub fn stats() -> Stats {
+    crate::interrupt::free(|_| {
+        // unsafe: OK per C API; the function returns a pointer to a static, which is copied out
+        // right away (with interrupts disabled to get a consistent snapshot).
+        let stats = unsafe { &*riot_sys::gnrc_sixlowpan_frag_stats_get() };
+        Stats {
+            rbuf_full: stats.rbuf_full as _,
+            frag_full: stats.frag_full as _,
+            #[cfg(riot_module_gnrc_sixlowpan_frag_vrb)]
+            vrb_full: stats.vrb_full as _,
+        }
+    })
+}
@@ -2,6 +2,21 @@
 //!
 //! The various configured GPIO types ([InputGPIO], [OutputGPIO], [InOutGPIO]) can be used through
 //! the [embedded_hal::digital::v2] traits.
+//!
+//! With the periph_gpio_irq module, pins can also be configured as an `IrqInputGPIO`, whose
+//! interrupt can be used. With the `with_embedded_hal_async` feature, that implements
+//! `embedded_hal_async::digital::Wait`, waking the waiting task from the pin's interrupt.
+//!
+//! For push buttons, the [button] module provides debouncing and press / long press detection;
+//! pulse outputs of sensors can be evaluated with the [pulse_counter].
+
+#[cfg(all(feature = "with_embedded_hal_async", riot_module_periph_gpio_irq))]
+mod wait;
+
+#[cfg(all(riot_module_periph_gpio_irq, riot_module_ztimer_msec))]
+pub mod button;
+#[cfg(riot_module_periph_gpio_irq)]
+pub mod pulse_counter;
 
 use riot_sys::{gpio_clear, gpio_mode_t, gpio_read, gpio_set, gpio_t, gpio_toggle};
 
@@ -110,6 +125,35 @@ impl GPIO {
         Ok(InputGPIO(self))
     }
 
+    /// Configure the pin as an input whose interrupt can be used
+    ///
+    /// This fails if the pin can not be configured in this mode, or has no interrupt support. The
+    /// interrupt is left disabled; it is set up by the users of the pin (see [IrqInputGPIO]).
+    #[cfg(riot_module_periph_gpio_irq)]
+    #[doc(alias = "gpio_init_int")]
+    pub fn configure_as_irq_input(
+        self,
+        mode: InputMode,
+    ) -> Result<IrqInputGPIO, crate::error::NumericError> {
+        extern "C" fn ignore(_arg: *mut riot_sys::libc::c_void) {}
+
+        let mode = mode.to_c();
+        // unsafe: OK per C API; the callback does not use its argument, and the interrupt is
+        // disabled right away.
+        unsafe {
+            riot_sys::gpio_init_int(
+                self.0,
+                mode,
+                riot_sys::gpio_flank_t_GPIO_BOTH,
+                Some(ignore),
+                core::ptr::null_mut(),
+            )
+        }
+        .negative_to_error()?;
+        unsafe { riot_sys::gpio_irq_disable(self.0) };
+        Ok(IrqInputGPIO(self, mode))
+    }
+
     pub fn configure_as_inout(
         self,
         mode: InOutMode,
@@ -192,6 +236,76 @@ impl InputPin for InputGPIO {
     }
 }
 
+#[cfg(feature = "with_embedded_hal_1")]
+impl embedded_hal_1::digital::ErrorType for InputGPIO {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "with_embedded_hal_1")]
+impl embedded_hal_1::digital::InputPin for InputGPIO {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(unsafe { gpio_read(self.to_c()) } != 0)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(unsafe { gpio_read(self.to_c()) } == 0)
+    }
+}
+
+/// A [GPIO] configured for input, whose interrupt was successfully set up once
+///
+/// This is used by the [button] and [pulse_counter] modules, and, with the
+/// `with_embedded_hal_async` feature, implements `embedded_hal_async::digital::Wait`.
+#[cfg(riot_module_periph_gpio_irq)]
+pub struct IrqInputGPIO(GPIO, gpio_mode_t);
+
+#[cfg(riot_module_periph_gpio_irq)]
+impl IrqInputGPIO {
+    /// See [GPIO::to_c]
+    pub fn to_c(&self) -> riot_sys::gpio_t {
+        self.0.to_c()
+    }
+
+    /// Mode the pin was configured in, for when the interrupt is set up again
+    pub(crate) fn mode(&self) -> gpio_mode_t {
+        self.1
+    }
+
+    /// Lose information about how the pin is configured, making it configurable again
+    pub fn deconfigured(self) -> GPIO {
+        self.0
+    }
+}
+
+#[cfg(riot_module_periph_gpio_irq)]
+impl InputPin for IrqInputGPIO {
+    type Error = Never;
+
+    fn is_high(&self) -> Result<bool, Never> {
+        Ok(unsafe { gpio_read(self.to_c()) } != 0)
+    }
+
+    fn is_low(&self) -> Result<bool, Never> {
+        Ok(unsafe { gpio_read(self.to_c()) } == 0)
+    }
+}
+
+#[cfg(all(feature = "with_embedded_hal_1", riot_module_periph_gpio_irq))]
+impl embedded_hal_1::digital::ErrorType for IrqInputGPIO {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(all(feature = "with_embedded_hal_1", riot_module_periph_gpio_irq))]
+impl embedded_hal_1::digital::InputPin for IrqInputGPIO {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(unsafe { gpio_read(self.to_c()) } != 0)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(unsafe { gpio_read(self.to_c()) } == 0)
+    }
+}
+
 /// A [GPIO] configured and usable for input and output
 pub struct InOutGPIO(GPIO);
 
@@ -0,0 +1,248 @@
+//! Debounced push buttons with press, long press and release events
+//!
+//! A [Button] watches an [IrqInputGPIO](super::IrqInputGPIO) through its interrupt. Each edge
+//! (re)starts a debounce timer, and only when the pin was stable for the debounce time, its level
+//! is evaluated. Events are reported to a [Handler] from interrupt context; handlers that need to
+//! do more than setting a flag can forward the event to a thread, eg. through
+//! [crate::msg::send_from_isr].
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gpio::{GPIO, InputMode};
+//! use riot_wrappers::gpio::button::{Button, Event, Handler};
+//!
+//! struct Toggle;
+//! impl Handler for Toggle {
+//!     fn event(&mut self, event: Event) {
+//!         if event == Event::Release { long: false } {
+//!             // short press: toggle something
+//!         }
+//!     }
+//! }
+//!
+//! # let pin: GPIO = unimplemented!();
+//! let pin = pin.configure_as_irq_input(InputMode::InPullUp).unwrap();
+//! let button = Button::new(pin, true, Toggle);
+//! pin_utils::pin_mut!(button);
+//! button.start();
+//! ```
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+
+use super::IrqInputGPIO;
+use crate::error::NegativeErrorExt;
+use crate::ztimer::Ticks;
+
+/// Something that happened to a [Button]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// The button was pressed
+    Press,
+    /// The button has been held down for the long press time
+    LongPress,
+    /// The button was released
+    Release {
+        /// Whether a [LongPress](Event::LongPress) was reported before
+        long: bool,
+    },
+}
+
+/// Recipient of a [Button]'s events
+///
+/// This is called in interrupt context, and needs to be short.
+pub trait Handler: Send {
+    fn event(&mut self, event: Event);
+}
+
+struct State<H> {
+    pressed: bool,
+    long: bool,
+    handler: H,
+}
+
+/// A debounced button on a GPIO pin
+///
+/// As the pin's interrupt and the timers refer to it, the button is used in pinned form; its
+/// interrupt is disabled and its timers are stopped when it is dropped.
+pub struct Button<H: Handler> {
+    pin: IrqInputGPIO,
+    active_low: bool,
+    debounce: Ticks<1000>,
+    long_press: Ticks<1000>,
+    /// Only accessed in critical sections
+    state: UnsafeCell<State<H>>,
+    debounce_timer: UnsafeCell<riot_sys::ztimer_t>,
+    long_press_timer: UnsafeCell<riot_sys::ztimer_t>,
+    started: bool,
+    _pinned: PhantomPinned,
+}
+
+impl<H: Handler> Button<H> {
+    /// Create a button on an input pin
+    ///
+    /// With `active_low`, the button counts as pressed when the pin is low (which is typical of
+    /// buttons that connect to ground with a pull-up on the pin).
+    ///
+    /// The debounce time defaults to 20ms, and a long press is reported after 1s.
+    pub fn new(pin: IrqInputGPIO, active_low: bool, handler: H) -> Self {
+        Button {
+            pin,
+            active_low,
+            debounce: Ticks(20),
+            long_press: Ticks(1000),
+            state: UnsafeCell::new(State {
+                pressed: false,
+                long: false,
+                handler,
+            }),
+            debounce_timer: Default::default(),
+            long_press_timer: Default::default(),
+            started: false,
+            _pinned: PhantomPinned,
+        }
+    }
+
+    /// Change the time the pin needs to be stable before it is evaluated
+    pub fn with_debounce(mut self, debounce: Ticks<1000>) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Change the time after which a held button is reported as a long press
+    pub fn with_long_press(mut self, long_press: Ticks<1000>) -> Self {
+        self.long_press = long_press;
+        self
+    }
+
+    fn is_pressed(&self) -> bool {
+        // unsafe: OK per C API
+        let high = unsafe { riot_sys::gpio_read(self.pin.to_c()) } != 0;
+        high != self.active_low
+    }
+
+    extern "C" fn edge(arg: *mut riot_sys::libc::c_void) {
+        // unsafe: Set up to point to the pinned button, which disables the interrupt before it
+        // goes away
+        let button = unsafe { &*(arg as *const Self) };
+        crate::interrupt::free(|_| {
+            // unsafe: OK per C API; setting a running timer again restarts it.
+            unsafe {
+                riot_sys::ztimer_set(
+                    riot_sys::ZTIMER_MSEC,
+                    button.debounce_timer.get(),
+                    button.debounce.0,
+                )
+            };
+        });
+    }
+
+    extern "C" fn debounced(arg: *mut riot_sys::libc::c_void) {
+        // unsafe: Set up to point to the pinned button, which stops the timer before it goes away
+        let button = unsafe { &*(arg as *const Self) };
+        crate::interrupt::free(|_| {
+            // unsafe: State is only accessed in critical sections
+            let state = unsafe { &mut *button.state.get() };
+            let pressed = button.is_pressed();
+            if pressed == state.pressed {
+                return;
+            }
+            state.pressed = pressed;
+            if pressed {
+                state.long = false;
+                // unsafe: OK per C API
+                unsafe {
+                    riot_sys::ztimer_set(
+                        riot_sys::ZTIMER_MSEC,
+                        button.long_press_timer.get(),
+                        button.long_press.0,
+                    )
+                };
+                state.handler.event(Event::Press);
+            } else {
+                // unsafe: OK per C API
+                unsafe {
+                    riot_sys::ztimer_remove(riot_sys::ZTIMER_MSEC, button.long_press_timer.get())
+                };
+                state.handler.event(Event::Release { long: state.long });
+            }
+        });
+    }
+
+    extern "C" fn held(arg: *mut riot_sys::libc::c_void) {
+        // unsafe: Set up to point to the pinned button, which stops the timer before it goes away
+        let button = unsafe { &*(arg as *const Self) };
+        crate::interrupt::free(|_| {
+            // unsafe: State is only accessed in critical sections
+            let state = unsafe { &mut *button.state.get() };
+            if state.pressed {
+                state.long = true;
+                state.handler.event(Event::LongPress);
+            }
+        });
+    }
+
+    /// Start watching the pin
+    ///
+    /// If the button is held down at this time, that is reported as a press.
+    #[doc(alias = "gpio_init_int")]
+    pub fn start(self: &mut Pin<&mut Self>) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        if s.started {
+            return;
+        }
+        let arg = s as *mut Self as *mut riot_sys::libc::c_void;
+        s.debounce_timer.get_mut().callback = Some(Self::debounced);
+        s.debounce_timer.get_mut().arg = arg;
+        s.long_press_timer.get_mut().callback = Some(Self::held);
+        s.long_press_timer.get_mut().arg = arg;
+
+        // unsafe: OK per C API; the argument stays valid as the button is pinned, and the
+        // interrupt is disabled before it is dropped.
+        unsafe {
+            riot_sys::gpio_init_int(
+                s.pin.to_c(),
+                s.pin.mode(),
+                riot_sys::gpio_flank_t_GPIO_BOTH,
+                Some(Self::edge),
+                arg,
+            )
+        }
+        .negative_to_error()
+        .expect("Pin was already successfully configured for interrupts");
+        s.started = true;
+
+        // Evaluate the initial state once it is stable
+        Self::edge(arg);
+    }
+
+    /// Whether the button is currently (in its debounced state) pressed
+    pub fn pressed(&self) -> bool {
+        // unsafe: State is only accessed in critical sections
+        crate::interrupt::free(|_| unsafe { (*self.state.get()).pressed })
+    }
+
+    /// Run a closure on the handler
+    ///
+    /// This runs with interrupts disabled, as the handler is otherwise accessed from interrupts.
+    pub fn with_handler<R>(&self, f: impl FnOnce(&mut H) -> R) -> R {
+        // unsafe: State is only accessed in critical sections
+        crate::interrupt::free(|_| f(unsafe { &mut (*self.state.get()).handler }))
+    }
+}
+
+impl<H: Handler> Drop for Button<H> {
+    fn drop(&mut self) {
+        if self.started {
+            // unsafe: OK per C API
+            unsafe {
+                riot_sys::gpio_irq_disable(self.pin.to_c());
+                riot_sys::ztimer_remove(riot_sys::ZTIMER_MSEC, self.debounce_timer.get());
+                riot_sys::ztimer_remove(riot_sys::ZTIMER_MSEC, self.long_press_timer.get());
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Counting pulses on a GPIO pin through its interrupt
+//!
+//! Many sensors report their measurement as a pulse frequency (eg. flow meters, or the S0
+//! interface of energy meters), and PIR motion sensors are often evaluated by how many times they
+//! triggered. A [PulseCounter] counts edges of an [IrqInputGPIO](super::IrqInputGPIO) in its
+//! interrupt, and is read out from a thread, typically in fixed intervals:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gpio::{GPIO, InputMode};
+//! use riot_wrappers::gpio::pulse_counter::{Edge, PulseCounter};
+//! use riot_wrappers::ztimer::Clock;
+//!
+//! # let pin: GPIO = unimplemented!();
+//! let pin = pin.configure_as_irq_input(InputMode::InPullUp).unwrap();
+//! let counter = PulseCounter::new(pin, Edge::Falling);
+//! pin_utils::pin_mut!(counter);
+//! counter.start();
+//! loop {
+//!     Clock::msec().sleep_ticks(10_000);
+//!     let (pulses, elapsed) = counter.take_interval();
+//!     // eg. 1000 pulses per kWh: power in W is pulses * 3600_000 / elapsed.0
+//! }
+//! ```
+//!
+//! The counter does not debounce; for mechanical contacts, a hardware filter is required.
+
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use super::IrqInputGPIO;
+use crate::error::NegativeErrorExt;
+
+/// Which edges of the signal are counted as pulses
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Edge {
+    Rising,
+    Falling,
+    /// Both edges are counted, so each full pulse counts twice
+    Both,
+}
+
+impl Edge {
+    fn to_c(self) -> riot_sys::gpio_flank_t {
+        match self {
+            Edge::Rising => riot_sys::gpio_flank_t_GPIO_RISING,
+            Edge::Falling => riot_sys::gpio_flank_t_GPIO_FALLING,
+            Edge::Both => riot_sys::gpio_flank_t_GPIO_BOTH,
+        }
+    }
+}
+
+/// A counter of edges on a GPIO pin
+///
+/// As the pin's interrupt refers to it, the counter is used in pinned form; the interrupt is
+/// disabled when it is dropped.
+pub struct PulseCounter {
+    pin: IrqInputGPIO,
+    edge: Edge,
+    // Only ever stored to by the interrupt, and by the thread in critical sections; this only
+    // needs load and store, which are available on all platforms.
+    count: AtomicU32,
+    started: bool,
+    #[cfg(riot_module_ztimer_msec)]
+    last_take: Option<crate::ztimer::Ticks<1000>>,
+    _pinned: PhantomPinned,
+}
+
+impl PulseCounter {
+    pub fn new(pin: IrqInputGPIO, edge: Edge) -> Self {
+        PulseCounter {
+            pin,
+            edge,
+            count: AtomicU32::new(0),
+            started: false,
+            #[cfg(riot_module_ztimer_msec)]
+            last_take: None,
+            _pinned: PhantomPinned,
+        }
+    }
+
+    extern "C" fn pulse(arg: *mut riot_sys::libc::c_void) {
+        // unsafe: Set up to point to the pinned counter, which disables the interrupt before it
+        // goes away
+        let count = unsafe { &*(arg as *const AtomicU32) };
+        count.store(
+            count.load(Ordering::Relaxed).wrapping_add(1),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Start counting
+    #[doc(alias = "gpio_init_int")]
+    pub fn start(self: &mut Pin<&mut Self>) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        if s.started {
+            return;
+        }
+        // unsafe: OK per C API; the argument stays valid as the counter is pinned, and the
+        // interrupt is disabled before it is dropped.
+        unsafe {
+            riot_sys::gpio_init_int(
+                s.pin.to_c(),
+                s.pin.mode(),
+                s.edge.to_c(),
+                Some(Self::pulse),
+                &s.count as *const AtomicU32 as *mut _,
+            )
+        }
+        .negative_to_error()
+        .expect("Pin was already successfully configured for interrupts");
+        s.started = true;
+        #[cfg(riot_module_ztimer_msec)]
+        {
+            s.last_take = Some(crate::ztimer::Clock::msec().now());
+        }
+    }
+
+    /// Number of pulses counted since the counter was started or last [taken](Self::take)
+    ///
+    /// The count wraps around at the end of the u32 range.
+    pub fn count(&self) -> u32 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Return the number of pulses, and reset the count
+    pub fn take(&self) -> u32 {
+        crate::interrupt::free(|_| {
+            let count = self.count.load(Ordering::Relaxed);
+            self.count.store(0, Ordering::Relaxed);
+            count
+        })
+    }
+
+    /// Return the number of pulses along with the time over which they were counted, and reset
+    /// the count
+    ///
+    /// The time is measured from the last time this was called (or the counter was started) on
+    /// the millisecond clock. If [take](Self::take) is used in between, the pulses taken there are
+    /// missing from the count.
+    #[cfg(riot_module_ztimer_msec)]
+    pub fn take_interval(self: &mut Pin<&mut Self>) -> (u32, crate::ztimer::Ticks<1000>) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        let (count, now) = crate::interrupt::free(|_| {
+            let count = s.count.load(Ordering::Relaxed);
+            s.count.store(0, Ordering::Relaxed);
+            (count, crate::ztimer::Clock::msec().now())
+        });
+        let elapsed = match s.last_take.replace(now) {
+            Some(last) => now.0.wrapping_sub(last.0),
+            None => 0,
+        };
+        (count, crate::ztimer::Ticks(elapsed))
+    }
+}
+
+impl Drop for PulseCounter {
+    fn drop(&mut self) {
+        if self.started {
+            // unsafe: OK per C API
+            unsafe { riot_sys::gpio_irq_disable(self.pin.to_c()) };
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Implementation of [embedded_hal_async::digital::Wait] for [IrqInputGPIO]
+//!
+//! The pin was already configured with `gpio_init_int` when it was created, which fails on pins
+//! without interrupt support. Each wait then reconfigures the pin for the requested flank, pointing the
+//! interrupt at state inside the (pinned) future. The interrupt is disabled again when the edge
+//! was seen or the future is dropped, so no interrupt can ever reference a stale future.
+
+use core::cell::UnsafeCell;
+use core::future::Future;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use embedded_hal_1::digital::InputPin;
+use riot_sys::gpio_flank_t;
+
+use super::IrqInputGPIO;
+use crate::error::NegativeErrorExt;
+
+/// State shared between an [EdgeFuture] and the interrupt
+struct Shared {
+    fired: bool,
+    waker: Option<Waker>,
+}
+
+/// Future that completes at the next edge of the given flank on a pin
+struct EdgeFuture<'a> {
+    pin: &'a mut IrqInputGPIO,
+    flank: gpio_flank_t,
+    /// If set, the future also completes when the pin is at that level (high if true) after the
+    /// interrupt is set up; this avoids missing an edge that happens right before.
+    until_level: Option<bool>,
+    started: bool,
+    shared: UnsafeCell<Shared>,
+    _pinned: PhantomPinned,
+}
+
+impl<'a> EdgeFuture<'a> {
+    fn new(pin: &'a mut IrqInputGPIO, flank: gpio_flank_t) -> Self {
+        Self::with_level(pin, flank, None)
+    }
+
+    fn with_level(
+        pin: &'a mut IrqInputGPIO,
+        flank: gpio_flank_t,
+        until_level: Option<bool>,
+    ) -> Self {
+        Self {
+            pin,
+            flank,
+            until_level,
+            started: false,
+            shared: UnsafeCell::new(Shared {
+                fired: false,
+                waker: None,
+            }),
+            _pinned: PhantomPinned,
+        }
+    }
+
+    fn until_level(pin: &'a mut IrqInputGPIO, high: bool) -> Self {
+        let flank = if high {
+            riot_sys::gpio_flank_t_GPIO_RISING
+        } else {
+            riot_sys::gpio_flank_t_GPIO_FALLING
+        };
+        Self::with_level(pin, flank, Some(high))
+    }
+}
+
+extern "C" fn callback(arg: *mut riot_sys::libc::c_void) {
+    // unsafe: Set up to point to the pinned shared state of a future that disables the interrupt
+    // before it goes away. We're in the ISR, so the thread is not accessing it concurrently.
+    let shared = unsafe { &mut *(arg as *mut Shared) };
+    shared.fired = true;
+    if let Some(waker) = shared.waker.take() {
+        waker.wake();
+    }
+}
+
+impl<'a> Future for EdgeFuture<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // unsafe: Nothing is moved out of self.
+        let this = unsafe { self.get_unchecked_mut() };
+        let gpio = this.pin.to_c();
+
+        crate::interrupt::free(|_| {
+            // unsafe: Interrupts are off, so the callback does not run concurrently.
+            let shared = unsafe { &mut *this.shared.get() };
+            if shared.fired {
+                // unsafe: OK per C API
+                unsafe { riot_sys::gpio_irq_disable(gpio) };
+                return Poll::Ready(());
+            }
+            shared.waker = Some(cx.waker().clone());
+
+            if !this.started {
+                // unsafe: OK per C API; the argument stays valid as the future is pinned, and
+                // the interrupt is disabled before the future is dropped.
+                unsafe {
+                    riot_sys::gpio_init_int(
+                        gpio,
+                        this.pin.mode(),
+                        this.flank,
+                        Some(callback),
+                        this.shared.get() as *mut _,
+                    )
+                }
+                .negative_to_error()
+                .expect("Pin was already successfully configured for interrupts");
+                this.started = true;
+
+                if let Some(high) = this.until_level {
+                    // unsafe: OK per C API
+                    if (unsafe { riot_sys::gpio_read(gpio) } != 0) == high {
+                        // unsafe: OK per C API
+                        unsafe { riot_sys::gpio_irq_disable(gpio) };
+                        return Poll::Ready(());
+                    }
+                }
+            }
+
+            Poll::Pending
+        })
+    }
+}
+
+impl<'a> Drop for EdgeFuture<'a> {
+    fn drop(&mut self) {
+        if self.started {
+            // unsafe: OK per C API
+            unsafe { riot_sys::gpio_irq_disable(self.pin.to_c()) };
+        }
+    }
+}
+
+impl embedded_hal_async::digital::Wait for IrqInputGPIO {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        if self.is_low()? {
+            EdgeFuture::until_level(self, true).await;
+        }
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        if self.is_high()? {
+            EdgeFuture::until_level(self, false).await;
+        }
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        EdgeFuture::new(self, riot_sys::gpio_flank_t_GPIO_RISING).await;
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        EdgeFuture::new(self, riot_sys::gpio_flank_t_GPIO_FALLING).await;
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        EdgeFuture::new(self, riot_sys::gpio_flank_t_GPIO_BOTH).await;
+        Ok(())
+    }
+}
@@ -24,7 +24,56 @@ impl I2CDevice {
     }
 }
 
+/// Set of 7-bit addresses at which devices responded in a [scan]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Addresses([u8; 16]);
+
+impl Addresses {
+    /// Whether a device responded at the given address
+    pub fn contains(&self, address: u8) -> bool {
+        address < 0x80 && self.0[address as usize / 8] & (1 << (address % 8)) != 0
+    }
+
+    /// Iterate over all addresses at which a device responded, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..0x80).filter(|a| self.contains(*a))
+    }
+
+    /// Number of responding devices
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    fn insert(&mut self, address: u8) {
+        self.0[address as usize / 8] |= 1 << (address % 8);
+    }
+}
+
+/// Probe all non-reserved 7-bit addresses (0x08 to 0x77) on the bus for responding devices
+///
+/// Each address is probed by reading a single byte; devices that acknowledge their address are
+/// reported. The bus is acquired for the whole duration of the scan.
+pub fn scan(bus: &mut I2CDevice) -> Addresses {
+    let mut found = Addresses::default();
+    // unsafe: OK per C API
+    unsafe { i2c_acquire(bus.dev) };
+    for address in 0x08..0x78 {
+        let mut byte = 0;
+        // unsafe: OK per C API
+        let err = unsafe {
+            riot_sys::i2c_read_byte(bus.dev, address as u16, &mut byte as *mut u8 as *mut _, 0)
+        };
+        if err == 0 {
+            found.insert(address);
+        }
+    }
+    // unsafe: OK per C API
+    unsafe { i2c_release(bus.dev) };
+    found
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Error {
     AcquireError,
@@ -32,6 +81,31 @@ pub enum Error {
     ReadError(i32),
 }
 
+#[cfg(feature = "with_embedded_hal_1")]
+impl embedded_hal_1::i2c::Error for Error {
+    fn kind(&self) -> embedded_hal_1::i2c::ErrorKind {
+        use crate::error::{Errno, NumericError};
+        use embedded_hal_1::i2c::{ErrorKind, NoAcknowledgeSource};
+
+        let number = match self {
+            Error::AcquireError => return ErrorKind::Bus,
+            Error::WriteError(n) | Error::ReadError(n) => *n,
+        };
+        // Mapping as per the error documentation of i2c_read_bytes / i2c_write_bytes
+        match (NumericError {
+            number: number as _,
+        })
+        .errno()
+        {
+            Some(Errno::NxIo) => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            Some(Errno::Io) => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            Some(Errno::Again) => ErrorKind::ArbitrationLoss,
+            Some(Errno::TimedOut) => ErrorKind::Bus,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
 use riot_sys::libc;
 use riot_sys::{i2c_acquire, i2c_read_bytes, i2c_release, i2c_write_bytes};
 
@@ -121,3 +195,73 @@ impl blocking::i2c::Read for I2CDevice {
         Ok(())
     }
 }
+
+#[cfg(feature = "with_embedded_hal_1")]
+impl embedded_hal_1::i2c::ErrorType for I2CDevice {
+    type Error = Error;
+}
+
+#[cfg(feature = "with_embedded_hal_1")]
+impl embedded_hal_1::i2c::I2c for I2CDevice {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [embedded_hal_1::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        use embedded_hal_1::i2c::Operation;
+
+        const NOSTOP: u8 = riot_sys::i2c_flags_t_I2C_NOSTOP as _;
+        const NOSTART: u8 = riot_sys::i2c_flags_t_I2C_NOSTART as _;
+
+        unsafe { i2c_acquire(self.dev) };
+
+        let count = operations.len();
+        let mut previous_was_read = None;
+        let mut result = Ok(());
+        for (i, operation) in operations.iter_mut().enumerate() {
+            let is_read = matches!(operation, Operation::Read(_));
+            let mut flags = 0;
+            // Only the last operation ends with a stop condition ...
+            if i + 1 != count {
+                flags |= NOSTOP;
+            }
+            // ... and a start condition is only needed when changing direction
+            if previous_was_read == Some(is_read) {
+                flags |= NOSTART;
+            }
+            previous_was_read = Some(is_read);
+
+            let err = match operation {
+                Operation::Read(buffer) => unsafe {
+                    i2c_read_bytes(
+                        self.dev,
+                        address as u16,
+                        buffer.as_mut_ptr() as *mut libc::c_void,
+                        buffer.len() as _,
+                        flags,
+                    )
+                },
+                Operation::Write(bytes) => unsafe {
+                    i2c_write_bytes(
+                        self.dev,
+                        address as u16,
+                        bytes.as_ptr() as *const libc::c_void,
+                        bytes.len() as _,
+                        flags,
+                    )
+                },
+            };
+            if err != 0 {
+                result = Err(if is_read {
+                    Error::ReadError(err)
+                } else {
+                    Error::WriteError(err)
+                });
+                break;
+            }
+        }
+
+        unsafe { i2c_release(self.dev) };
+        result
+    }
+}
@@ -0,0 +1,80 @@
+//! Allocation of identifiers that are unique within the running system
+//!
+//! Protocols frequently need numbers that differ between requests, eg. CoAP message IDs and
+//! tokens or MQTT packet identifiers. An [IdAllocator] hands out such numbers from a counter that
+//! is advanced atomically (using RIOT's [atomic utilities](crate::atomic_utils)), so it can be
+//! shared between threads and interrupts without locking, also on platforms that lack atomic
+//! read-modify-write instructions:
+//!
+//! ```no_run
+//! # #![no_std]
+//! use riot_wrappers::ids::IdAllocator;
+//!
+//! static MESSAGE_IDS: IdAllocator<u16> = IdAllocator::new(0);
+//!
+//! let first = MESSAGE_IDS.next();
+//! let second = MESSAGE_IDS.next();
+//! assert!(first != second);
+//! ```
+//!
+//! Identifiers wrap around after all values of the type were handed out.
+
+use crate::atomic_utils::{AtomicU16, AtomicU32};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Integer types an [IdAllocator] can hand out
+///
+/// This is implemented for the types for which [atomic_utils](crate::atomic_utils) provides
+/// atomic types.
+pub trait Id: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    type Atomic;
+}
+
+/// A counter that hands out a different number on every call to [next](Self::next)
+///
+/// This is usually placed in a static, and can be used from any thread and from interrupts.
+pub struct IdAllocator<T: Id>(T::Atomic);
+
+macro_rules! id_type {
+    ($t:ty, $atomic:ident) => {
+        impl sealed::Sealed for $t {}
+
+        impl Id for $t {
+            type Atomic = $atomic;
+        }
+
+        impl IdAllocator<$t> {
+            /// Create an allocator whose first identifier is `first`
+            pub const fn new(first: $t) -> Self {
+                IdAllocator($atomic::new(first))
+            }
+
+            /// Produce the next identifier
+            pub fn next(&self) -> $t {
+                self.0.fetch_add(1)
+            }
+
+            /// Continue handing out identifiers from `next`
+            ///
+            /// This is typically used once at startup to start from a random value (eg. from
+            /// [`random_uint32`](https://doc.riot-os.org/group__sys__random.html)), making it
+            /// unlikely that identifiers are reused across reboots.
+            pub fn reset(&self, next: $t) {
+                self.0.store(next)
+            }
+        }
+    };
+}
+
+id_type!(u16, AtomicU16);
+id_type!(u32, AtomicU32);
+
+impl<T: Id> core::fmt::Debug for IdAllocator<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("IdAllocator { .. }")
+    }
+}
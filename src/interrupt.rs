@@ -78,6 +78,111 @@ pub fn free<R, F: FnOnce(&CriticalSection) -> R>(f: F) -> R {
     ret
 }
 
+/// A value shared between interrupt handlers and threads, such as a peripheral handle
+///
+/// The cell starts out empty and is [initialized](IsrCell::init) once (typically by the thread
+/// that sets up the interrupt); afterwards, the value is accessed through closures, either in an
+/// interrupt (proven by an [`InIsr`](crate::thread::InIsr) token), or inside a critical section.
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::interrupt::IsrCell;
+/// # use riot_wrappers::thread::InIsr;
+/// struct Counter(u32);
+/// static PRESSES: IsrCell<Counter> = IsrCell::new();
+///
+/// // in the main thread
+/// PRESSES.init(Counter(0)).ok();
+///
+/// // in a GPIO callback
+/// if let Ok(in_isr) = InIsr::new() {
+///     PRESSES.with_isr(in_isr, |c| c.0 += 1);
+/// }
+///
+/// // back in a thread
+/// let presses = PRESSES.with(|c| c.0);
+/// ```
+///
+/// Accesses that overlap (because an interrupt preempted another one that is accessing the
+/// value, or because the closure tries to access the cell again) do not run the closure, and
+/// return None just as accesses to an uninitialized cell do. This relies on RIOT running on a
+/// single core.
+pub struct IsrCell<T> {
+    data: core::cell::UnsafeCell<Option<T>>,
+    borrowed: core::cell::Cell<bool>,
+}
+
+// unsafe: All accesses go through the borrowed flag, which is only changed in contexts that can
+// not be preempted by anything else that accesses it (inside an ISR that any preempting ISR runs
+// to completion in, or with interrupts disabled).
+unsafe impl<T: Send> Sync for IsrCell<T> {}
+
+impl<T> IsrCell<T> {
+    /// Create an empty cell
+    pub const fn new() -> Self {
+        IsrCell {
+            data: core::cell::UnsafeCell::new(None),
+            borrowed: core::cell::Cell::new(false),
+        }
+    }
+
+    /// Store a value in the cell
+    ///
+    /// If the cell was already initialized (or is being accessed right now), the value is
+    /// returned.
+    pub fn init(&self, value: T) -> Result<(), T> {
+        free(|_| {
+            if self.borrowed.get() {
+                return Err(value);
+            }
+            // unsafe: Not borrowed, and interrupts are off
+            let data = unsafe { &mut *self.data.get() };
+            if data.is_some() {
+                return Err(value);
+            }
+            *data = Some(value);
+            Ok(())
+        })
+    }
+
+    /// Access the value from inside an interrupt
+    ///
+    /// Returns None if the cell is not initialized or already being accessed.
+    pub fn with_isr<R>(
+        &self,
+        _in_isr: crate::thread::InIsr,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        self.access(f)
+    }
+
+    /// Access the value inside a critical section
+    ///
+    /// Returns None if the cell is not initialized or already being accessed.
+    pub fn with_cs<R>(&self, _cs: &CriticalSection, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.access(f)
+    }
+
+    /// Access the value with interrupts disabled for the duration of the closure
+    ///
+    /// Returns None if the cell is not initialized or already being accessed.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        free(|cs| self.with_cs(cs, f))
+    }
+
+    /// Common implementation for all accesses, whose callers ensure that they can not be
+    /// preempted by other accesses except for ones that complete before they return
+    fn access<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        if self.borrowed.replace(true) {
+            return None;
+        }
+        // unsafe: Access is exclusive through the borrowed flag
+        let result = unsafe { &mut *self.data.get() }.as_mut().map(f);
+        self.borrowed.set(false);
+        result
+    }
+}
+
 /// Wrap a Rust interrupt handler in an extern "C" wrapper that does the post-return cleaups.
 ///
 /// As with all code executed in interrupt contexts, the wrapped function should not panic.
@@ -34,6 +34,10 @@ pub use riot_sys;
 /// Re-exporting the cstr macro module because our macros in [shell] use it.
 pub use cstr;
 
+pub mod assert;
+pub mod config;
+pub mod core_panic;
+pub mod cstr_helpers;
 pub mod error;
 
 mod helpers;
@@ -98,6 +102,8 @@ unsafe fn inline_cast_ref_mut<A, B>(input: &mut A) -> &mut B {
 pub mod saul;
 #[cfg(riot_module_shell)]
 pub mod shell;
+#[cfg(riot_module_registry)]
+pub mod registry;
 pub mod stdio;
 pub mod thread;
 // internally cfg-gated as it has a no-op implementation
@@ -107,6 +113,8 @@ pub mod gcoap;
 pub mod gnrc;
 #[cfg(riot_module_gnrc)]
 pub mod gnrc_util;
+#[cfg(riot_module_eui_provider)]
+pub mod eui_provider;
 #[cfg(riot_module_periph_i2c)]
 pub mod i2c;
 #[cfg(riot_module_core_msg)]
@@ -118,18 +126,51 @@ pub mod spi;
 #[cfg(riot_module_periph_adc)]
 pub mod adc;
 
+#[cfg(riot_module_periph_uart)]
+pub mod uart;
+
 #[cfg(riot_module_periph_dac)]
 pub mod dac;
 
+#[cfg(riot_module_servo)]
+pub mod servo;
+
+#[cfg(riot_module_periph_pio)]
+pub mod pio;
+
+#[cfg(all(riot_module_periph_rtc_mem, riot_module_checksum))]
+pub mod rtc_mem;
+
+#[cfg(riot_module_periph_ptp)]
+pub mod ptp;
+
+// internally cfg-gated, as both its parts are optional
+pub mod pm;
+
 #[cfg(riot_module_ztimer)]
 pub mod ztimer;
 
+#[cfg(riot_module_trickle)]
+pub mod trickle;
+
+#[cfg(all(riot_module_saul, riot_module_ztimer))]
+pub mod duty_cycle;
+
 pub mod mutex;
 #[cfg(riot_module_pthread)]
 pub mod rwlock;
+#[cfg(riot_module_core_mbox)]
+pub mod mbox;
+
+// not cfg-gated, as it is implemented in Rust
+pub mod crypto;
+#[cfg(riot_module_atomic_utils)]
+pub mod atomic_utils;
+#[cfg(riot_module_atomic_utils)]
+pub mod ids;
 
 #[cfg(feature = "set_panic_handler")]
-mod panic;
+pub mod panic;
 
 #[cfg(feature = "with_coap_handler")]
 pub mod coap_handler;
@@ -143,6 +184,9 @@ pub mod socket_embedded_nal;
 #[cfg(all(riot_module_sock_tcp, feature = "with_embedded_nal"))]
 pub mod socket_embedded_nal_tcp;
 
+#[cfg(any(riot_module_sock_dns, riot_module_dns_cache))]
+pub mod dns;
+
 #[cfg(riot_module_periph_gpio)]
 pub mod gpio;
 
@@ -152,22 +196,48 @@ pub mod bluetil;
 pub mod nimble {
     #[cfg(riot_module_nimble_host)]
     pub mod uuid;
+    #[cfg(riot_module_nimble_scanner)]
+    pub mod scanner;
+    #[cfg(riot_module_nimble_netif)]
+    pub mod netif;
 }
 
+pub mod color;
 #[cfg(riot_module_ws281x)]
 pub mod ws281x;
 
 #[cfg(riot_module_microbit)]
 pub mod microbit;
 
+#[cfg(riot_module_disp_dev)]
+pub mod disp_dev;
+
+#[cfg(riot_module_mtd)]
+pub mod mtd;
+
+#[cfg(riot_module_periph_sdmmc)]
+pub mod sdmmc;
+
 #[cfg(riot_module_vfs)]
 pub mod vfs;
 
+#[cfg(riot_module_suit_worker)]
+pub mod suit;
+
 pub mod interrupt;
 #[path = "main_module.rs"]
 pub mod main;
+pub mod testing;
 
 pub mod led;
 
 #[cfg(riot_module_auto_init)]
 pub mod auto_init;
+
+#[cfg(riot_module_trace)]
+pub mod trace;
+
+#[cfg(all(feature = "with_embassy", riot_module_core_thread_flags))]
+pub mod embassy;
+#[cfg(riot_module_core_thread_flags)]
+pub mod offload;
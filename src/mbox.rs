@@ -0,0 +1,181 @@
+//! Fixed-size [mailboxes](https://doc.riot-os.org/group__core__mbox.html) between threads
+//!
+//! An [MBox] is a queue of small values that any number of threads can put values into and take
+//! them from; unlike with messages, the values are not addressed to a particular thread. The
+//! queue memory is part of the mailbox, which is typically a static:
+//!
+//! ```no_run
+//! # #![no_std]
+//! use riot_wrappers::mbox::MBox;
+//!
+//! static READINGS: MBox<u16, 8> = MBox::new();
+//!
+//! // In a sensor thread
+//! READINGS.put(1234);
+//!
+//! // In a processing thread
+//! let reading = READINGS.get();
+//! ```
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+/// Value of the `blocking` argument to `_mbox_put` and `_mbox_get`
+const NON_BLOCKING: i32 = 0;
+/// Value of the `blocking` argument to `_mbox_put` and `_mbox_get`
+const BLOCKING: i32 = 1;
+
+/// A mailbox holding up to `N` values of type `T`
+///
+/// The values need to be small enough to fit in a message's content (typically 4 bytes, checked
+/// at build time), and `N` needs to be a power of two.
+///
+/// As the mailbox refers to its own queue, it can only be used as a `'static`.
+pub struct MBox<T: Copy + Send, const N: usize> {
+    /// Only accessed after `initialized` was set, which happens in `mbox()`
+    mbox: UnsafeCell<MaybeUninit<riot_sys::mbox_t>>,
+    /// Only accessed through the C mbox
+    queue: UnsafeCell<MaybeUninit<[riot_sys::msg_t; N]>>,
+    /// Only accessed in critical sections
+    initialized: UnsafeCell<bool>,
+    _phantom: PhantomData<T>,
+}
+
+// All access to the mailbox goes through the C functions (or critical sections), and values are
+// only moved through it between threads.
+unsafe impl<T: Copy + Send, const N: usize> Sync for MBox<T, N> {}
+
+impl<T: Copy + Send, const N: usize> MBox<T, N> {
+    /// Evaluated on use, making unsuitable types and sizes a build time error
+    const FITS: () = {
+        assert!(
+            core::mem::size_of::<T>() <= core::mem::size_of::<riot_sys::msg_t__bindgen_ty_1>()
+                && core::mem::align_of::<T>()
+                    <= core::mem::align_of::<riot_sys::msg_t__bindgen_ty_1>(),
+            "Type can not be transported in a message"
+        );
+        assert!(
+            N.is_power_of_two(),
+            "Mailbox size needs to be a power of two"
+        );
+    };
+
+    /// Create an empty mailbox
+    pub const fn new() -> Self {
+        let () = Self::FITS;
+        MBox {
+            mbox: UnsafeCell::new(MaybeUninit::uninit()),
+            queue: UnsafeCell::new(MaybeUninit::uninit()),
+            initialized: UnsafeCell::new(false),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the C mailbox, initializing it at first use
+    fn mbox(&'static self) -> *mut riot_sys::mbox_t {
+        crate::interrupt::free(|_| {
+            // unsafe: Only accessed in critical sections
+            let initialized = unsafe { &mut *self.initialized.get() };
+            if !*initialized {
+                // unsafe: OK per C API; mbox_init fully initializes the mailbox, and the queue
+                // stays in place as self is 'static.
+                unsafe {
+                    riot_sys::inline::mbox_init(
+                        crate::inline_cast_mut(self.mbox.get() as *mut riot_sys::mbox_t),
+                        crate::inline_cast_mut(self.queue.get() as *mut riot_sys::msg_t),
+                        N as _,
+                    )
+                };
+                *initialized = true;
+            }
+        });
+        self.mbox.get() as *mut _
+    }
+
+    fn put_inner(&'static self, value: T, blocking: i32) -> Result<(), T> {
+        let mut msg = riot_sys::msg_t::default();
+        // unsafe: Size and alignment were checked in FITS
+        unsafe { core::ptr::write(&mut msg.content as *mut _ as *mut T, value) };
+        // unsafe: OK per C API
+        match unsafe { riot_sys::_mbox_put(self.mbox(), &mut msg, blocking) } {
+            1 => Ok(()),
+            _ => Err(value),
+        }
+    }
+
+    fn get_inner(&'static self, blocking: i32) -> Option<T> {
+        let mut msg = MaybeUninit::uninit();
+        // unsafe: OK per C API
+        match unsafe { riot_sys::_mbox_get(self.mbox(), msg.as_mut_ptr(), blocking) } {
+            1 => {
+                // unsafe: Initialized by _mbox_get, and only put_inner placed values in there.
+                let msg: riot_sys::msg_t = unsafe { msg.assume_init() };
+                Some(unsafe { core::ptr::read(&msg.content as *const _ as *const T) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Put a value into the mailbox, blocking while it is full
+    ///
+    /// If a thread is waiting in [get](Self::get), the value is handed to it directly.
+    ///
+    /// ## Panics
+    ///
+    /// This panics when called in an interrupt context; use [try_put](Self::try_put) there.
+    #[doc(alias = "mbox_put")]
+    pub fn put(&'static self, value: T) {
+        crate::thread::InThread::new().expect("Blocking put is only possible in threads");
+        let _ = self.put_inner(value, BLOCKING);
+    }
+
+    /// Put a value into the mailbox if there is space, or return it
+    ///
+    /// This can be called from any thread, and from interrupts.
+    #[doc(alias = "mbox_try_put")]
+    pub fn try_put(&'static self, value: T) -> Result<(), T> {
+        self.put_inner(value, NON_BLOCKING)
+    }
+
+    /// Take a value out of the mailbox, blocking while it is empty
+    ///
+    /// ## Panics
+    ///
+    /// This panics when called in an interrupt context; use [try_get](Self::try_get) there.
+    #[doc(alias = "mbox_get")]
+    pub fn get(&'static self) -> T {
+        crate::thread::InThread::new().expect("Blocking get is only possible in threads");
+        self.get_inner(BLOCKING)
+            .expect("Blocking mbox_get always produces a value")
+    }
+
+    /// Take a value out of the mailbox if one is available
+    #[doc(alias = "mbox_try_get")]
+    pub fn try_get(&'static self) -> Option<T> {
+        self.get_inner(NON_BLOCKING)
+    }
+
+    /// Number of values currently in the mailbox
+    #[doc(alias = "mbox_avail")]
+    pub fn len(&'static self) -> usize {
+        // unsafe: OK per C API
+        unsafe { riot_sys::inline::mbox_avail(crate::inline_cast_mut(self.mbox())) as usize }
+    }
+
+    /// True if no values are in the mailbox
+    pub fn is_empty(&'static self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of values the mailbox can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T: Copy + Send, const N: usize> Default for MBox<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
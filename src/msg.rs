@@ -20,6 +20,7 @@ pub mod v2;
 // KernelPID has a valid range from KERNEL_PID_FIRST to KERNEL_PID_LAST and have it use that
 // knowledge.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MsgSender {
     Invalid, // = riot_sys::KERNEL_PID_UNDEF
     Thread(KernelPID),
@@ -39,6 +40,7 @@ impl MsgSender {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MsgSendError {
     ReceiverNotWaiting,
     InvalidPID,
@@ -97,6 +99,41 @@ where
     }
 }
 
+/// Send a message from interrupt context
+///
+/// Sending from an interrupt never blocks: If the target thread is neither waiting for a message
+/// nor has space in its message queue, the message is lost, and
+/// [ReceiverNotWaiting](MsgSendError::ReceiverNotWaiting) is returned. Messages sent this way
+/// are seen by the recipient as coming from [MsgSender::ISR].
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::msg::{send_from_isr, NumericMsg};
+/// # use riot_wrappers::thread::{InIsr, KernelPID};
+/// # let worker: KernelPID = unimplemented!();
+/// # const BUTTON_PRESSED: u16 = 1;
+/// // in a GPIO interrupt callback:
+/// if let Ok(in_isr) = InIsr::new() {
+///     if send_from_isr(in_isr, &worker, NumericMsg::new(BUTTON_PRESSED, 0)).is_err() {
+///         // The worker is lagging behind; the press is dropped.
+///     }
+/// }
+/// ```
+#[doc(alias = "msg_send_int")]
+pub fn send_from_isr(
+    _in_isr: crate::thread::InIsr,
+    target: &KernelPID,
+    msg: impl WrapsMsgT,
+) -> Result<(), MsgSendError> {
+    let mut m = msg.extract();
+    // unsafe: OK per C API; the InIsr token guarantees the required context.
+    match unsafe { riot_sys::msg_send_int(&mut m, target.into()) } {
+        1 => Ok(()),
+        0 => Err(MsgSendError::ReceiverNotWaiting),
+        _ => Err(MsgSendError::InvalidPID),
+    }
+}
+
 /// An initialized message with inaccessible value.
 pub struct OpaqueMsg(msg_t);
 
@@ -108,6 +145,63 @@ impl OpaqueMsg {
     }
 }
 
+/// Receiving end of the current thread's message queue
+///
+/// This is obtained from [TokenParts::message_queue()](crate::thread::TokenParts::message_queue)
+/// after the queue was set up with
+/// [TokenParts::with_message_queue()](crate::thread::TokenParts::with_message_queue), and is
+/// tied to the thread that set it up.
+pub struct QueueReceiver<'a> {
+    _phantom: PhantomData<&'a ()>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<'a> QueueReceiver<'a> {
+    /// Create a receiver
+    ///
+    /// ## Safety
+    ///
+    /// The current thread must have a message queue for the lifetime of the receiver.
+    pub(crate) unsafe fn new() -> Self {
+        QueueReceiver {
+            _phantom: PhantomData,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Take the next message out of the queue, blocking until one arrives
+    #[doc(alias = "msg_receive")]
+    pub fn receive(&self) -> OpaqueMsg {
+        OpaqueMsg::receive()
+    }
+
+    /// Take the next message out of the queue if there is one
+    #[doc(alias = "msg_try_receive")]
+    pub fn try_receive(&self) -> Option<OpaqueMsg> {
+        let mut m: MaybeUninit<msg_t> = MaybeUninit::uninit();
+        // unsafe: OK per C API
+        match unsafe { riot_sys::msg_try_receive(m.as_mut_ptr()) } {
+            // unsafe: Initialized by msg_try_receive
+            1 => Some(OpaqueMsg(unsafe { m.assume_init() })),
+            _ => None,
+        }
+    }
+
+    /// Number of messages waiting in the queue
+    #[doc(alias = "msg_avail")]
+    pub fn len(&self) -> usize {
+        // unsafe: OK per C API
+        let avail = unsafe { riot_sys::msg_avail() };
+        // Only negative if there is no queue, which is excluded by construction
+        avail.max(0) as usize
+    }
+
+    /// True if no messages are waiting in the queue
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 impl ::core::fmt::Debug for OpaqueMsg {
     fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
         formatter
@@ -225,3 +319,214 @@ where
         }
     }
 }
+
+/// A Rust type that can be sent through a [Channel]
+///
+/// Implementing this assigns the type a message type number, by which received messages are
+/// turned back into values of the type.
+///
+/// ## Safety
+///
+/// The type number must not be used for any other message content that may be sent to a thread
+/// that receives through a [Channel] of this type; in particular, it must be unique among the
+/// implementations of this trait, and distinct from the message types other (eg. C) components
+/// send. As the value is transported by copying its bytes, the type must also be valid when
+/// copied bytewise (which is implied by `Copy`) and must not contain references to data that
+/// does not live as long as the message is in transit.
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::msg::ChannelMessage;
+/// #[derive(Copy, Clone)]
+/// struct Measurement {
+///     sensor: u8,
+///     value: i16,
+/// }
+///
+/// // unsafe: 0x3100 is not used for any other message in this application
+/// unsafe impl ChannelMessage for Measurement {
+///     const TYPE: u16 = 0x3100;
+/// }
+/// ```
+pub unsafe trait ChannelMessage: Copy + Send {
+    /// Message type number used for this type
+    const TYPE: u16;
+}
+
+/// Error returned by [Channel::receive] and [Channel::receive_with_timeout]
+#[derive(Debug)]
+pub enum ReceiveError {
+    /// A message of a different type arrived; it is passed on for processing
+    Other(OpaqueMsg),
+    /// No message arrived in time
+    Timeout,
+}
+
+/// A typed view of the messages of a single type sent to a thread
+///
+/// Values are copied into the message itself (so they need to fit in the `content` of a
+/// `msg_t`, which is checked at build time); this avoids handling raw `msg_t`s on either end:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::msg::{Channel, ChannelMessage};
+/// # use riot_wrappers::thread::KernelPID;
+/// # #[derive(Copy, Clone)]
+/// # struct Measurement(i16);
+/// # unsafe impl ChannelMessage for Measurement { const TYPE: u16 = 0x3100; }
+/// # let worker: KernelPID = unimplemented!();
+/// // In a sensor thread
+/// let to_worker = Channel::<Measurement>::new(worker);
+/// to_worker.send(Measurement(42)).unwrap();
+///
+/// // In the worker thread
+/// let incoming = Channel::<Measurement>::new(riot_wrappers::thread::get_pid());
+/// if let Ok(Measurement(value)) = incoming.receive() {
+///     // ...
+/// }
+/// ```
+///
+/// Like the rest of this module, this relies on all senders of the type number to send values of
+/// the indicated type, which implementers of [ChannelMessage] guarantee; the [v2] module
+/// provides stronger guarantees at the cost of more setup.
+pub struct Channel<T: ChannelMessage> {
+    destination: KernelPID,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: ChannelMessage> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ChannelMessage> Copy for Channel<T> {}
+
+impl<T: ChannelMessage> ::core::fmt::Debug for Channel<T> {
+    fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        formatter
+            .debug_struct("Channel")
+            .field("type", &core::any::type_name::<T>())
+            .field("destination", &self.destination)
+            .finish()
+    }
+}
+
+impl<T: ChannelMessage> Channel<T> {
+    /// Evaluated on use, making oversized types a build time error
+    const FITS: () = assert!(
+        core::mem::size_of::<T>() <= core::mem::size_of::<riot_sys::msg_t__bindgen_ty_1>()
+            && core::mem::align_of::<T>() <= core::mem::align_of::<riot_sys::msg_t__bindgen_ty_1>(),
+        "Type can not be transported in a message"
+    );
+
+    /// Create a channel that sends to (or, if it is the current thread, receives on) the
+    /// destination thread
+    pub fn new(destination: KernelPID) -> Self {
+        let () = Self::FITS;
+        Channel {
+            destination,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The thread the channel sends to
+    pub fn destination(&self) -> KernelPID {
+        self.destination
+    }
+
+    fn pack(value: T) -> msg_t {
+        let mut msg = msg_t {
+            type_: T::TYPE,
+            ..msg_t::default()
+        };
+        // unsafe: Size and alignment were checked in FITS
+        unsafe { core::ptr::write(&mut msg.content as *mut _ as *mut T, value) };
+        msg
+    }
+
+    fn unpack(msg: msg_t) -> Result<T, ReceiveError> {
+        if msg.type_ != T::TYPE {
+            return Err(ReceiveError::Other(OpaqueMsg(msg)));
+        }
+        // unsafe: Senders of this type number put a T in there (guaranteed by the unsafe
+        // ChannelMessage implementation)
+        Ok(unsafe { core::ptr::read(&msg.content as *const _ as *const T) })
+    }
+
+    /// Send a value, blocking until the destination thread receives it (or, if it has a message
+    /// queue, until there is space in it)
+    ///
+    /// When called in an interrupt, this does not block, and fails if the value can not be
+    /// delivered right away.
+    #[doc(alias = "msg_send")]
+    pub fn send(&self, value: T) -> Result<(), MsgSendError> {
+        let mut m = Self::pack(value);
+        // unsafe: OK per C API
+        match unsafe { msg_send(&mut m, self.destination.into()) } {
+            1 => Ok(()),
+            0 => Err(MsgSendError::ReceiverNotWaiting),
+            _ => Err(MsgSendError::InvalidPID),
+        }
+    }
+
+    /// Send a value if the destination thread can take it right away
+    #[doc(alias = "msg_try_send")]
+    pub fn try_send(&self, value: T) -> Result<(), MsgSendError> {
+        let mut m = Self::pack(value);
+        // unsafe: OK per C API
+        match unsafe { riot_sys::msg_try_send(&mut m, self.destination.into()) } {
+            1 => Ok(()),
+            0 => Err(MsgSendError::ReceiverNotWaiting),
+            _ => Err(MsgSendError::InvalidPID),
+        }
+    }
+
+    fn assert_receiving(&self) {
+        assert!(
+            self.destination == crate::thread::get_pid(),
+            "Channels can only receive in their destination thread"
+        );
+    }
+
+    /// Block until a message arrives for the current thread
+    ///
+    /// Messages of other types are returned in the error, as they can not be put back.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the channel's destination is not the current thread.
+    #[doc(alias = "msg_receive")]
+    pub fn receive(&self) -> Result<T, ReceiveError> {
+        self.assert_receiving();
+        let mut m: MaybeUninit<msg_t> = MaybeUninit::uninit();
+        // unsafe: OK per C API
+        let _ = unsafe { msg_receive(m.as_mut_ptr()) };
+        // unsafe: Initialized by msg_receive
+        Self::unpack(unsafe { m.assume_init() })
+    }
+
+    /// Like [receive](Self::receive), but giving up after the timeout has passed
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the channel's destination is not the current thread.
+    #[cfg(riot_module_ztimer_msec)]
+    #[doc(alias = "ztimer_msg_receive_timeout")]
+    pub fn receive_with_timeout(
+        &self,
+        timeout: crate::ztimer::Ticks<1000>,
+    ) -> Result<T, ReceiveError> {
+        self.assert_receiving();
+        let mut m: MaybeUninit<msg_t> = MaybeUninit::uninit();
+        // unsafe: OK per C API
+        let result = unsafe {
+            riot_sys::ztimer_msg_receive_timeout(riot_sys::ZTIMER_MSEC, m.as_mut_ptr(), timeout.0)
+        };
+        if result < 0 {
+            return Err(ReceiveError::Timeout);
+        }
+        // unsafe: Initialized by ztimer_msg_receive_timeout if it succeeded
+        Self::unpack(unsafe { m.assume_init() })
+    }
+}
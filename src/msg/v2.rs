@@ -7,8 +7,10 @@
 //!
 //! For safety, the module relies on other components not tossing around messages indiscriminately.
 //! In Rust, senders are told through the SendPort how the recipient will transmute the data back.
-//! For C components, safe wrappers (TBD: will) require and consume appropriate tickets and from
-//! there on rely on the C side to only send what is described in the API.
+//! For C components, a SendPort is given up into an [ExternalDestination] (safely for [AnyContent]
+//! types, or unsafely when the C API documents the content), and from there on the C side is
+//! relied on to only send what is described in the API. Interrupts can send through a SendPort
+//! directly.
 //!
 //! ## Example
 //!
@@ -122,6 +124,71 @@ impl<TYPE: Send, const TYPENO: u16> SendPort<TYPE, TYPENO> {
         }
     }
 
+    /// Send a message from an interrupt
+    ///
+    /// This behaves like [`SendPort::try_send()`] (which also works in interrupts), but the
+    /// [InIsr](thread::InIsr) token makes the context explicit. The recipient sees the message as
+    /// coming from [`Sender::ISR`].
+    ///
+    /// Ports are Send and Sync, so they can be placed where interrupt handlers find them (eg. in
+    /// a static set up by the receiving thread).
+    #[doc(alias = "msg_send_int")]
+    pub fn try_send_from_isr(&self, _in_isr: thread::InIsr, data: TYPE) -> Result<(), TYPE> {
+        let mut msg: riot_sys::msg_t = Default::default();
+        msg.type_ = TYPENO;
+
+        // As in try_send
+        let mut incoming = ManuallyDrop::new(data);
+        core::mem::swap(&mut incoming, unsafe {
+            core::mem::transmute(&mut msg.content)
+        });
+
+        // unsafe: OK per C API; the InIsr token guarantees the required context.
+        let result = unsafe { riot_sys::msg_send_int(&mut msg, self.destination.into()) };
+        debug_assert!(
+            result >= 0,
+            "Target PID vanished even though a SendPort was still around"
+        );
+        match result {
+            1 => Ok(()),
+            _ => {
+                core::mem::swap(&mut incoming, unsafe {
+                    core::mem::transmute(&mut msg.content)
+                });
+                Err(ManuallyDrop::into_inner(incoming))
+            }
+        }
+    }
+
+    /// Give up the port to let components outside of Rust's type checks (typically C modules)
+    /// send messages to the port's thread
+    ///
+    /// This is safe for types where any message content is a valid value; see
+    /// [`SendPort::external_unchecked()`] for other types.
+    pub fn external(self) -> ExternalDestination
+    where
+        TYPE: AnyContent,
+    {
+        // unsafe: Any content is valid for the type
+        unsafe { self.external_unchecked() }
+    }
+
+    /// Give up the port to let components outside of Rust's type checks send messages to the
+    /// port's thread
+    ///
+    /// ## Safety
+    ///
+    /// The caller must ensure that everyone the destination is passed to only sends messages of
+    /// the type number whose content is a valid TYPE (eg. because the C API documents that the
+    /// content's `ptr` is a pointer to a particular struct, and TYPE is a suitable pointer
+    /// wrapper).
+    pub unsafe fn external_unchecked(self) -> ExternalDestination {
+        ExternalDestination {
+            destination: self.destination,
+            typeno: TYPENO,
+        }
+    }
+
     /// Access the port's destination
     ///
     /// This is particularly useful when messages are not sent directly through [`SendPort::try_send()`], but
@@ -133,6 +200,164 @@ impl<TYPE: Send, const TYPENO: u16> SendPort<TYPE, TYPENO> {
     }
 }
 
+/// Types for which any content of a message is a valid value
+///
+/// Ports of these types can be handed to senders whose messages are not typechecked (through
+/// [`SendPort::external()`]), eg. to RIOT C modules that report events through messages with a
+/// number in the content's `value`.
+///
+/// ## Safety
+///
+/// Implementers must accept any bit pattern of the size of their type, read from the start of the
+/// message's content.
+pub unsafe trait AnyContent: Send + Copy {}
+
+// unsafe: All bit patterns are valid for these, and they fit in the content's u32 value.
+unsafe impl AnyContent for u32 {}
+unsafe impl AnyContent for i32 {}
+unsafe impl AnyContent for u16 {}
+unsafe impl AnyContent for i16 {}
+unsafe impl AnyContent for u8 {}
+unsafe impl AnyContent for i8 {}
+unsafe impl AnyContent for () {}
+
+/// The destination of a [SendPort] that was given up for use by senders outside of Rust's type
+/// checks (typically C modules), as created by [`SendPort::external()`]
+///
+/// This carries the thread's PID and the message type number to configure the sender with. As
+/// the port can not be recombined any more, the thread keeps accepting messages on that number
+/// (which is what C modules expect).
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize {
+/// # use riot_wrappers::msg::v2::*;
+/// # let message_semantics = unsafe { NoConfiguredMessages::new() };
+/// // A C driver that sends messages with the event's number in `content.value`
+/// let (message_semantics, driver_events, send_events): (_, ReceivePort<u32, 0x4200>, _) =
+///     message_semantics.split_off();
+/// let destination = send_events.external();
+/// // pass destination.pid() and destination.typeno() to the C driver's setup function
+/// loop {
+///     let _ = message_semantics
+///         .receive()
+///         .decode(&driver_events, |_sender, event| {
+///             // ...
+///         });
+/// }
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct ExternalDestination {
+    destination: thread::KernelPID,
+    typeno: u16,
+}
+
+impl ExternalDestination {
+    /// Thread that the messages are to be sent to
+    pub fn pid(&self) -> thread::KernelPID {
+        self.destination
+    }
+
+    /// Message type number the messages are to be sent with
+    pub fn typeno(&self) -> u16 {
+        self.typeno
+    }
+}
+
+/// Message content type for synchronous request/response exchanges
+///
+/// A port created for a `Call<REQ, RESP>` carries requests of type REQ, to which the receiving
+/// thread answers with a RESP. The sender uses [`SendPort::call()`], which blocks until the
+/// response arrives; the recipient handles the request with [`ReceivedMessage::decode_call()`],
+/// which sends the response back.
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize {
+/// # use riot_wrappers::msg::v2::*;
+/// # let message_semantics = unsafe { NoConfiguredMessages::new() };
+/// type SquareReceived = ReceivePort<Call<u32, u32>, 1>;
+/// let (message_semantics, receive_square, send_square): (_, SquareReceived, _) = message_semantics.split_off();
+/// // Other threads run `let nine = send_square.call(3);`; the server thread loops over:
+/// let message = message_semantics.receive();
+/// message.decode_call(&receive_square, |_sender, x| x * x);
+/// # 0
+/// # }
+/// ```
+///
+/// The response type needs to fit in a message just like the request type does. A request that is
+/// not answered leaves the calling thread blocked forever; in particular, dropping a received
+/// call message without decoding it through [`ReceivedMessage::decode_call()`] does that.
+#[repr(transparent)]
+pub struct Call<REQ: Send, RESP: Send> {
+    request: REQ,
+    _response: PhantomData<RESP>,
+}
+
+/// Check the size and alignment requirements that split_off checks for the request for a
+/// response type
+fn assert_response_fits<RESP>() {
+    assert!(
+        core::mem::size_of::<RESP>() <= core::mem::size_of::<riot_sys::msg_t__bindgen_ty_1>()
+            && core::mem::align_of::<RESP>()
+                <= core::mem::align_of::<riot_sys::msg_t__bindgen_ty_1>(),
+        "Response type can not be transported in a message"
+    );
+}
+
+impl<REQ: Send, RESP: Send, const TYPENO: u16> SendPort<Call<REQ, RESP>, TYPENO> {
+    /// Send a request to the port's thread, and block until it has responded
+    ///
+    /// Unlike [`SendPort::try_send()`], this waits for the receiving thread to be ready to
+    /// receive.
+    ///
+    /// ## Panics
+    ///
+    /// This panics when called in an interrupt context, as it blocks.
+    #[doc(alias = "msg_send_receive")]
+    pub fn call(&self, request: REQ) -> RESP {
+        assert_response_fits::<RESP>();
+        crate::thread::InThread::new().expect("Calls are only possible from within threads");
+
+        let mut msg: riot_sys::msg_t = Default::default();
+        msg.type_ = TYPENO;
+        // Moving the request into the content as in try_send; it is not dropped here but by the
+        // recipient.
+        let mut incoming = ManuallyDrop::new(Call::<REQ, RESP> {
+            request,
+            _response: PhantomData,
+        });
+        core::mem::swap(&mut incoming, unsafe {
+            core::mem::transmute(&mut msg.content)
+        });
+
+        let mut reply = MaybeUninit::uninit();
+        // unsafe: OK per C API. The destination is live by construction of the SendPort, and
+        // only a decode_call on the matching ReceivePort replies to this message type.
+        let result = unsafe {
+            riot_sys::msg_send_receive(&mut msg, reply.as_mut_ptr(), self.destination.into())
+        };
+        // Not just a debug assertion: The reply is only initialized on success
+        assert!(
+            result == 1,
+            "Target PID vanished even though a SendPort was still around"
+        );
+        let mut reply: riot_sys::msg_t = unsafe { reply.assume_init() };
+
+        let mut response = MaybeUninit::<RESP>::uninit();
+        core::mem::swap(&mut response, unsafe {
+            core::mem::transmute(&mut reply.content)
+        });
+        // unsafe: The reply was populated with a RESP by decode_call
+        unsafe { response.assume_init() }
+    }
+}
+
 impl<TYPE: Send, const TYPENO: u16> core::fmt::Debug for ReceivePort<TYPE, TYPENO> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
         write!(
@@ -424,4 +649,44 @@ impl<'a, S: MessageSemantics> ReceivedMessage<'a, S> {
             Err(self)
         }
     }
+
+    /// Handle a request received on a [Call] port, and send the function's result back to the
+    /// calling thread
+    ///
+    /// Like [`.decode()`](ReceivedMessage::decode), this returns the message unmodified if it
+    /// was not sent on the given port.
+    #[doc(alias = "msg_reply")]
+    pub fn decode_call<
+        F: FnOnce(Sender, REQ) -> RESP,
+        REQ: Send,
+        RESP: Send,
+        const TYPENO: u16,
+    >(
+        mut self,
+        _port: &'a ReceivePort<Call<REQ, RESP>, TYPENO>,
+        f: F,
+    ) -> Result<(), ReceivedMessage<S>> {
+        if self.msg.type_ != TYPENO {
+            return Err(self);
+        }
+        assert_response_fits::<RESP>();
+        let call: Call<REQ, RESP> = unsafe { self.extract() };
+        let sender = self.sender();
+        let mut request_msg = self.msg;
+        core::mem::forget(self); // Or else the value would be double-dropped
+
+        let response = f(sender, call.request);
+
+        let mut reply: riot_sys::msg_t = Default::default();
+        reply.type_ = TYPENO;
+        let mut outgoing = ManuallyDrop::new(response);
+        core::mem::swap(&mut outgoing, unsafe {
+            core::mem::transmute(&mut reply.content)
+        });
+        // unsafe: OK per C API. The sender is blocked in msg_send_receive (Call messages are only
+        // sent through SendPort::call), so the reply is delivered.
+        let result = unsafe { riot_sys::msg_reply(&mut request_msg, &mut reply) };
+        debug_assert!(result == 1, "Caller was not waiting for a reply");
+        Ok(())
+    }
 }
@@ -0,0 +1,192 @@
+//! Access to flash memory and similar storage through RIOT's [Memory Technology
+//! Device](https://doc.riot-os.org/group__drivers__mtd.html) layer
+//!
+//! An [Mtd] wraps any MTD device (internal flash, SPI NOR flash, SD cards through `mtd_sdcard`,
+//! ...) and provides byte addressed reads, page oriented writes and sector erasure.
+//!
+//! With the `with_embedded_storage` feature, [Mtd] implements the [embedded_storage] NOR flash
+//! traits, so that storage crates built on them can be used on any RIOT supported device. As
+//! those traits express the write and erase granularity as constants, those are type parameters
+//! of [Mtd] that are checked against the device's geometry at construction time:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::mtd::Mtd;
+//! # let dev: &'static mut riot_sys::mtd_dev_t = unimplemented!();
+//! // A device with 4 byte write size and 4KiB sectors
+//! let mut flash: Mtd<4, 4096> = Mtd::new(dev).unwrap();
+//! ```
+
+use crate::error::{Errno, NegativeErrorExt, NumericError};
+
+/// An initialized MTD device
+///
+/// `WRITE_SIZE` and `ERASE_SIZE` are the device's write granularity and sector size; they are
+/// only relevant for the embedded-storage traits, and can both be set to 1 when those are not
+/// used (see [Mtd::new_unchecked]). They can not be 0.
+pub struct Mtd<'a, const WRITE_SIZE: usize, const ERASE_SIZE: usize> {
+    dev: &'a mut riot_sys::mtd_dev_t,
+}
+
+impl<'a, const WRITE_SIZE: usize, const ERASE_SIZE: usize> Mtd<'a, WRITE_SIZE, ERASE_SIZE> {
+    /// Initialize the device, and check that its geometry matches the type parameters
+    ///
+    /// Fails with `EINVAL` if the geometry does not match.
+    #[doc(alias = "mtd_init")]
+    pub fn new(dev: &'a mut riot_sys::mtd_dev_t) -> Result<Self, NumericError> {
+        let mtd = Self::new_unchecked(dev)?;
+        if mtd.write_size() as usize != WRITE_SIZE || mtd.sector_size() as usize != ERASE_SIZE {
+            return Err(Errno::Inval.into());
+        }
+        Ok(mtd)
+    }
+
+    /// Initialize the device without checking its geometry against the type parameters
+    ///
+    /// This is suitable when the embedded-storage traits are not used, and the device's geometry
+    /// is only queried at runtime.
+    ///
+    /// Fails with `EINVAL` if either type parameter is 0.
+    #[doc(alias = "mtd_init")]
+    pub fn new_unchecked(dev: &'a mut riot_sys::mtd_dev_t) -> Result<Self, NumericError> {
+        // The embedded-storage traits divide by these
+        if WRITE_SIZE == 0 || ERASE_SIZE == 0 {
+            return Err(Errno::Inval.into());
+        }
+        // unsafe: OK per C API; initializing an initialized device is harmless.
+        unsafe { riot_sys::mtd_init(dev) }.negative_to_error()?;
+        Ok(Mtd { dev })
+    }
+
+    /// Number of sectors on the device
+    pub fn sector_count(&self) -> u32 {
+        self.dev.sector_count
+    }
+
+    /// Number of pages in a sector
+    pub fn pages_per_sector(&self) -> u32 {
+        self.dev.pages_per_sector
+    }
+
+    /// Size of a page in bytes
+    pub fn page_size(&self) -> u32 {
+        self.dev.page_size
+    }
+
+    /// Minimum size and alignment of writes in bytes
+    pub fn write_size(&self) -> u32 {
+        self.dev.write_size
+    }
+
+    /// Size of a sector (the unit of erasure) in bytes
+    pub fn sector_size(&self) -> u32 {
+        self.dev.pages_per_sector * self.dev.page_size
+    }
+
+    /// Total size of the device in bytes
+    pub fn capacity(&self) -> u32 {
+        self.sector_count() * self.sector_size()
+    }
+
+    /// Read `buf.len()` bytes starting at `addr`
+    #[doc(alias = "mtd_read")]
+    pub fn read(&mut self, addr: u32, buf: &mut [u8]) -> Result<(), NumericError> {
+        // unsafe: OK per C API
+        unsafe { riot_sys::mtd_read(self.dev, buf.as_mut_ptr() as *mut _, addr, buf.len() as _) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    /// Write data starting at `addr`, which may span several pages
+    ///
+    /// The affected area needs to be erased before; the address and length need to be multiples
+    /// of the [write size](Self::write_size).
+    #[doc(alias = "mtd_write_page_raw")]
+    pub fn write(&mut self, addr: u32, data: &[u8]) -> Result<(), NumericError> {
+        let page_size = self.page_size();
+        // unsafe: OK per C API; the data is not retained.
+        unsafe {
+            riot_sys::mtd_write_page_raw(
+                self.dev,
+                data.as_ptr() as *const _,
+                addr / page_size,
+                addr % page_size,
+                data.len() as _,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    /// Erase `count` sectors starting at sector number `first`
+    #[doc(alias = "mtd_erase_sector")]
+    pub fn erase_sectors(&mut self, first: u32, count: u32) -> Result<(), NumericError> {
+        // unsafe: OK per C API
+        unsafe { riot_sys::mtd_erase_sector(self.dev, first, count) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    /// Access the underlying C device
+    pub fn as_ptr(&mut self) -> *mut riot_sys::mtd_dev_t {
+        self.dev
+    }
+}
+
+#[cfg(feature = "with_embedded_storage")]
+mod storage_impls {
+    use super::*;
+    use embedded_storage::nor_flash::{
+        check_erase,
+        check_read,
+        check_write,
+        ErrorType,
+        NorFlash,
+        NorFlashErrorKind,
+        ReadNorFlash,
+    };
+
+    fn kind_to_error(kind: NorFlashErrorKind) -> NumericError {
+        match kind {
+            NorFlashErrorKind::NotAligned => Errno::Dom.into(),
+            _ => Errno::Overflow.into(),
+        }
+    }
+
+    impl<'a, const W: usize, const E: usize> ErrorType for Mtd<'a, W, E> {
+        type Error = NumericError;
+    }
+
+    impl<'a, const W: usize, const E: usize> ReadNorFlash for Mtd<'a, W, E> {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), NumericError> {
+            check_read(self, offset, bytes.len()).map_err(kind_to_error)?;
+            Mtd::read(self, offset, bytes)
+        }
+
+        fn capacity(&self) -> usize {
+            Mtd::capacity(self) as _
+        }
+    }
+
+    impl<'a, const W: usize, const E: usize> NorFlash for Mtd<'a, W, E> {
+        const WRITE_SIZE: usize = W;
+        const ERASE_SIZE: usize = E;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), NumericError> {
+            check_erase(self, from, to).map_err(kind_to_error)?;
+            let sector_size = self.sector_size();
+            // ERASE_SIZE may differ from the actual sector size if created through new_unchecked
+            if from % sector_size != 0 || to % sector_size != 0 {
+                return Err(kind_to_error(NorFlashErrorKind::NotAligned));
+            }
+            self.erase_sectors(from / sector_size, (to - from) / sector_size)
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), NumericError> {
+            check_write(self, offset, bytes.len()).map_err(kind_to_error)?;
+            Mtd::write(self, offset, bytes)
+        }
+    }
+}
@@ -5,6 +5,7 @@
 //! [mutex]: https://doc.riot-os.org/group__core__sync__mutex.html
 //! [std::sync::mutex]: https://doc.rust-lang.org/std/sync/struct.Mutex.html
 
+use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
 // For correctness considerations, all uses of UnsafeCell can be ignored here; the only reason why
 // an UnsafeCell is used is to indicate to the linker that a static mutex still needs to be
@@ -25,6 +26,26 @@ use core::cell::UnsafeCell;
 pub struct Mutex<T> {
     mutex: UnsafeCell<riot_sys::inline::mutex_t>,
     data: UnsafeCell<T>,
+    #[cfg(feature = "mutex_stats")]
+    stats: UnsafeCell<MutexStats>,
+}
+
+/// Statistics on the use of a [Mutex], see [Mutex::stats]
+///
+/// This is only available with the `mutex_stats` feature.
+#[cfg(feature = "mutex_stats")]
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct MutexStats {
+    /// Number of times the mutex was acquired
+    pub locks: u32,
+    /// Number of times a thread had to wait for the mutex
+    pub contended: u32,
+    /// Longest time a thread had to wait for the mutex, in microseconds
+    ///
+    /// This is only measured if the ztimer_usec module is in use, and 0 otherwise.
+    pub max_wait_us: u32,
 }
 
 impl<T> Mutex<T> {
@@ -36,6 +57,12 @@ impl<T> Mutex<T> {
         Mutex {
             data: UnsafeCell::new(t),
             mutex: UnsafeCell::new(new),
+            #[cfg(feature = "mutex_stats")]
+            stats: UnsafeCell::new(MutexStats {
+                locks: 0,
+                contended: 0,
+                max_wait_us: 0,
+            }),
         }
     }
 
@@ -56,15 +83,95 @@ impl<T> Mutex<T> {
             .lock()
     }
 
+    /// Run a closure on the mutex's data while holding the lock
+    ///
+    /// This is a shorthand for locking, accessing the guard and dropping it, and is subject to the
+    /// same restrictions as [.lock()](Self::lock).
+    pub fn lock_and<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.lock())
+    }
+
     /// Get an accessor to the mutex if the mutex is available
     #[doc(alias = "mutex_trylock")]
     pub fn try_lock(&self) -> Option<MutexGuard<T>> {
         match unsafe { riot_sys::mutex_trylock(self.mutex.get()) } {
-            1 => Some(MutexGuard { mutex: &self }),
+            1 => {
+                #[cfg(feature = "mutex_stats")]
+                self.record_lock(None);
+                Some(MutexGuard { mutex: &self })
+            }
             _ => None,
         }
     }
 
+    /// Whether the mutex is currently locked
+    ///
+    /// This is only a snapshot, and mainly useful for diagnostics.
+    pub fn is_locked(&self) -> bool {
+        // unsafe: Reading a single pointer, which is updated atomically by the C side
+        unsafe { !core::ptr::read_volatile(&(*self.mutex.get()).queue.next).is_null() }
+    }
+
+    /// The thread that currently holds the mutex, if it is locked
+    ///
+    /// RIOT only tracks this when the core_mutex_priority_inheritance or core_mutex_debug module is
+    /// in use. It does not record whether a lock was taken from an interrupt context (through
+    /// [try_lock](Self::try_lock)): For such a lock, this reports whichever thread (if any) was
+    /// recorded at that time, typically the thread that was interrupted, even though that thread
+    /// does not hold the lock.
+    #[cfg(any(
+        riot_module_core_mutex_priority_inheritance,
+        riot_module_core_mutex_debug
+    ))]
+    pub fn holder(&self) -> Option<crate::thread::KernelPID> {
+        crate::interrupt::free(|_| {
+            if !self.is_locked() {
+                return None;
+            }
+            // unsafe: Reading a plain field in a critical section
+            crate::thread::KernelPID::new(unsafe { (*self.mutex.get()).owner })
+        })
+    }
+
+    /// The priority the current holder of the mutex had before it inherited a higher priority
+    /// from a waiting thread
+    ///
+    /// If this differs from the holder's current [priority](crate::thread::KernelPID::priority),
+    /// a higher priority thread is blocked on the mutex.
+    #[cfg(riot_module_core_mutex_priority_inheritance)]
+    pub fn holder_original_priority(&self) -> Option<u8> {
+        crate::interrupt::free(|_| {
+            if !self.is_locked() {
+                return None;
+            }
+            // unsafe: Reading a plain field in a critical section
+            Some(unsafe { (*self.mutex.get()).owner_original_priority })
+        })
+    }
+
+    /// Statistics on how the mutex was used so far
+    ///
+    /// This is only available with the `mutex_stats` feature.
+    #[cfg(feature = "mutex_stats")]
+    pub fn stats(&self) -> MutexStats {
+        // unsafe: Stats are only accessed in critical sections
+        crate::interrupt::free(|_| unsafe { *self.stats.get() })
+    }
+
+    /// Count a successful lock; to be called while holding the lock
+    #[cfg(feature = "mutex_stats")]
+    fn record_lock(&self, wait_us: Option<u32>) {
+        crate::interrupt::free(|_| {
+            // unsafe: Stats are only accessed in critical sections
+            let stats = unsafe { &mut *self.stats.get() };
+            stats.locks = stats.locks.wrapping_add(1);
+            if let Some(wait_us) = wait_us {
+                stats.contended = stats.contended.wrapping_add(1);
+                stats.max_wait_us = stats.max_wait_us.max(wait_us);
+            }
+        });
+    }
+
     /// Lock the mutex and throw away the key
     ///
     /// Try to lock the mutex (returning None if it is locked). When successful, a mutable
@@ -104,9 +211,24 @@ impl<'a, T> crate::thread::ValueInThread<&'a Mutex<T>> {
     /// context, so no additional check is performed.
     #[doc(alias = "mutex_lock")]
     pub fn lock(self) -> MutexGuard<'a, T> {
+        #[cfg(feature = "mutex_stats")]
+        if let Some(guard) = self.try_lock() {
+            return guard;
+        }
+        #[cfg(all(feature = "mutex_stats", riot_module_ztimer_usec))]
+        let start = crate::ztimer::Clock::usec().now();
+
         // unsafe: All preconditions of the C function are met (not-NULL through taking a &self,
         // being initialized through RAII guarantees, thread context is in the InThread).
         unsafe { riot_sys::mutex_lock(crate::inline_cast_mut(self.mutex.get())) };
+
+        #[cfg(all(feature = "mutex_stats", riot_module_ztimer_usec))]
+        self.record_lock(Some(
+            crate::ztimer::Clock::usec().now().0.wrapping_sub(start.0),
+        ));
+        #[cfg(all(feature = "mutex_stats", not(riot_module_ztimer_usec)))]
+        self.record_lock(Some(0));
+
         MutexGuard { mutex: &self }
     }
 }
@@ -145,6 +267,24 @@ impl<'a, T> MutexGuard<'a, T> {
         ::core::mem::forget(self);
         unsafe { riot_sys::mutex_unlock_and_sleep(crate::inline_cast_mut(m.get())) };
     }
+
+    /// Narrow the lock down to a part of the protected data, eg. a single field of a struct
+    ///
+    /// The mutex stays locked until the resulting guard is dropped.
+    ///
+    /// This is an associated function rather than a method so that it does not shadow methods of
+    /// `T`; it is called as `MutexGuard::map(guard, |data| &mut data.field)`.
+    pub fn map<U>(guard: Self, f: impl FnOnce(&mut T) -> &mut U) -> MappedMutexGuard<'a, U> {
+        let mutex = &guard.mutex.mutex;
+        // unsafe: The guard grants exclusive access until it is (here: manually) released.
+        let data = f(unsafe { &mut *guard.mutex.data.get() }) as *mut U;
+        core::mem::forget(guard);
+        MappedMutexGuard {
+            mutex,
+            data,
+            _data: PhantomData,
+        }
+    }
 }
 
 impl<'a, T> Deref for MutexGuard<'a, T> {
@@ -161,6 +301,51 @@ impl<'a, T> DerefMut for MutexGuard<'a, T> {
     }
 }
 
+/// A lock on a mutex that gives access to only a part of its data
+///
+/// This is created through [MutexGuard::map], and unlocks the mutex when dropped just like the
+/// original guard.
+pub struct MappedMutexGuard<'a, U> {
+    mutex: &'a UnsafeCell<riot_sys::inline::mutex_t>,
+    data: *mut U,
+    _data: PhantomData<&'a mut U>,
+}
+
+impl<'a, U> Drop for MappedMutexGuard<'a, U> {
+    fn drop(&mut self) {
+        unsafe { riot_sys::mutex_unlock(crate::inline_cast_mut(self.mutex.get())) }
+    }
+}
+
+impl<'a, U> MappedMutexGuard<'a, U> {
+    /// Narrow the lock down further; see [MutexGuard::map]
+    pub fn map<V>(guard: Self, f: impl FnOnce(&mut U) -> &mut V) -> MappedMutexGuard<'a, V> {
+        let mutex = guard.mutex;
+        // unsafe: The guard grants exclusive access until it is (here: manually) released.
+        let data = f(unsafe { &mut *guard.data }) as *mut V;
+        core::mem::forget(guard);
+        MappedMutexGuard {
+            mutex,
+            data,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<'a, U> Deref for MappedMutexGuard<'a, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        unsafe { &*self.data }
+    }
+}
+
+impl<'a, U> DerefMut for MappedMutexGuard<'a, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        unsafe { &mut *self.data }
+    }
+}
+
 impl<T> mutex_trait::Mutex for &Mutex<T> {
     type Data = T;
 
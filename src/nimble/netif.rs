@@ -0,0 +1,207 @@
+//! IPv6 over BLE connections through RIOT's [nimble_netif](https://doc.riot-os.org/group__ble__nimble__netif.html)
+//!
+//! The nimble_netif module provides a GNRC network interface whose links are BLE connections.
+//! Connections are established either actively ([connect()]) or by advertising and waiting for a
+//! peer ([accept()]); all changes in connection state are reported to the callback set in
+//! [set_event_callback()], which runs in the NimBLE host thread.
+//!
+//! The configuration structs are passed on as they are in C, as their fields vary between RIOT
+//! versions.
+
+use crate::error::{NegativeErrorExt, NumericError};
+use crate::mutex::Mutex;
+
+type Callback = &'static mut (dyn FnMut(Event) + Send);
+
+/// Number of events that can be held back while the callback is running
+const QUEUE_LEN: usize = 4;
+
+struct Dispatch {
+    callback: Option<Callback>,
+    /// Set while an event is being passed to the callback
+    running: bool,
+    /// Events that arrived while the callback was running, to be passed to it after it returned
+    queued: heapless::Deque<Event, QUEUE_LEN>,
+}
+
+static DISPATCH: Mutex<Dispatch> = Mutex::new(Dispatch {
+    callback: None,
+    running: false,
+    queued: heapless::Deque::new(),
+});
+
+/// Handle of a connection slot of nimble_netif
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Connection(i32);
+
+impl Connection {
+    /// Close the connection
+    #[doc(alias = "nimble_netif_close")]
+    pub fn close(self) -> Result<(), NumericError> {
+        // unsafe: OK per C API; invalid handles are reported as errors.
+        unsafe { riot_sys::nimble_netif_close(self.0) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    /// The raw handle as used by the C API and the nimble_netif_conn functions
+    pub fn handle(&self) -> i32 {
+        self.0
+    }
+}
+
+/// Type of event reported to the callback
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// Advertising started
+    Accepting,
+    /// Advertising stopped
+    AcceptStop,
+    /// A connection attempt in the central role was started
+    InitCentral,
+    /// A connection attempt in the peripheral role was started
+    InitPeripheral,
+    /// A connection in the central role was established
+    ConnectedCentral,
+    /// A connection in the peripheral role was established
+    ConnectedPeripheral,
+    /// A connection in the central role was closed
+    ClosedCentral,
+    /// A connection in the peripheral role was closed
+    ClosedPeripheral,
+    /// A connection attempt in the central role was aborted
+    AbortCentral,
+    /// A connection attempt in the peripheral role was aborted
+    AbortPeripheral,
+    /// The parameters of a connection were changed
+    ConnUpdated,
+    /// An event not known to this wrapper
+    Other(u32),
+}
+
+impl EventKind {
+    fn from_c(event: riot_sys::nimble_netif_event_t) -> Self {
+        use EventKind::*;
+        match event {
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_ACCEPTING => Accepting,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_ACCEPT_STOP => AcceptStop,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_INIT_CENTRAL => InitCentral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_INIT_PERIPHERAL => InitPeripheral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_CONNECTED_CENTRAL => ConnectedCentral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_CONNECTED_PERIPHERAL => ConnectedPeripheral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_CLOSED_CENTRAL => ClosedCentral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_CLOSED_PERIPHERAL => ClosedPeripheral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_ABORT_CENTRAL => AbortCentral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_ABORT_PERIPHERAL => AbortPeripheral,
+            riot_sys::nimble_netif_event_t_NIMBLE_NETIF_CONN_UPDATED => ConnUpdated,
+            other => Other(other as _),
+        }
+    }
+}
+
+/// A change in nimble_netif's connection state
+#[derive(Debug, Copy, Clone)]
+pub struct Event {
+    pub kind: EventKind,
+    /// Connection the event is about; meaningless for [EventKind::Accepting] and
+    /// [EventKind::AcceptStop]
+    pub connection: Connection,
+    /// Address of the peer, if the event has one
+    pub addr: Option<[u8; 6]>,
+}
+
+extern "C" fn event_cb(handle: i32, event: riot_sys::nimble_netif_event_t, addr: *const u8) {
+    let addr = if addr.is_null() {
+        None
+    } else {
+        // unsafe: Non-null addresses are BLE addresses per C API
+        Some(unsafe { *(addr as *const [u8; 6]) })
+    };
+    let event = Event {
+        kind: EventKind::from_c(event),
+        connection: Connection(handle),
+        addr,
+    };
+    {
+        let mut dispatch = DISPATCH.lock();
+        if dispatch.running {
+            // Passed on by whoever is running the callback right now (which may be this very
+            // thread, further up the stack)
+            let _ = dispatch.queued.push_back(event);
+            return;
+        }
+        dispatch.running = true;
+    }
+
+    let mut event = event;
+    loop {
+        // The callback is taken out of the lock while it runs, so that it can use the other
+        // functions of this module
+        let callback = DISPATCH.lock().callback.take();
+        if let Some(callback) = callback {
+            callback(event);
+            let mut dispatch = DISPATCH.lock();
+            // Unless the callback installed a new one through set_event_callback()
+            if dispatch.callback.is_none() {
+                dispatch.callback = Some(callback);
+            }
+        }
+
+        let mut dispatch = DISPATCH.lock();
+        match dispatch.queued.pop_front() {
+            Some(next) => event = next,
+            None => {
+                dispatch.running = false;
+                return;
+            }
+        }
+    }
+}
+
+/// Set the function that is called on every connection event, replacing any previous one
+///
+/// The callback may call the other functions of this module (including this one). Events that
+/// nimble_netif reports while the callback is running (eg. synchronously from inside such a call)
+/// are passed to the callback after it returned; if more than a few pile up that way, the excess
+/// ones are dropped.
+#[doc(alias = "nimble_netif_eventcb")]
+pub fn set_event_callback(callback: Callback) {
+    DISPATCH.lock().callback = Some(callback);
+    // unsafe: OK per C API
+    unsafe { riot_sys::nimble_netif_eventcb(Some(event_cb)) };
+}
+
+/// Start connecting to the peer with the given address
+///
+/// The connection is usable once the event callback reports [EventKind::ConnectedCentral] for
+/// the returned connection.
+#[doc(alias = "nimble_netif_connect")]
+pub fn connect(
+    addr: &riot_sys::ble_addr_t,
+    config: &riot_sys::nimble_netif_connect_cfg_t,
+) -> Result<Connection, NumericError> {
+    // unsafe: OK per C API; both arguments are copied.
+    let handle = unsafe { riot_sys::nimble_netif_connect(addr, config) }.negative_to_error()?;
+    Ok(Connection(handle as _))
+}
+
+/// Advertise with the given advertising data, accepting incoming connections
+///
+/// The advertising data is typically built with [crate::bluetil::Ad], and needs to announce the
+/// IPSS service for peers to recognize the device.
+#[doc(alias = "nimble_netif_accept")]
+pub fn accept(ad: &[u8], config: &riot_sys::nimble_netif_accept_cfg_t) -> Result<(), NumericError> {
+    // unsafe: OK per C API; the data is copied.
+    unsafe { riot_sys::nimble_netif_accept(ad.as_ptr(), ad.len() as _, config) }
+        .negative_to_error()
+        .map(|_| ())
+}
+
+/// Stop advertising
+#[doc(alias = "nimble_netif_accept_stop")]
+pub fn accept_stop() -> Result<(), NumericError> {
+    // unsafe: OK per C API
+    unsafe { riot_sys::nimble_netif_accept_stop() }
+        .negative_to_error()
+        .map(|_| ())
+}
@@ -0,0 +1,240 @@
+//! BLE scanning through RIOT's [nimble_scanner](https://doc.riot-os.org/group__ble__nimble__scanner.html)
+//!
+//! Scanning is a global operation: While it is active, every advertisement that is received is
+//! handed to the callback passed to [start()] as a [Report]. The callback runs in the NimBLE host
+//! thread, and should thus only do short processing (such as storing the report somewhere, or
+//! sending a message to an application thread).
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::nimble::scanner;
+//! static mut CALLBACK: fn(&scanner::Report) = |report| {
+//!     for (type_, data) in report.ad_structures() {
+//!         // ... look for manufacturer data (type 0xff) of beacons ...
+//!     }
+//! };
+//! // unsafe: CALLBACK is not accessed anywhere else
+//! scanner::start(&scanner::Config::default(), unsafe { &mut CALLBACK }).unwrap();
+//! ```
+
+use crate::error::{NegativeErrorExt, NumericError};
+use crate::mutex::Mutex;
+
+type Callback = &'static mut (dyn FnMut(&Report) + Send);
+
+/// Number of reports that can be held back while the callback is running
+const QUEUE_LEN: usize = 4;
+/// Maximum length of the advertising data of a report that is held back (the length of legacy
+/// advertising data)
+const QUEUED_DATA_LEN: usize = 31;
+
+/// A [Report] that owns its data
+struct QueuedReport {
+    event_type: u8,
+    addr_type: u8,
+    addr: [u8; 6],
+    rssi: i8,
+    data: heapless::Vec<u8, QUEUED_DATA_LEN>,
+}
+
+struct Dispatch {
+    callback: Option<Callback>,
+    /// Set while a report is being passed to the callback
+    running: bool,
+    /// Reports that arrived while the callback was running, to be passed to it after it returned
+    queued: heapless::Deque<QueuedReport, QUEUE_LEN>,
+}
+
+static DISPATCH: Mutex<Dispatch> = Mutex::new(Dispatch {
+    callback: None,
+    running: false,
+    queued: heapless::Deque::new(),
+});
+
+/// Scan parameters
+#[derive(Debug, Copy, Clone)]
+pub struct Config {
+    /// Time between the start of two scan windows
+    pub interval_ms: u16,
+    /// Duration of each scan window
+    pub window_ms: u16,
+    /// If set, no scan requests are sent, and only advertisements (no scan responses) are seen
+    pub passive: bool,
+    /// If set, the controller reports each advertiser only once per scan
+    pub filter_duplicates: bool,
+}
+
+impl Default for Config {
+    /// Continuous active scanning on the 1M PHY
+    fn default() -> Self {
+        Config {
+            interval_ms: 100,
+            window_ms: 100,
+            passive: false,
+            filter_duplicates: false,
+        }
+    }
+}
+
+/// A received advertisement or scan response
+pub struct Report<'a> {
+    /// Event type (`BLE_HCI_ADV_RPT_EVTYPE_*` for legacy advertisements)
+    pub event_type: u8,
+    /// Address type of the advertiser (`BLE_ADDR_*`)
+    pub addr_type: u8,
+    /// Address of the advertiser, in NimBLE's (little endian) byte order
+    pub addr: [u8; 6],
+    /// Received signal strength in dBm
+    pub rssi: i8,
+    /// Raw advertising data
+    pub data: &'a [u8],
+}
+
+impl<'a> Report<'a> {
+    /// Iterate over the AD structures in the advertising data as pairs of type and value
+    ///
+    /// Iteration ends at the first malformed structure.
+    pub fn ad_structures(&self) -> AdStructures<'a> {
+        AdStructures(self.data)
+    }
+}
+
+/// Iterator over AD structures; see [Report::ad_structures]
+pub struct AdStructures<'a>(&'a [u8]);
+
+impl<'a> Iterator for AdStructures<'a> {
+    type Item = (u8, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&len, rest) = self.0.split_first()?;
+        let len = len as usize;
+        if len == 0 || rest.len() < len {
+            // A zero length marks early termination of the data
+            self.0 = &[];
+            return None;
+        }
+        self.0 = &rest[len..];
+        Some((rest[0], &rest[1..len]))
+    }
+}
+
+extern "C" fn disc_cb(
+    type_: u8,
+    addr: *const riot_sys::ble_addr_t,
+    info: *const riot_sys::nimble_scanner_info_t,
+    ad: *const u8,
+    ad_len: riot_sys::size_t,
+) {
+    // unsafe: Pointers are valid for the duration of the callback per C API
+    let (addr, info, data) =
+        unsafe { (&*addr, &*info, core::slice::from_raw_parts(ad, ad_len as _)) };
+    let report = Report {
+        event_type: type_,
+        addr_type: addr.type_,
+        addr: addr.val,
+        rssi: info.rssi,
+        data,
+    };
+    {
+        let mut dispatch = DISPATCH.lock();
+        if dispatch.running {
+            // Passed on by whoever is running the callback right now (which may be this very
+            // thread, further up the stack)
+            if let Ok(data) = heapless::Vec::from_slice(report.data) {
+                let _ = dispatch.queued.push_back(QueuedReport {
+                    event_type: report.event_type,
+                    addr_type: report.addr_type,
+                    addr: report.addr,
+                    rssi: report.rssi,
+                    data,
+                });
+            }
+            return;
+        }
+        dispatch.running = true;
+    }
+
+    run_callback(&report);
+    loop {
+        let queued = {
+            let mut dispatch = DISPATCH.lock();
+            match dispatch.queued.pop_front() {
+                Some(queued) => queued,
+                None => {
+                    dispatch.running = false;
+                    return;
+                }
+            }
+        };
+        run_callback(&Report {
+            event_type: queued.event_type,
+            addr_type: queued.addr_type,
+            addr: queued.addr,
+            rssi: queued.rssi,
+            data: &queued.data,
+        });
+    }
+}
+
+fn run_callback(report: &Report) {
+    // The callback is taken out of the lock while it runs, so that it can call start() or stop()
+    let callback = DISPATCH.lock().callback.take();
+    if let Some(callback) = callback {
+        callback(report);
+        let mut dispatch = DISPATCH.lock();
+        // Unless the callback installed a new one through start()
+        if dispatch.callback.is_none() {
+            dispatch.callback = Some(callback);
+        }
+    }
+}
+
+/// Start scanning, reporting every received advertisement to the callback
+///
+/// If scanning was already active, it is restarted with the new configuration and callback. This
+/// may also be called from inside the callback.
+///
+/// Reports that arrive while the callback is running are passed to the callback after it
+/// returned; if more than a few pile up that way, or if their advertising data exceeds that of
+/// legacy advertisements, they are dropped.
+#[doc(alias = "nimble_scanner_init")]
+#[doc(alias = "nimble_scanner_start")]
+pub fn start(config: &Config, callback: Callback) -> Result<(), NumericError> {
+    stop();
+    DISPATCH.lock().callback = Some(callback);
+
+    let mut flags = riot_sys::NIMBLE_SCANNER_PHY_1M;
+    if config.passive {
+        flags |= riot_sys::NIMBLE_SCANNER_PASSIVE;
+    }
+    if config.filter_duplicates {
+        flags |= riot_sys::NIMBLE_SCANNER_FILTER_DUPS;
+    }
+    let cfg = riot_sys::nimble_scanner_cfg_t {
+        itvl_ms: config.interval_ms,
+        win_ms: config.window_ms,
+        flags: flags as _,
+        ..Default::default()
+    };
+    // unsafe: OK per C API; the configuration is copied.
+    unsafe { riot_sys::nimble_scanner_init(&cfg, Some(disc_cb)) }.negative_to_error()?;
+    // unsafe: OK per C API
+    unsafe { riot_sys::nimble_scanner_start() }.negative_to_error()?;
+    Ok(())
+}
+
+/// Stop scanning
+///
+/// The callback is retained, but not called any more until scanning is started again.
+#[doc(alias = "nimble_scanner_stop")]
+pub fn stop() {
+    // unsafe: OK per C API
+    unsafe { riot_sys::nimble_scanner_stop() };
+}
+
+/// Whether scanning is currently active
+#[doc(alias = "nimble_scanner_status")]
+pub fn is_scanning() -> bool {
+    // unsafe: OK per C API
+    unsafe { riot_sys::nimble_scanner_status() == riot_sys::NIMBLE_SCANNER_SCANNING as _ }
+}
@@ -0,0 +1,319 @@
+//! Running blocking operations on a worker thread, awaiting them from async code
+//!
+//! Many RIOT peripheral APIs (eg. I²C and SPI) only offer blocking operations. Calling them from an
+//! async task would stall all other tasks of the executor for the duration of the transaction. An
+//! [Offload] lets async code hand such operations to a dedicated worker thread, and await their
+//! completion instead.
+//!
+//! The worker processes one operation at a time. It is set up by calling [Offload::run_worker] in
+//! a thread of its own; operations are then started through [Offload::run].
+//!
+//! With the `with_embedded_hal_async` feature, [Offloaded] wraps a blocking embedded-hal 1.0 I²C or
+//! SPI device (such as [I2CDevice](crate::i2c::I2CDevice) or
+//! [SPIBusDevice](crate::spi::SPIBusDevice)) into an implementation of the corresponding
+//! embedded-hal-async trait.
+//!
+//! This module requires the core_thread_flags module.
+
+use core::cell::UnsafeCell;
+use core::future::Future;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use crate::mutex::Mutex;
+use crate::thread::KernelPID;
+
+/// Thread flag by which a worker thread is notified of new jobs
+///
+/// Threads that run [Offload::run_worker] should not use this flag for other purposes.
+///
+/// See the [flags module](crate::thread::flags) for the other flags used by this crate.
+pub const OFFLOAD_FLAG: riot_sys::thread_flags_t = 1 << 11;
+
+/// Number of tasks that can wait for a busy worker without resorting to busy polling
+const WAITING: usize = 4;
+
+/// Type erased view of a [Job]
+trait RunJob {
+    fn run(&mut self);
+}
+
+/// A closure along with space for its result
+struct Job<F, R> {
+    f: Option<F>,
+    result: Option<R>,
+}
+
+impl<F: FnOnce() -> R, R> RunJob for Job<F, R> {
+    fn run(&mut self) {
+        let f = self.f.take().expect("Jobs are only run once");
+        self.result = Some(f());
+    }
+}
+
+/// The job currently assigned to the worker
+struct Current {
+    // The 'static is a lie; the job's owner ensures that the job outlives its presence here.
+    job: *mut (dyn RunJob + 'static),
+    started: bool,
+    done: bool,
+    waker: Option<Waker>,
+}
+
+struct State {
+    worker: Option<KernelPID>,
+    current: Option<Current>,
+    waiting: heapless::Deque<Waker, WAITING>,
+}
+
+/// A worker thread that runs blocking operations on behalf of async tasks
+///
+/// This is typically placed in a static.
+pub struct Offload {
+    // Only accessed with interrupts disabled
+    state: UnsafeCell<State>,
+    // Held by the worker while it runs a job, so that abandoned jobs can be waited for
+    running: Mutex<()>,
+}
+
+// The state is only accessed in critical sections, and jobs are required to be Send.
+unsafe impl Sync for Offload {}
+
+impl Offload {
+    pub const fn new() -> Self {
+        Offload {
+            state: UnsafeCell::new(State {
+                worker: None,
+                current: None,
+                waiting: heapless::Deque::new(),
+            }),
+            running: Mutex::new(()),
+        }
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut State) -> R) -> R {
+        // unsafe: Interrupts are off, and no references to the state escape the closure.
+        crate::interrupt::free(|_| f(unsafe { &mut *self.state.get() }))
+    }
+
+    /// Run jobs submitted through [.run()](Self::run) in the current thread, forever
+    ///
+    /// # Panics
+    ///
+    /// ... if a worker was already started for this offloader.
+    #[doc(alias = "thread_flags_wait_any")]
+    pub fn run_worker(&'static self) -> ! {
+        let me = crate::thread::get_pid();
+        self.with_state(|state| {
+            assert!(state.worker.is_none(), "Worker was already started");
+            state.worker = Some(me);
+        });
+
+        loop {
+            let guard = self.running.lock();
+            let job = self.with_state(|state| match &mut state.current {
+                Some(current) if !current.started => {
+                    current.started = true;
+                    Some(current.job)
+                }
+                _ => None,
+            });
+            if let Some(job) = job {
+                // unsafe: The job's owner does not go away while started is set and the running
+                // lock is held (see OffloadFuture::drop).
+                unsafe { (*job).run() };
+                self.with_state(|state| {
+                    let current = state.current.as_mut().expect("Job was not removed");
+                    current.done = true;
+                    if let Some(waker) = current.waker.take() {
+                        waker.wake();
+                    }
+                });
+            }
+            drop(guard);
+
+            if job.is_none() {
+                // unsafe: OK per C API
+                unsafe { riot_sys::thread_flags_wait_any(OFFLOAD_FLAG) };
+            }
+        }
+    }
+
+    /// Run a closure on the worker thread, and await its result
+    ///
+    /// Jobs are run one at a time. If the returned future is dropped while the job is running,
+    /// the drop blocks until the job is complete.
+    pub fn run<'a, F: FnOnce() -> R + Send + 'a, R: Send + 'a>(
+        &'a self,
+        f: F,
+    ) -> impl Future<Output = R> + 'a {
+        OffloadFuture {
+            offload: self,
+            job: Job {
+                f: Some(f),
+                result: None,
+            },
+            submitted: false,
+            _pinned: PhantomPinned,
+        }
+    }
+}
+
+struct OffloadFuture<'a, F, R> {
+    offload: &'a Offload,
+    job: Job<F, R>,
+    submitted: bool,
+    _pinned: PhantomPinned,
+}
+
+impl<'a, F, R> OffloadFuture<'a, F, R> {
+    fn is_ours(&self, current: &Option<Current>) -> bool {
+        current.as_ref().map(|c| c.job as *const () as usize)
+            == Some(&self.job as *const _ as usize)
+    }
+}
+
+impl<'a, F: FnOnce() -> R + Send + 'a, R: Send + 'a> Future for OffloadFuture<'a, F, R> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<R> {
+        // unsafe: Nothing is moved out of self (the result is taken out of an Option).
+        let this = unsafe { self.get_unchecked_mut() };
+
+        let (ready, notify) = this.offload.with_state(|state| {
+            if this.submitted {
+                let current = state.current.as_mut().expect("Submitted job vanished");
+                if current.done {
+                    state.current = None;
+                    while let Some(waker) = state.waiting.pop_front() {
+                        waker.wake();
+                    }
+                    return (true, None);
+                }
+                current.waker = Some(cx.waker().clone());
+                return (false, None);
+            }
+
+            if state.current.is_none() {
+                let job: *mut (dyn RunJob + 'a) = &mut this.job;
+                // unsafe: Erasing the lifetime; see Current::job
+                let job: *mut (dyn RunJob + 'static) = unsafe { core::mem::transmute(job) };
+                state.current = Some(Current {
+                    job,
+                    started: false,
+                    done: false,
+                    waker: Some(cx.waker().clone()),
+                });
+                this.submitted = true;
+                (false, state.worker)
+            } else {
+                if state.waiting.push_back(cx.waker().clone()).is_err() {
+                    // Can't wait in line; come back right away
+                    cx.waker().wake_by_ref();
+                }
+                (false, None)
+            }
+        });
+
+        if let Some(worker) = notify {
+            let _ = crate::thread::flags::set(
+                worker,
+                crate::thread::flags::Flags::from_bits(OFFLOAD_FLAG),
+            );
+        }
+
+        if ready {
+            Poll::Ready(this.job.result.take().expect("Job was run"))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'a, F, R> Drop for OffloadFuture<'a, F, R> {
+    fn drop(&mut self) {
+        if !self.submitted {
+            return;
+        }
+        let started = self.offload.with_state(|state| {
+            if !self.is_ours(&state.current) {
+                // Completed and already removed
+                return false;
+            }
+            let started = state.current.as_ref().map(|c| c.started) == Some(true);
+            if !started {
+                state.current = None;
+                while let Some(waker) = state.waiting.pop_front() {
+                    waker.wake();
+                }
+            }
+            started
+        });
+        if started {
+            // Wait for the worker to finish the job, then clean up
+            drop(self.offload.running.lock());
+            self.offload.with_state(|state| {
+                state.current = None;
+                while let Some(waker) = state.waiting.pop_front() {
+                    waker.wake();
+                }
+            });
+        }
+    }
+}
+
+/// A blocking device whose operations are run on an [Offload] worker
+///
+/// This implements the embedded-hal-async traits for I²C and SPI devices if the wrapped device
+/// implements the corresponding blocking embedded-hal 1.0 trait.
+#[cfg(feature = "with_embedded_hal_async")]
+pub struct Offloaded<T> {
+    pub inner: T,
+    pub offload: &'static Offload,
+}
+
+#[cfg(feature = "with_embedded_hal_async")]
+impl<T: embedded_hal_1::i2c::ErrorType> embedded_hal_1::i2c::ErrorType for Offloaded<T> {
+    type Error = T::Error;
+}
+
+#[cfg(feature = "with_embedded_hal_async")]
+impl<T> embedded_hal_async::i2c::I2c for Offloaded<T>
+where
+    T: embedded_hal_1::i2c::I2c + Send,
+    T::Error: Send,
+{
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [embedded_hal_1::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let inner = &mut self.inner;
+        self.offload
+            .run(move || inner.transaction(address, operations))
+            .await
+    }
+}
+
+#[cfg(feature = "with_embedded_hal_async")]
+impl<T: embedded_hal_1::spi::ErrorType> embedded_hal_1::spi::ErrorType for Offloaded<T> {
+    type Error = T::Error;
+}
+
+#[cfg(feature = "with_embedded_hal_async")]
+impl<T> embedded_hal_async::spi::SpiDevice for Offloaded<T>
+where
+    T: embedded_hal_1::spi::SpiDevice + Send,
+    T::Error: Send,
+{
+    async fn transaction(
+        &mut self,
+        operations: &mut [embedded_hal_1::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        let inner = &mut self.inner;
+        self.offload
+            .run(move || inner.transaction(operations))
+            .await
+    }
+}
@@ -1,5 +1,53 @@
+//! The panic handler set by the `set_panic_handler` feature
+//!
+//! A panic in a thread stops that thread: It stays around sleeping forever, as anything on its
+//! stack may still be referenced from elsewhere. Threads started through [crate::thread] report
+//! the panic as their [Outcome](crate::thread::Outcome). Panics in interrupts or with interrupts
+//! disabled stop the system through [core_panic](crate::core_panic).
+//!
+//! Applications can register a [hook](set_hook) to be informed of panics in threads, eg. to log
+//! them persistently or to reboot the system.
+
+use core::cell::UnsafeCell;
+use core::panic::PanicInfo;
+
+struct Hook(UnsafeCell<Option<fn(&PanicInfo)>>);
+
+// unsafe: Only accessed in critical sections
+unsafe impl Sync for Hook {}
+
+static HOOK: Hook = Hook(UnsafeCell::new(None));
+
+/// Register a function to be called when a thread panics
+///
+/// The hook is called after the panic message was printed, in the panicking thread. It is not
+/// called for panics that stop the whole system (in interrupts, with interrupts disabled, or with
+/// the `panic_handler_crash` feature), as these do not leave a safe environment to run code in.
+///
+/// Only one hook can be set; setting one replaces any previous hook.
+///
+/// ```no_run
+/// # #![no_std]
+/// fn reboot_on_panic(_info: &core::panic::PanicInfo) {
+///     // unsafe: OK per C API
+///     unsafe { riot_sys::pm_reboot() };
+/// }
+///
+/// riot_wrappers::panic::set_hook(reboot_on_panic);
+/// ```
+pub fn set_hook(hook: fn(&PanicInfo)) {
+    // unsafe: Only accessed in critical sections
+    crate::interrupt::free(|_| unsafe { *HOOK.0.get() = Some(hook) });
+}
+
+/// Remove the hook set through [set_hook]
+pub fn take_hook() -> Option<fn(&PanicInfo)> {
+    // unsafe: Only accessed in critical sections
+    crate::interrupt::free(|_| unsafe { (*HOOK.0.get()).take() })
+}
+
 #[panic_handler]
-fn panic(info: &::core::panic::PanicInfo) -> ! {
+fn panic(info: &PanicInfo) -> ! {
     use crate::thread;
 
     let os_can_continue = crate::thread::InThread::new()
@@ -18,13 +66,10 @@ fn panic(info: &::core::panic::PanicInfo) -> ! {
         // by a UART, and with these the debugger is usually also close enough that the risk of
         // smashing things by overflowing the ISR stack outweighs the benefits.
 
-        unsafe {
-            riot_sys::core_panic(
-                riot_sys::core_panic_t_PANIC_GENERAL_ERROR,
-                cstr::cstr!("RUST PANIC").as_ptr() as _,
-            )
-        };
-        unreachable!()
+        crate::core_panic::core_panic(
+            crate::core_panic::PanicCode::GeneralError,
+            cstr::cstr!("RUST PANIC"),
+        );
     }
 
     // I *guess* it's OK for a panic to simply make a thread into a zombie -- this does allow other
@@ -52,14 +97,22 @@ fn panic(info: &::core::panic::PanicInfo) -> ! {
     }
 
     if cfg!(feature = "panic_handler_crash") {
-        unsafe {
-            riot_sys::core_panic(
-                riot_sys::core_panic_t_PANIC_GENERAL_ERROR,
-                cstr::cstr!("RUST PANIC").as_ptr() as _,
-            )
-        }
+        crate::core_panic::core_panic(
+            crate::core_panic::PanicCode::GeneralError,
+            cstr::cstr!("RUST PANIC"),
+        );
     }
 
+    // unsafe: Only accessed in critical sections
+    let hook = crate::interrupt::free(|_| unsafe { *HOOK.0.get() });
+    if let Some(hook) = hook {
+        hook(info);
+    }
+
+    // Threads spawned through this crate report the panic as their outcome; they are parked
+    // like all others, as their stacks may still be in use.
+    thread::record_panicked_thread();
+
     // Not trying any unwinding -- this thread is just dead, won't be re-claimed, any mutexes it
     // holds are just held indefinitely rather than throwing poison errors.
     loop {
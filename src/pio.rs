@@ -0,0 +1,216 @@
+//! Programmable I/O blocks through RIOT's [PIO](https://doc.riot-os.org/group__drivers__periph__pio.html)
+//! peripheral API
+//!
+//! PIO blocks (currently available on the RP2040) run small programs on dedicated state
+//! machines, which makes it possible to implement custom serial protocols with precise timing.
+//!
+//! The workflow is to load a program into a PIO block's instruction memory ([Program::load]), to
+//! lock one of that block's state machines ([StateMachine::lock]), to configure it for the
+//! program and its pins, and to start it; data is then exchanged through the state machine's
+//! FIFOs:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::pio::{Program, StateMachine};
+//! # const PROGRAM: &[u16] = &[];
+//! # let conf: riot_sys::pio_program_conf_t = unimplemented!();
+//! # let data_pin: riot_sys::gpio_t = unimplemented!();
+//! let program = Program::load(0, PROGRAM).unwrap();
+//! let mut sm = StateMachine::lock(0).unwrap();
+//! sm.init(&program, &conf).unwrap();
+//! sm.set_clock(1_000_000);
+//! sm.set_out_pins(data_pin, 1);
+//! sm.start();
+//! sm.transmit(0xdead_beef);
+//! ```
+//!
+//! Programs are given as assembled PIO instructions, as produced by `pioasm` or the `pio` crate's
+//! assembler; program specific configuration (wrap, side-set) is passed as the C configuration
+//! struct as it would be in C.
+
+use core::marker::PhantomData;
+
+use crate::error::{Errno, NegativeErrorExt, NumericError};
+
+/// A program loaded into the instruction memory of a PIO block
+///
+/// The instruction memory is freed when this is dropped; as any [StateMachine] configured to run
+/// the program borrows it, such state machines are dropped (and thus stopped) before.
+pub struct Program {
+    pio: riot_sys::pio_t,
+    program: riot_sys::pio_program_t,
+}
+
+impl Program {
+    /// Allocate space for the instructions in the given PIO block, and write them there
+    #[doc(alias = "pio_alloc_program")]
+    #[doc(alias = "pio_write_program")]
+    pub fn load(pio: riot_sys::pio_t, instructions: &[u16]) -> Result<Self, NumericError> {
+        let mut program = riot_sys::pio_program_t {
+            instr_numof: instructions.len() as _,
+            ..Default::default()
+        };
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_alloc_program(pio, &mut program) }.negative_to_error()?;
+        let mut result = Program { pio, program };
+        // unsafe: OK per C API; the space was allocated for this many instructions.
+        unsafe {
+            riot_sys::pio_write_program(pio, &mut result.program, instructions.as_ptr() as *const _)
+        }
+        .negative_to_error()?;
+        Ok(result)
+    }
+
+    /// Offset of the program's first instruction in the block's instruction memory
+    pub fn location(&self) -> u32 {
+        self.program.location as _
+    }
+}
+
+impl Drop for Program {
+    #[doc(alias = "pio_free_program")]
+    fn drop(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_free_program(self.pio, &mut self.program) }
+    }
+}
+
+/// A locked state machine of a PIO block
+///
+/// The state machine is stopped and unlocked when this is dropped.
+///
+/// Once [initialized](Self::init) with a program, the state machine borrows it for its remaining
+/// lifetime `'a`.
+pub struct StateMachine<'a> {
+    pio: riot_sys::pio_t,
+    sm: riot_sys::pio_sm_t,
+    _program: PhantomData<&'a Program>,
+}
+
+impl<'a> StateMachine<'a> {
+    /// Lock any free state machine of the given PIO block
+    ///
+    /// Fails with `EBUSY` if all state machines of the block are in use.
+    #[doc(alias = "pio_sm_lock")]
+    pub fn lock(pio: riot_sys::pio_t) -> Result<Self, NumericError> {
+        // unsafe: OK per C API
+        let sm = unsafe { riot_sys::pio_sm_lock(pio) };
+        if sm < 0 {
+            return Err(Errno::Busy.into());
+        }
+        Ok(StateMachine {
+            pio,
+            sm,
+            _program: PhantomData,
+        })
+    }
+
+    /// Configure the state machine to run the program
+    ///
+    /// The configuration is the program specific part as generated along with the program.
+    #[doc(alias = "pio_sm_init_common")]
+    pub fn init(
+        &mut self,
+        program: &'a Program,
+        conf: &riot_sys::pio_program_conf_t,
+    ) -> Result<(), NumericError> {
+        if program.pio != self.pio {
+            return Err(Errno::Inval.into());
+        }
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_init_common(self.pio, self.sm, &program.program, conf) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    /// Set the frequency at which the state machine executes instructions
+    #[doc(alias = "pio_sm_set_clkdiv")]
+    pub fn set_clock(&mut self, frequency_hz: u32) {
+        // unsafe: OK per C API
+        unsafe {
+            let div = riot_sys::pio_sm_clkdiv(frequency_hz);
+            riot_sys::pio_sm_set_clkdiv(self.pio, self.sm, div);
+        }
+    }
+
+    /// Map the pins written by `out` instructions, starting at `base`
+    #[doc(alias = "pio_sm_set_out_pins")]
+    pub fn set_out_pins(&mut self, base: riot_sys::gpio_t, count: u32) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_set_out_pins(self.pio, self.sm, base, count as _) }
+    }
+
+    /// Map the pins written by `set` instructions, starting at `base`
+    #[doc(alias = "pio_sm_set_set_pins")]
+    pub fn set_set_pins(&mut self, base: riot_sys::gpio_t, count: u32) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_set_set_pins(self.pio, self.sm, base, count as _) }
+    }
+
+    /// Map the pins read by `in` instructions, starting at `base`
+    #[doc(alias = "pio_sm_set_in_pins")]
+    pub fn set_in_pins(&mut self, base: riot_sys::gpio_t) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_set_in_pins(self.pio, self.sm, base) }
+    }
+
+    /// Map the pins driven by side-set, starting at `base`
+    #[doc(alias = "pio_sm_set_sideset_pins")]
+    pub fn set_sideset_pins(&mut self, base: riot_sys::gpio_t) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_set_sideset_pins(self.pio, self.sm, base) }
+    }
+
+    /// Start executing the program
+    #[doc(alias = "pio_sm_start")]
+    pub fn start(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_start(self.pio, self.sm) }
+    }
+
+    /// Pause execution of the program
+    #[doc(alias = "pio_sm_stop")]
+    pub fn stop(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_stop(self.pio, self.sm) }
+    }
+
+    /// Reset the state machine's internal state, keeping its configuration
+    #[doc(alias = "pio_sm_restart")]
+    pub fn restart(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_restart(self.pio, self.sm) }
+    }
+
+    /// Push a word into the transmit FIFO, waiting for space if it is full
+    #[doc(alias = "pio_sm_transmit_word_block")]
+    pub fn transmit(&mut self, word: u32) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_transmit_word_block(self.pio, self.sm, word) }
+    }
+
+    /// Push each word into the transmit FIFO, waiting for space when it is full
+    pub fn transmit_all(&mut self, words: &[u32]) {
+        for word in words {
+            self.transmit(*word);
+        }
+    }
+
+    /// Pull a word from the receive FIFO, waiting for one if it is empty
+    #[doc(alias = "pio_sm_receive_word_block")]
+    pub fn receive(&mut self) -> u32 {
+        let mut word = 0;
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_receive_word_block(self.pio, self.sm, &mut word) };
+        word
+    }
+}
+
+impl Drop for StateMachine<'_> {
+    #[doc(alias = "pio_sm_unlock")]
+    fn drop(&mut self) {
+        self.stop();
+        // unsafe: OK per C API
+        unsafe { riot_sys::pio_sm_unlock(self.pio, self.sm) }
+    }
+}
@@ -0,0 +1,160 @@
+//! Power management through RIOT's [layered power
+//! modes](https://doc.riot-os.org/group__sys__pm__layered.html) and hooks around idling
+//!
+//! When no thread is ready to run, RIOT puts the CPU into the lowest power mode that is not
+//! blocked. A [Block] guard keeps the CPU out of a mode (and all lower ones) for as long as it is
+//! alive, eg. while a peripheral is in use that does not keep running in deeper modes.
+//!
+//! With [set_idle_hooks], an application can additionally run code whenever the system starts and
+//! stops idling, eg. to switch off an external regulator or to measure how much time the CPU
+//! spends sleeping.
+//!
+//! The [accounting] module builds on this to keep track of how long the CPU spends in which power
+//! mode.
+
+/// Number of power modes of the CPU
+///
+/// Mode 0 is the deepest; modes are blocked by their number.
+#[cfg(riot_module_pm_layered)]
+pub const NUM_MODES: u8 = riot_sys::PM_NUM_MODES as _;
+
+/// A guard that keeps the CPU from entering the given power mode (and any deeper ones)
+///
+/// The mode is unblocked when the guard is dropped.
+#[cfg(riot_module_pm_layered)]
+pub struct Block {
+    mode: u8,
+}
+
+#[cfg(riot_module_pm_layered)]
+impl Block {
+    /// Block the given power mode
+    ///
+    /// Panics if the mode is not a valid mode of the CPU.
+    #[doc(alias = "pm_block")]
+    pub fn new(mode: u8) -> Self {
+        assert!(mode < NUM_MODES, "Invalid power mode");
+        // unsafe: OK per C API; the mode is valid.
+        unsafe { riot_sys::pm_block(mode as _) };
+        Block { mode }
+    }
+
+    /// The mode blocked by this guard
+    pub fn mode(&self) -> u8 {
+        self.mode
+    }
+}
+
+#[cfg(riot_module_pm_layered)]
+impl Drop for Block {
+    #[doc(alias = "pm_unblock")]
+    fn drop(&mut self) {
+        // unsafe: OK per C API; the mode was blocked by this guard.
+        unsafe { riot_sys::pm_unblock(self.mode as _) };
+    }
+}
+
+/// Callbacks around the system's idle phases, see [set_idle_hooks]
+///
+/// Both are called from the scheduler with interrupts disabled, and thus need to be short and
+/// must not block. Before `enter`, the CPU is still running at full power; after `exit` it is
+/// again.
+#[cfg(all(riot_module_sched_cb, riot_module_core_idle_thread))]
+pub trait IdleHooks: Send {
+    /// Called when no thread is ready to run, right before the CPU starts idling
+    fn enter(&mut self);
+    /// Called when a thread becomes ready to run after idling
+    fn exit(&mut self);
+}
+
+#[cfg(all(riot_module_sched_cb, riot_module_core_idle_thread))]
+struct IdleState {
+    hooks: &'static mut dyn IdleHooks,
+    idle_pid: riot_sys::kernel_pid_t,
+}
+
+#[cfg(all(riot_module_sched_cb, riot_module_core_idle_thread))]
+static IDLE_STATE: crate::interrupt::IsrCell<IdleState> = crate::interrupt::IsrCell::new();
+
+/// Called on every context switch by the scheduler callback (see [crate::thread::set_switch_hook])
+#[cfg(all(riot_module_sched_cb, riot_module_core_idle_thread))]
+pub(crate) fn on_switch(
+    cs: &crate::interrupt::CriticalSection,
+    active: riot_sys::kernel_pid_t,
+    next: riot_sys::kernel_pid_t,
+) {
+    IDLE_STATE.with_cs(cs, |state| {
+        if next == state.idle_pid {
+            state.hooks.enter();
+        } else if active == state.idle_pid {
+            state.hooks.exit();
+        }
+    });
+    #[cfg(all(riot_module_pm_layered, riot_module_ztimer_msec))]
+    accounting::on_switch(cs, active, next);
+}
+
+/// Find the idle thread by its priority, which no other thread may have
+#[cfg(all(riot_module_sched_cb, riot_module_core_idle_thread))]
+fn idle_pid() -> Option<crate::thread::KernelPID> {
+    let idle_priority = (riot_sys::SCHED_PRIO_LEVELS - 1) as u8;
+    crate::thread::KernelPID::all_pids().find(|pid| pid.priority().ok() == Some(idle_priority))
+}
+
+/// Error returned by [set_idle_hooks]
+#[cfg(all(riot_module_sched_cb, riot_module_core_idle_thread))]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SetIdleHooksError {
+    /// Hooks were already set
+    AlreadySet,
+    /// The idle thread could not be found
+    NoIdleThread,
+}
+
+/// Install hooks that are called when the system starts and stops idling
+///
+/// Idling is detected as the scheduler switching to and from the idle thread, which is why this
+/// needs the `core_idle_thread` module (which is not used by default on all platforms). It also
+/// uses the `sched_cb` module's callback, which is then not available to C code (but can still be
+/// used through [crate::thread::set_switch_hook]).
+///
+/// Hooks can only be set once.
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::pm::{set_idle_hooks, IdleHooks};
+/// struct SleepCounter(u32);
+/// impl IdleHooks for SleepCounter {
+///     fn enter(&mut self) {
+///         self.0 += 1;
+///     }
+///     fn exit(&mut self) {}
+/// }
+/// static mut COUNTER: SleepCounter = SleepCounter(0);
+/// // unsafe: COUNTER is not accessed anywhere else
+/// set_idle_hooks(unsafe { &mut COUNTER }).unwrap();
+/// ```
+#[cfg(all(riot_module_sched_cb, riot_module_core_idle_thread))]
+#[doc(alias = "sched_register_cb")]
+pub fn set_idle_hooks(hooks: &'static mut dyn IdleHooks) -> Result<(), SetIdleHooksError> {
+    let idle_pid = idle_pid().ok_or(SetIdleHooksError::NoIdleThread)?;
+
+    IDLE_STATE
+        .init(IdleState {
+            hooks,
+            idle_pid: idle_pid.into(),
+        })
+        .map_err(|_| SetIdleHooksError::AlreadySet)?;
+
+    crate::thread::switch_hook::install();
+    Ok(())
+}
+
+#[cfg(all(
+    riot_module_sched_cb,
+    riot_module_core_idle_thread,
+    riot_module_pm_layered,
+    riot_module_ztimer_msec
+))]
+pub mod accounting;
@@ -0,0 +1,186 @@
+//! Accounting of the time spent in the CPU's power modes
+//!
+//! Once [started](start), every idle phase is timed and attributed to the power mode the system
+//! enters for it (which is the deepest mode that is neither [blocked](super::Block) nor deeper
+//! than a blocked mode at that time), and the time between idle phases is counted as active.
+//! Combined with the current consumption of the board in each mode, this gives an estimate of the
+//! battery charge used, which applications can use eg. to lengthen their duty cycle when they use
+//! more than planned:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::println;
+//! use riot_wrappers::pm::accounting;
+//!
+//! accounting::start().unwrap();
+//! // ... later
+//! let times = accounting::read().unwrap();
+//! // 5mA when active, 2µA in mode 0 and 400µA in mode 1, from the board's data sheet
+//! let used = times.charge_uc(5000, &[2, 400]);
+//! println!("{} ms active, {} µC used", times.active_ms, used);
+//! ```
+//!
+//! Times are measured on `ZTIMER_MSEC`. On boards where that clock stops in some power modes,
+//! time spent in them is not counted.
+//!
+//! Accounting uses the scheduler callback just like [set_idle_hooks](super::set_idle_hooks),
+//! and coexists with that and with [crate::thread::set_switch_hook].
+
+use crate::interrupt::{CriticalSection, IsrCell};
+use crate::ztimer::Clock;
+
+/// Number of idle states: one per power mode, and one for idling while all modes are blocked
+const SLOTS: usize = riot_sys::PM_NUM_MODES as usize + 1;
+
+struct State {
+    idle_pid: riot_sys::kernel_pid_t,
+    /// Clock reading at the start of the current phase
+    since: u32,
+    /// Slot of the current idle phase, or None while active
+    idle_in: Option<usize>,
+    times: ModeTimes,
+}
+
+impl State {
+    /// Add the time since the start of the current phase to that phase, and start a new one
+    fn flush(&mut self) {
+        let now = Clock::msec().now().0;
+        let elapsed = now.wrapping_sub(self.since) as u64;
+        self.since = now;
+        match self.idle_in {
+            Some(slot) => self.times.idle_ms[slot] += elapsed,
+            None => self.times.active_ms += elapsed,
+        }
+    }
+}
+
+static STATE: IsrCell<State> = IsrCell::new();
+
+/// The power mode the system will enter when idling now, or NUM_MODES if all are blocked
+fn lowest_unblocked() -> usize {
+    // unsafe: OK per C API
+    let blocker = unsafe { riot_sys::pm_get_blocker() };
+    // A blocked mode also keeps the system out of all deeper (lower numbered) modes, so the mode
+    // above the highest numbered blocked one is used
+    blocker
+        .blockers
+        .iter()
+        .rposition(|b| *b != 0)
+        .map_or(0, |i| i + 1)
+}
+
+/// Called on every context switch by [super::on_switch]
+pub(crate) fn on_switch(
+    cs: &CriticalSection,
+    active: riot_sys::kernel_pid_t,
+    next: riot_sys::kernel_pid_t,
+) {
+    STATE.with_cs(cs, |state| {
+        if next == state.idle_pid {
+            state.flush();
+            state.idle_in = Some(lowest_unblocked());
+        } else if active == state.idle_pid {
+            state.flush();
+            state.idle_in = None;
+        }
+    });
+}
+
+/// Time spent active and in the individual power modes since accounting was started or
+/// [reset]
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct ModeTimes {
+    /// Milliseconds in which a thread was running
+    pub active_ms: u64,
+    idle_ms: [u64; SLOTS],
+}
+
+impl ModeTimes {
+    /// Milliseconds spent idling in the given power mode
+    ///
+    /// Returns None if the mode is not a valid mode of the CPU.
+    pub fn in_mode_ms(&self, mode: u8) -> Option<u64> {
+        match mode {
+            m if m < super::NUM_MODES => Some(self.idle_ms[m as usize]),
+            _ => None,
+        }
+    }
+
+    /// Milliseconds spent idling while all power modes were blocked
+    ///
+    /// The CPU is then typically halted until the next interrupt, but its peripherals keep
+    /// running.
+    pub fn idle_unslept_ms(&self) -> u64 {
+        self.idle_ms[SLOTS - 1]
+    }
+
+    /// Total milliseconds accounted for
+    pub fn total_ms(&self) -> u64 {
+        self.active_ms + self.idle_ms.iter().sum::<u64>()
+    }
+
+    /// Estimate the charge used, in µC (µAs)
+    ///
+    /// `active_ua` is the current drawn while running (in µA), and `mode_ua[i]` the current drawn
+    /// in power mode `i`. Time in modes without a given current, as well as idle time without a
+    /// power mode, is counted at the active current, which errs on the side of caution.
+    pub fn charge_uc(&self, active_ua: u32, mode_ua: &[u32]) -> u64 {
+        let mut ua_ms = (self.active_ms + self.idle_unslept_ms()) * active_ua as u64;
+        for (mode, ms) in self.idle_ms[..SLOTS - 1].iter().enumerate() {
+            ua_ms += ms * *mode_ua.get(mode).unwrap_or(&active_ua) as u64;
+        }
+        ua_ms / 1000
+    }
+}
+
+/// Error returned by [start]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StartError {
+    /// Accounting was already started
+    AlreadyStarted,
+    /// The idle thread could not be found
+    NoIdleThread,
+}
+
+/// Start accounting, with all times at zero
+///
+/// Accounting can only be started once; use [reset] to start over.
+#[doc(alias = "sched_register_cb")]
+pub fn start() -> Result<(), StartError> {
+    let idle_pid = super::idle_pid().ok_or(StartError::NoIdleThread)?;
+
+    STATE
+        .init(State {
+            idle_pid: idle_pid.into(),
+            since: Clock::msec().now().0,
+            idle_in: None,
+            times: Default::default(),
+        })
+        .map_err(|_| StartError::AlreadyStarted)?;
+
+    crate::thread::switch_hook::install();
+    Ok(())
+}
+
+/// Obtain the times accounted so far, up to now
+///
+/// Returns None if accounting was not started.
+pub fn read() -> Option<ModeTimes> {
+    STATE.with(|state| {
+        state.flush();
+        state.times
+    })
+}
+
+/// Set all times back to zero
+///
+/// This is typically used after [read]ing the times at the end of a measurement period.
+pub fn reset() {
+    STATE.with(|state| {
+        state.flush();
+        state.times = Default::default();
+    });
+}
@@ -0,0 +1,152 @@
+//! Access to the [PTP clock](https://doc.riot-os.org/group__drivers__periph__ptp.html) of network
+//! peripherals
+//!
+//! Some Ethernet peripherals have a high resolution clock that is also used to timestamp frames,
+//! and which can be adjusted in speed and offset. This is the building block for time
+//! synchronization protocols like PTP (IEEE 1588) or NTP-like protocols with hardware
+//! timestamps: Timestamps of received datagrams are available through the socket's auxiliary data
+//! (see [crate::socket::RxAux] with the `sock_aux_timestamp` module), and the clock is corrected
+//! using [adjust] and [adjust_speed].
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::ptp;
+//! # let (t1, t2, t3, t4): (i64, i64, i64, i64) = unimplemented!();
+//! // With t1..t4 the usual PTP timestamps in nanoseconds, correct the local clock by the offset
+//! let offset = ((t2 - t1) - (t4 - t3)) / 2;
+//! ptp::adjust(-offset);
+//! ```
+//!
+//! The clock's epoch is not defined by RIOT; it is whatever the application
+//! [sets](set) it to (typically TAI for PTP).
+
+/// A point in time on the PTP clock
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Timestamp {
+    /// Seconds since the clock's epoch
+    pub seconds: u64,
+    /// Nanoseconds in addition to the seconds, always less than 1 000 000 000
+    pub nanoseconds: u32,
+}
+
+const NS_PER_SEC: u64 = 1_000_000_000;
+
+impl Timestamp {
+    /// Build a timestamp from nanoseconds since the clock's epoch
+    pub fn from_ns(ns: u64) -> Self {
+        Timestamp {
+            seconds: ns / NS_PER_SEC,
+            nanoseconds: (ns % NS_PER_SEC) as u32,
+        }
+    }
+
+    /// Express the timestamp in nanoseconds since the clock's epoch
+    ///
+    /// This overflows (and panics in debug builds) for timestamps past about 584 years.
+    pub fn as_ns(&self) -> u64 {
+        self.seconds * NS_PER_SEC + self.nanoseconds as u64
+    }
+
+    fn from_c(ts: &riot_sys::ptp_timestamp_t) -> Self {
+        Timestamp {
+            seconds: ts.seconds as _,
+            nanoseconds: ts.nanoseconds,
+        }
+    }
+
+    fn to_c(&self) -> riot_sys::ptp_timestamp_t {
+        riot_sys::ptp_timestamp_t {
+            seconds: self.seconds as _,
+            nanoseconds: self.nanoseconds,
+        }
+    }
+}
+
+/// Read the current time of the PTP clock
+#[doc(alias = "ptp_clock_read")]
+pub fn read() -> Timestamp {
+    let mut ts = Default::default();
+    // unsafe: OK per C API
+    unsafe { riot_sys::ptp_clock_read(&mut ts) };
+    Timestamp::from_c(&ts)
+}
+
+/// Set the PTP clock to the given time
+///
+/// This is a jump in time; for small corrections, [adjust] is preferable.
+#[doc(alias = "ptp_clock_set")]
+pub fn set(time: &Timestamp) {
+    let ts = time.to_c();
+    // unsafe: OK per C API
+    unsafe { riot_sys::ptp_clock_set(&ts) };
+}
+
+/// Shift the PTP clock by the given number of nanoseconds
+///
+/// Unlike reading, modifying and [setting](set) the time, this introduces no error from the time
+/// that passes in between.
+#[doc(alias = "ptp_clock_adjust")]
+pub fn adjust(offset_ns: i64) {
+    // unsafe: OK per C API
+    unsafe { riot_sys::ptp_clock_adjust(offset_ns) };
+}
+
+/// Change the speed of the PTP clock
+///
+/// The clock then runs at its nominal speed multiplied with `1 + correction / 2^32`, ie. the
+/// correction is in units of about 0.23 ppb. A correction of 0 restores the nominal speed.
+///
+/// How finely this can be adjusted depends on the hardware; the correction is rounded to what the
+/// peripheral supports.
+#[doc(alias = "ptp_clock_adjust_speed")]
+pub fn adjust_speed(correction: i32) {
+    // unsafe: OK per C API
+    unsafe { riot_sys::ptp_clock_adjust_speed(correction) };
+}
+
+/// Arm the PTP timer to fire at the given time
+///
+/// When it fires, the function exported through [ptp_timer_handler!] is called in interrupt
+/// context. Setting the timer again replaces any earlier setting.
+#[cfg(riot_module_periph_ptp_timer)]
+#[doc(alias = "ptp_timer_set_absolute")]
+pub fn set_timer(target: &Timestamp) {
+    let ts = target.to_c();
+    // unsafe: OK per C API
+    unsafe { riot_sys::ptp_timer_set_absolute(&ts) };
+}
+
+/// Disarm the PTP timer
+#[cfg(riot_module_periph_ptp_timer)]
+#[doc(alias = "ptp_timer_clear")]
+pub fn clear_timer() {
+    // unsafe: OK per C API
+    unsafe { riot_sys::ptp_timer_clear() };
+}
+
+/// Export a function `fn(&InIsr)` as the PTP timer callback (`ptp_timer_cb`)
+///
+/// RIOT expects the application to provide that callback when the `periph_ptp_timer` module is
+/// used; this macro may only be used once in an application, and not if the callback is already
+/// implemented in C.
+///
+/// ```no_run
+/// # #![no_std]
+/// fn on_ptp_timer(_: &riot_wrappers::thread::InIsr) {
+///     // eg. toggle a pulse-per-second output
+/// }
+/// riot_wrappers::ptp_timer_handler!(on_ptp_timer);
+/// ```
+#[cfg(riot_module_periph_ptp_timer)]
+#[macro_export]
+macro_rules! ptp_timer_handler {
+    ($func:path) => {
+        #[no_mangle]
+        pub extern "C" fn ptp_timer_cb() {
+            let isr = $crate::thread::InIsr::new()
+                .expect("PTP timer callback called outside interrupt context");
+            $func(&isr);
+        }
+    };
+}
@@ -0,0 +1,305 @@
+//! Runtime configuration through RIOT's [registry](https://doc.riot-os.org/group__sys__registry.html)
+//!
+//! The registry organizes configuration into groups of named parameters, which are addressed by
+//! slash separated names (`group/parameter`). Values are exchanged as strings. Parameters can be
+//! changed at runtime (eg. through the `registry` shell command), and are persisted and restored
+//! by whichever storage backends the application has registered on the C side.
+//!
+//! A Rust component provides its configuration by implementing [Group] and registering it
+//! through a [Registration]:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::registry::{Group, Registration, Exporter};
+//! # use riot_wrappers::error::{Errno, NumericError};
+//! # use core::fmt::Write;
+//! struct Blinker {
+//!     period_ms: u32,
+//! }
+//!
+//! impl Group for Blinker {
+//!     fn get(&self, name: &[&str], out: &mut dyn Write) -> Result<(), NumericError> {
+//!         match name {
+//!             ["period"] => write!(out, "{}", self.period_ms).map_err(|_| Errno::NoSpc.into()),
+//!             _ => Err(Errno::NoEnt.into()),
+//!         }
+//!     }
+//!
+//!     fn set(&mut self, name: &[&str], value: &str) -> Result<(), NumericError> {
+//!         match name {
+//!             ["period"] => {
+//!                 self.period_ms = value.parse().map_err(|_| Errno::Inval)?;
+//!                 Ok(())
+//!             }
+//!             _ => Err(Errno::NoEnt.into()),
+//!         }
+//!     }
+//!
+//!     fn export(&self, exporter: &mut Exporter) -> Result<(), NumericError> {
+//!         exporter.export_display("period", &self.period_ms)
+//!     }
+//! }
+//!
+//! static mut BLINKER: Registration<Blinker> =
+//!     Registration::new(cstr::cstr!("blinker"), Blinker { period_ms: 500 });
+//! // unsafe: BLINKER is not accessed anywhere else
+//! unsafe { BLINKER.register() };
+//! riot_wrappers::registry::load();
+//! ```
+
+use core::ffi::CStr;
+use core::fmt::Write;
+
+use riot_sys::libc;
+
+use crate::error::{Errno, NegativeErrorExt, NumericError};
+
+/// Maximum number of name components passed into a [Group]
+///
+/// This is the number of components the registry splits names into, minus the group name.
+const MAX_NAME_COMPONENTS: usize = riot_sys::REGISTRY_MAX_ARGS as usize - 1;
+
+/// Buffer size for names and values produced during an export
+const EXPORT_BUFFER: usize = riot_sys::REGISTRY_MAX_NAME_LEN as usize + 1;
+
+/// A group of configuration parameters
+///
+/// The `name` arguments are the components of the parameter's name after the group name; in most
+/// cases, this is a single element.
+pub trait Group {
+    /// Write the current value of the named parameter
+    fn get(&self, name: &[&str], out: &mut dyn Write) -> Result<(), NumericError>;
+
+    /// Set the named parameter
+    ///
+    /// Implementations may defer the effects of a change until [.commit()](Group::commit) is
+    /// called, eg. to apply several dependent parameters at once.
+    fn set(&mut self, name: &[&str], value: &str) -> Result<(), NumericError>;
+
+    /// Apply any changes made through [.set()](Group::set)
+    ///
+    /// This is called after a batch of values was loaded from storage, or on explicit request.
+    fn commit(&mut self) -> Result<(), NumericError> {
+        Ok(())
+    }
+
+    /// Report all parameters of the group along with their current values
+    ///
+    /// This is used when storing the configuration.
+    fn export(&self, exporter: &mut Exporter) -> Result<(), NumericError>;
+}
+
+/// Sink for the parameters reported by [Group::export]
+pub struct Exporter<'a> {
+    group: &'a str,
+    export_func: unsafe extern "C" fn(*const libc::c_char, *mut libc::c_char) -> libc::c_int,
+}
+
+impl<'a> Exporter<'a> {
+    /// Report a parameter with its value
+    pub fn export(&mut self, name: &str, value: &str) -> Result<(), NumericError> {
+        self.export_display(name, &value)
+    }
+
+    /// Report a parameter with the value given in its Display form
+    ///
+    /// Fails with `ENOSPC` if the full name or the value exceed the registry's maximum length.
+    pub fn export_display(
+        &mut self,
+        name: &str,
+        value: &dyn core::fmt::Display,
+    ) -> Result<(), NumericError> {
+        let mut full_name: heapless::String<EXPORT_BUFFER> = heapless::String::new();
+        let mut value_buf: heapless::String<EXPORT_BUFFER> = heapless::String::new();
+        write!(full_name, "{}/{}\0", self.group, name).map_err(|_| Errno::NoSpc)?;
+        write!(value_buf, "{}\0", value).map_err(|_| Errno::NoSpc)?;
+        // unsafe: OK per C API; both are null terminated, and the value is not retained.
+        unsafe { (self.export_func)(full_name.as_ptr() as *const _, value_buf.as_ptr() as *mut _) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+}
+
+/// Writer into a C buffer, as used for values requested by the registry
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    written: usize,
+}
+
+impl<'a> Write for SliceWriter<'a> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let dest = self
+            .buf
+            .get_mut(self.written..self.written + s.len())
+            .ok_or(core::fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.written += s.len();
+        Ok(())
+    }
+}
+
+/// A [Group] along with the handler struct through which the registry accesses it
+pub struct Registration<G: Group> {
+    handler: riot_sys::registry_handler_t,
+    group: G,
+}
+
+impl<G: Group> Registration<G> {
+    /// Prepare a group for registration under the given name
+    pub const fn new(name: &'static CStr, group: G) -> Self {
+        Registration {
+            handler: riot_sys::registry_handler_t {
+                node: riot_sys::clist_node_t {
+                    next: core::ptr::null_mut(),
+                },
+                name: name.as_ptr() as *mut _,
+                hndlr_get: Some(Self::get),
+                hndlr_set: Some(Self::set),
+                hndlr_commit: Some(Self::commit),
+                hndlr_export: Some(Self::export),
+                context: core::ptr::null_mut(),
+            },
+            group,
+        }
+    }
+
+    /// Make the group accessible through the registry
+    #[doc(alias = "registry_register")]
+    pub fn register(&'static mut self) {
+        self.handler.context = self as *mut Self as *mut _;
+        // unsafe: OK per C API; the handler is 'static.
+        unsafe { riot_sys::registry_register(&mut self.handler) };
+    }
+
+    /// Access the group from Rust
+    ///
+    /// Note that after registration, the registry may access the group from other threads (eg.
+    /// the shell); the group then needs to be accessed by other means (eg. a mutex inside it).
+    pub fn group(&mut self) -> &mut G {
+        &mut self.group
+    }
+
+    /// Collect argv into name components
+    ///
+    /// unsafe: argv needs to contain argc valid C strings.
+    unsafe fn with_name<R>(
+        argc: libc::c_int,
+        argv: *mut *mut libc::c_char,
+        f: impl FnOnce(&[&str]) -> Result<R, NumericError>,
+    ) -> Result<R, NumericError> {
+        let mut components: heapless::Vec<&str, MAX_NAME_COMPONENTS> = heapless::Vec::new();
+        for i in 0..argc as usize {
+            let component = CStr::from_ptr(*argv.add(i))
+                .to_str()
+                .map_err(|_| Errno::NoEnt)?;
+            components.push(component).map_err(|_| Errno::NoEnt)?;
+        }
+        f(&components)
+    }
+
+    unsafe extern "C" fn get(
+        argc: libc::c_int,
+        argv: *mut *mut libc::c_char,
+        val: *mut libc::c_char,
+        val_len_max: libc::c_int,
+        context: *mut libc::c_void,
+    ) -> *mut libc::c_char {
+        let this = &*(context as *const Self);
+        let buf = core::slice::from_raw_parts_mut(val as *mut u8, val_len_max as _);
+        let mut cursor = SliceWriter { buf, written: 0 };
+        let result = Self::with_name(argc, argv, |name| {
+            this.group.get(name, &mut cursor)?;
+            cursor.write_char('\0').map_err(|_| Errno::NoSpc.into())
+        });
+        match result {
+            Ok(()) => val,
+            Err(_) => core::ptr::null_mut(),
+        }
+    }
+
+    unsafe extern "C" fn set(
+        argc: libc::c_int,
+        argv: *mut *mut libc::c_char,
+        val: *mut libc::c_char,
+        context: *mut libc::c_void,
+    ) -> libc::c_int {
+        let this = &mut *(context as *mut Self);
+        let result = Self::with_name(argc, argv, |name| {
+            let value = CStr::from_ptr(val).to_str().map_err(|_| Errno::Inval)?;
+            this.group.set(name, value)
+        });
+        match result {
+            Ok(()) => 0,
+            Err(e) => e.number as _,
+        }
+    }
+
+    unsafe extern "C" fn commit(context: *mut libc::c_void) -> libc::c_int {
+        let this = &mut *(context as *mut Self);
+        match this.group.commit() {
+            Ok(()) => 0,
+            Err(e) => e.number as _,
+        }
+    }
+
+    unsafe extern "C" fn export(
+        export_func: Option<
+            unsafe extern "C" fn(*const libc::c_char, *mut libc::c_char) -> libc::c_int,
+        >,
+        _argc: libc::c_int,
+        _argv: *mut *mut libc::c_char,
+        context: *mut libc::c_void,
+    ) -> libc::c_int {
+        let this = &*(context as *const Self);
+        let export_func = match export_func {
+            Some(f) => f,
+            None => return NumericError::from(Errno::Inval).number as _,
+        };
+        let group = match CStr::from_ptr(this.handler.name).to_str() {
+            Ok(group) => group,
+            Err(_) => return NumericError::from(Errno::Inval).number as _,
+        };
+        let mut exporter = Exporter { group, export_func };
+        match this.group.export(&mut exporter) {
+            Ok(()) => 0,
+            Err(e) => e.number as _,
+        }
+    }
+}
+
+/// Load all values from the registered storage backends into the groups, and commit them
+#[doc(alias = "registry_load")]
+pub fn load() -> Result<(), NumericError> {
+    // unsafe: OK per C API
+    unsafe { riot_sys::registry_load() }
+        .negative_to_error()
+        .map(|_| ())
+}
+
+/// Store all groups' current values in the registered storage backend
+#[doc(alias = "registry_save")]
+pub fn save() -> Result<(), NumericError> {
+    // unsafe: OK per C API
+    unsafe { riot_sys::registry_save() }
+        .negative_to_error()
+        .map(|_| ())
+}
+
+/// Set a parameter by its full name, as the registry shell command does
+#[doc(alias = "registry_set_value")]
+pub fn set(name: &CStr, value: &CStr) -> Result<(), NumericError> {
+    // unsafe: OK per C API; the C function does not actually modify the strings.
+    unsafe { riot_sys::registry_set_value(name.as_ptr() as *mut _, value.as_ptr() as *mut _) }
+        .negative_to_error()
+        .map(|_| ())
+}
+
+/// Commit all groups, or the named group
+#[doc(alias = "registry_commit")]
+pub fn commit(name: Option<&CStr>) -> Result<(), NumericError> {
+    let name = name.map_or(core::ptr::null_mut(), |n| n.as_ptr() as *mut _);
+    // unsafe: OK per C API
+    unsafe { riot_sys::registry_commit(name) }
+        .negative_to_error()
+        .map(|_| ())
+}
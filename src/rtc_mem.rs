@@ -0,0 +1,328 @@
+//! Access to memory that persists across deep sleep (backup RAM)
+//!
+//! Some MCUs retain a small memory area in the RTC domain even when the rest of the system is
+//! powered down. RIOT exposes it through the [periph_rtc_mem] API as a byte array accessed by
+//! offset; this module provides that API with bounds checks ([`read()`], [`write()`]) and a typed
+//! [`Slot`] that stores an [`AsBytes`] value along with a checksum, so that stale or
+//! never-initialized memory is not mistaken for a stored value. A [`RecordStore`] additionally tags the stored value with a
+//! magic number and a version, for state that needs to survive firmware updates.
+//!
+//! The checksums are calculated by RIOT, so this module is only available when the `checksum`
+//! module is enabled in addition to `periph_rtc_mem`.
+//!
+//! Slots and record stores are placed manually by the application; it is the application's
+//! responsibility to not place them (or C users of the memory) in overlapping areas.
+//!
+//! [periph_rtc_mem]: https://doc.riot-os.org/group__drivers__periph__rtc__mem.html
+
+use core::marker::PhantomData;
+
+/// Error returned when an access would exceed the available persistent memory
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OutOfBounds;
+
+/// Size of the persistent memory in bytes
+#[doc(alias = "rtc_mem_size")]
+pub fn size() -> usize {
+    // unsafe: C function has no preconditions
+    unsafe { riot_sys::rtc_mem_size() as _ }
+}
+
+fn check_bounds(offset: usize, len: usize) -> Result<(), OutOfBounds> {
+    match offset.checked_add(len) {
+        Some(end) if end <= size() => Ok(()),
+        _ => Err(OutOfBounds),
+    }
+}
+
+/// Read `data.len()` bytes starting at `offset` from the persistent memory
+#[doc(alias = "rtc_mem_read")]
+pub fn read(offset: usize, data: &mut [u8]) -> Result<(), OutOfBounds> {
+    check_bounds(offset, data.len())?;
+    // unsafe: Bounds were checked, and data is valid for writing len bytes
+    unsafe { riot_sys::rtc_mem_read(offset as _, data.as_mut_ptr() as _, data.len() as _) };
+    Ok(())
+}
+
+/// Write `data` into the persistent memory starting at `offset`
+#[doc(alias = "rtc_mem_write")]
+pub fn write(offset: usize, data: &[u8]) -> Result<(), OutOfBounds> {
+    check_bounds(offset, data.len())?;
+    // unsafe: Bounds were checked, and data is valid for reading len bytes
+    unsafe { riot_sys::rtc_mem_write(offset as _, data.as_ptr() as _, data.len() as _) };
+    Ok(())
+}
+
+/// A type that can be stored in persistent memory by copying its in-memory representation
+///
+/// This is implemented for the unsigned and signed integer types and for arrays of such types;
+/// applications can implement it for their own `#[repr(C)]` state structures.
+///
+/// Note that the representation of integers is native endian, so persistent memory written by one
+/// board is not generally understood by another -- which is not an issue for memory that never
+/// leaves the device.
+///
+/// # Safety
+///
+/// Implementing types must not contain any padding bytes (which would be read uninitialized when
+/// storing), and every bit pattern of the type's size must be a valid value: Loading only checks
+/// that the stored checksum matches, and then uses the bytes as they are.
+pub unsafe trait AsBytes: Copy {}
+
+macro_rules! as_bytes_int {
+    ($($t:ty),*) => {
+        $(
+            // unsafe: Integers have no padding, and all bit patterns are valid
+            unsafe impl AsBytes for $t {}
+        )*
+    };
+}
+
+as_bytes_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+// unsafe: Arrays have no padding between their elements, and are valid if all elements are valid
+unsafe impl<T: AsBytes, const N: usize> AsBytes for [T; N] {}
+
+fn as_bytes<T: AsBytes>(value: &T) -> &[u8] {
+    // unsafe: T has no padding bytes per the AsBytes requirements, so all its bytes are
+    // initialized.
+    unsafe {
+        core::slice::from_raw_parts(value as *const T as *const u8, core::mem::size_of::<T>())
+    }
+}
+
+/// Read a value from persistent memory, returning it if `check` accepts its bytes
+fn read_value<T: AsBytes>(offset: usize, check: impl FnOnce(&[u8]) -> bool) -> Option<T> {
+    // unsafe: All bit patterns (including all zero) are valid for T per the AsBytes requirements
+    let mut value: T = unsafe { core::mem::MaybeUninit::zeroed().assume_init() };
+    // unsafe: As above; any bytes written into the value leave it valid.
+    let bytes = unsafe {
+        core::slice::from_raw_parts_mut(&mut value as *mut T as *mut u8, core::mem::size_of::<T>())
+    };
+    read(offset, bytes).ok()?;
+    if check(bytes) {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Length of the checksum appended to the data in a [`Slot`] or a [`RecordStore`]
+pub const CHECKSUM_LEN: usize = 2;
+
+/// CRC-16/CCITT-FALSE over the concatenation of all parts, as calculated by RIOT's checksum module
+///
+/// This is the checksum used by all persistent memory structures of this module.
+#[doc(alias = "crc16_ccitt_false_update")]
+fn checksum(parts: &[&[u8]]) -> u16 {
+    parts.iter().fold(0xffff, |crc, part| {
+        // unsafe: OK per C API
+        unsafe { riot_sys::crc16_ccitt_false_update(crc, part.as_ptr(), part.len() as _) }
+    })
+}
+
+/// A location in persistent memory that holds a value of type `T` with a checksum
+///
+/// The slot occupies [`Slot::LEN`] bytes starting at its offset.
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize {
+/// use riot_wrappers::rtc_mem::Slot;
+///
+/// let sequence: Slot<u32> = Slot::new(0).expect("Board has too little backup RAM");
+/// let next = sequence.load().map(|n| n + 1).unwrap_or(0);
+/// sequence.store(&next);
+/// # 0
+/// # }
+/// ```
+pub struct Slot<T: AsBytes> {
+    offset: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: AsBytes> Slot<T> {
+    /// Describe a slot at the given offset
+    ///
+    /// This fails if the slot would not fit in the persistent memory of the board.
+    pub fn new(offset: usize) -> Result<Self, OutOfBounds> {
+        check_bounds(offset, Self::LEN)?;
+        Ok(Self {
+            offset,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Number of bytes occupied by the slot: the value, followed by its checksum
+    pub const LEN: usize = core::mem::size_of::<T>() + CHECKSUM_LEN;
+
+    /// Offset of the first byte after the slot, for placing the next slot
+    pub fn end(&self) -> usize {
+        self.offset + Self::LEN
+    }
+
+    fn checksum_offset(&self) -> usize {
+        self.offset + core::mem::size_of::<T>()
+    }
+
+    /// Read the stored value
+    ///
+    /// This returns None if the checksum does not match (eg. because nothing was stored there
+    /// yet since the device was powered up).
+    pub fn load(&self) -> Option<T> {
+        let mut stored_checksum = [0; CHECKSUM_LEN];
+        // Bounds were checked at construction
+        read(self.checksum_offset(), &mut stored_checksum).ok()?;
+        read_value(self.offset, |data| {
+            checksum(&[data]) == u16::from_le_bytes(stored_checksum)
+        })
+    }
+
+    /// Store a value
+    ///
+    /// If this is interrupted (eg. by a power loss), a later [`.load()`](Self::load) will most
+    /// likely find a checksum mismatch and return None.
+    pub fn store(&self, value: &T) {
+        let data = as_bytes(value);
+        // Bounds were checked at construction
+        let _ = write(self.offset, data);
+        let _ = write(self.checksum_offset(), &checksum(&[data]).to_le_bytes());
+    }
+
+    /// Invalidate the slot, so that subsequent loads return None
+    pub fn clear(&self) {
+        // Storing the inverted checksum of whatever is in there ensures a mismatch
+        let crc = read_value::<T>(self.offset, |_| true).map(|v| checksum(&[as_bytes(&v)]));
+        let _ = write(self.checksum_offset(), &(!crc.unwrap_or(0)).to_le_bytes());
+    }
+}
+
+/// Reason why a [`RecordStore`] could not produce a record
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LoadError {
+    /// The record would not fit in the persistent memory
+    OutOfBounds,
+    /// The magic number does not match, ie. nothing was saved by this application yet
+    NotFound,
+    /// A record was saved by a different version of the application
+    VersionMismatch(u8),
+    /// The stored length or CRC does not match, eg. because saving was interrupted
+    Corrupt,
+}
+
+impl From<OutOfBounds> for LoadError {
+    fn from(_: OutOfBounds) -> Self {
+        LoadError::OutOfBounds
+    }
+}
+
+/// A record in persistent memory that is identified by a magic number and a format version, and
+/// protected by a CRC
+///
+/// Unlike a [`Slot`], which only detects uninitialized or damaged memory, a record store also
+/// detects data left by a different application (through the magic number) or by an older
+/// version of the same application (through the version), so that state carried over deep sleep
+/// is only used if it is understood. This makes it suitable for state that changes its layout
+/// over firmware updates.
+///
+/// A record of type `T` occupies [`RecordStore::len_for`]`::<T>()` bytes starting at the store's
+/// offset.
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize {
+/// use riot_wrappers::rtc_mem::RecordStore;
+///
+/// let store = RecordStore::new(0, 0x5254, 1);
+/// let wakeups = store.load::<u32>().unwrap_or(0);
+/// store.save(&(wakeups + 1)).expect("Board has too little backup RAM");
+/// # 0
+/// # }
+/// ```
+pub struct RecordStore {
+    offset: usize,
+    magic: u16,
+    version: u8,
+}
+
+impl RecordStore {
+    /// Number of bytes preceding the record data (magic, version and length)
+    pub const HEADER_LEN: usize = 5;
+
+    /// Describe a record store at the given offset
+    ///
+    /// The magic number should be specific to the application; the version should be increased
+    /// whenever the stored record's serialization changes.
+    pub const fn new(offset: usize, magic: u16, version: u8) -> Self {
+        Self {
+            offset,
+            magic,
+            version,
+        }
+    }
+
+    /// Number of bytes occupied by a record of type `T`
+    pub const fn len_for<T: AsBytes>() -> usize {
+        Self::HEADER_LEN + core::mem::size_of::<T>() + CHECKSUM_LEN
+    }
+
+    fn header(&self, n: usize) -> [u8; Self::HEADER_LEN] {
+        let magic = self.magic.to_le_bytes();
+        let len = (n as u16).to_le_bytes();
+        [magic[0], magic[1], self.version, len[0], len[1]]
+    }
+
+    /// Store a record
+    ///
+    /// If this is interrupted (eg. by a power loss), a later [`.load()`](Self::load) will most
+    /// likely report [`LoadError::Corrupt`].
+    pub fn save<T: AsBytes>(&self, value: &T) -> Result<(), OutOfBounds> {
+        let n = core::mem::size_of::<T>();
+        check_bounds(self.offset, Self::len_for::<T>())?;
+        let header = self.header(n);
+        let data = as_bytes(value);
+        let crc = checksum(&[&header, data]);
+
+        let _ = write(self.offset, &header);
+        let _ = write(self.offset + Self::HEADER_LEN, data);
+        let _ = write(self.offset + Self::HEADER_LEN + n, &crc.to_le_bytes());
+        Ok(())
+    }
+
+    /// Read a stored record
+    pub fn load<T: AsBytes>(&self) -> Result<T, LoadError> {
+        let n = core::mem::size_of::<T>();
+        check_bounds(self.offset, Self::len_for::<T>())?;
+        let mut header = [0; Self::HEADER_LEN];
+        let mut crc = [0; CHECKSUM_LEN];
+        let _ = read(self.offset, &mut header);
+
+        let expected = self.header(n);
+        if header[..2] != expected[..2] {
+            return Err(LoadError::NotFound);
+        }
+        if header[2] != self.version {
+            return Err(LoadError::VersionMismatch(header[2]));
+        }
+        if header[3..] != expected[3..] {
+            return Err(LoadError::Corrupt);
+        }
+
+        let _ = read(self.offset + Self::HEADER_LEN + n, &mut crc);
+        read_value(self.offset + Self::HEADER_LEN, |data| {
+            checksum(&[&header, data]) == u16::from_le_bytes(crc)
+        })
+        .ok_or(LoadError::Corrupt)
+    }
+
+    /// Invalidate the stored record, so that subsequent loads report [`LoadError::NotFound`]
+    pub fn clear(&self) -> Result<(), OutOfBounds> {
+        write(self.offset, &(!self.magic).to_le_bytes())
+    }
+}
@@ -21,16 +21,21 @@ use riot_sys as raw;
 use riot_sys::libc;
 
 use crate::error;
-use crate::helpers::PointerToCStr;
+use crate::cstr_helpers::PointerToCStr;
 use crate::Never;
 use error::NegativeErrorExt;
 
 pub mod registration;
+#[cfg(feature = "with_minicbor")]
+pub mod senml;
 
 
 /// A discovered SAUL registry entry
 pub struct RegistryEntry(*mut riot_sys::saul_reg);
 
+// Registry entries are never removed, and their drivers can be called from any thread.
+unsafe impl Send for RegistryEntry {}
+
 /// Public result type of [`RegistryEntry::all()`].
 ///
 /// Do not rely on the precise type here -- this is meant only to be used as a means to explicitly
@@ -200,12 +205,26 @@ impl core::fmt::Display for Phydat {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Phydat {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Phydat {{ {=[?]} x 10^{} {:?} }}",
+            self.value(),
+            self.scale(),
+            self.unit()
+        )
+    }
+}
+
 /// Device class
 ///
 /// Both for the class in general and for its details, Option is used to represent undefined /
 /// unknown values, which are used as a wildcard in queries and as an indicator of unknown /
 /// unspported types in introspection.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Class {
     Actuator(Option<ActuatorClass>),
     Sensor(Option<SensorClass>),
@@ -312,6 +331,7 @@ impl Class {
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 /// Classes of actuators; typically used as details on a [Class]
 pub enum ActuatorClass {
@@ -323,6 +343,7 @@ pub enum ActuatorClass {
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 /// Classes of sensors; typically used as details on a [Class]
 pub enum SensorClass {
@@ -356,7 +377,8 @@ pub enum SensorClass {
     Size,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Unit of measurement required to interpret numeric values in a [Phydat] exchanged with a SAUL
 /// device
 pub enum Unit {
@@ -0,0 +1,195 @@
+//! Encoding of sensor values as [SenML](https://www.rfc-editor.org/rfc/rfc8428) in CBOR
+//!
+//! The [Encoder] writes a SenML pack into a buffer (typically a CoAP response's payload), one
+//! [Phydat] at a time. Values are encoded exactly: as integers if their scale is 0, and as CBOR
+//! decimal fractions otherwise. SAUL units are translated to SenML units where SenML has an
+//! equivalent unit; for others, no unit is sent.
+//!
+//! Multi-dimensional values (eg. of an accelerometer) are sent as one record per dimension, with
+//! `:x`, `:y` and `:z` appended to the name.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::saul::senml::Encoder;
+//! # let mut buf = [0u8; 256];
+//! // Typically, buf is the payload_mut() of a CoAP response, which is truncated to len after
+//! let mut encoder = Encoder::new(&mut buf);
+//! encoder.base(Some("urn:dev:mac:0024befffe804ff1:"), None).unwrap();
+//! riot_wrappers::saul::senml::encode_registry(&mut encoder).unwrap();
+//! let len = encoder.finish().unwrap();
+//! ```
+//!
+//! This needs the `with_minicbor` feature.
+
+use minicbor::data::Tag;
+use minicbor::encode::write::Cursor;
+
+use super::{Phydat, RegistryEntry, Unit};
+
+// SenML labels, RFC 8428 Section 6
+const BASE_NAME: i8 = -2;
+const BASE_TIME: i8 = -3;
+const NAME: i8 = 0;
+const UNIT: i8 = 1;
+const VALUE: i8 = 2;
+const BOOLEAN_VALUE: i8 = 4;
+const TIME: i8 = 6;
+
+/// Error produced when the encoded data exceeds the buffer
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BufferTooSmall;
+
+impl<E> From<minicbor::encode::Error<E>> for BufferTooSmall {
+    fn from(_: minicbor::encode::Error<E>) -> Self {
+        // Encoding into a slice can only fail for lack of space
+        BufferTooSmall
+    }
+}
+
+impl Unit {
+    /// The unit's name in the [SenML units
+    /// registry](https://www.iana.org/assignments/senml/senml.xhtml#senml-units), if it has an
+    /// equivalent there
+    pub fn senml_name(self) -> Option<&'static str> {
+        Some(match self {
+            Unit::TempC => "Cel",
+            Unit::TempK => "K",
+            Unit::Lux => "lx",
+            Unit::M => "m",
+            Unit::M2 => "m2",
+            Unit::M3 => "m3",
+            Unit::Gram => "g",
+            Unit::A => "A",
+            Unit::V => "V",
+            Unit::W => "W",
+            Unit::T => "T",
+            Unit::Dbm => "dBm",
+            Unit::Coulomb => "C",
+            Unit::F => "F",
+            Unit::Ohm => "Ohm",
+            Unit::Ph => "pH",
+            Unit::Bar => "bar",
+            Unit::Pa => "Pa",
+            Unit::Cd => "cd",
+            Unit::Percent => "%",
+            Unit::Ppm => "ppm",
+            _ => return None,
+        })
+    }
+}
+
+/// Writer of a SenML pack into a buffer
+pub struct Encoder<'b> {
+    encoder: minicbor::Encoder<Cursor<&'b mut [u8]>>,
+    started: bool,
+}
+
+impl<'b> Encoder<'b> {
+    /// Start encoding into the given buffer
+    pub fn new(buf: &'b mut [u8]) -> Self {
+        Encoder {
+            encoder: minicbor::Encoder::new(Cursor::new(buf)),
+            started: false,
+        }
+    }
+
+    fn start(&mut self) -> Result<(), BufferTooSmall> {
+        if !self.started {
+            self.encoder.begin_array()?;
+            self.started = true;
+        }
+        Ok(())
+    }
+
+    /// Write a record that only sets the base name and base time for all subsequent records
+    ///
+    /// The base time is in seconds since the Unix epoch. This is typically called before any
+    /// value is pushed, if at all.
+    pub fn base(&mut self, name: Option<&str>, time: Option<u64>) -> Result<(), BufferTooSmall> {
+        self.start()?;
+        let len = name.is_some() as u64 + time.is_some() as u64;
+        self.encoder.map(len)?;
+        if let Some(name) = name {
+            self.encoder.i8(BASE_NAME)?.str(name)?;
+        }
+        if let Some(time) = time {
+            self.encoder.i8(BASE_TIME)?.u64(time)?;
+        }
+        Ok(())
+    }
+
+    /// Write the value as one record per dimension
+    ///
+    /// The time, if given, is relative to the base time (or, if none is set, seconds since the
+    /// Unix epoch).
+    pub fn push(
+        &mut self,
+        name: &str,
+        value: &Phydat,
+        time: Option<i64>,
+    ) -> Result<(), BufferTooSmall> {
+        self.start()?;
+        let unit = value.unit();
+        let senml_unit = unit.and_then(Unit::senml_name);
+        let values = value.value();
+        for (i, v) in values.iter().enumerate() {
+            let len = 2 + senml_unit.is_some() as u64 + time.is_some() as u64;
+            self.encoder.map(len)?;
+
+            self.encoder.i8(NAME)?;
+            if values.len() == 1 {
+                self.encoder.str(name)?;
+            } else {
+                let suffix = [":x", ":y", ":z"].get(i).copied().unwrap_or(":?");
+                self.encoder.begin_str()?.str(name)?.str(suffix)?.end()?;
+            }
+
+            if let Some(u) = senml_unit {
+                self.encoder.i8(UNIT)?.str(u)?;
+            }
+
+            if matches!(unit, Some(Unit::Bool)) {
+                self.encoder.i8(BOOLEAN_VALUE)?.bool(*v != 0)?;
+            } else if value.scale() == 0 {
+                self.encoder.i8(VALUE)?.i16(*v)?;
+            } else {
+                self.encoder
+                    .i8(VALUE)?
+                    .tag(Tag::DecimalFraction)?
+                    .array(2)?
+                    .i8(value.scale())?
+                    .i16(*v)?;
+            }
+
+            if let Some(time) = time {
+                self.encoder.i8(TIME)?.i64(time)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Terminate the pack, and return the number of bytes written into the buffer
+    pub fn finish(mut self) -> Result<usize, BufferTooSmall> {
+        self.start()?;
+        self.encoder.end()?;
+        Ok(self.encoder.into_writer().position())
+    }
+}
+
+/// Read all SAUL sensors and push their values into the encoder
+///
+/// Entries are named by their SAUL name; entries without a name, and entries that can not be read
+/// (eg. actuators that do not support reading) are skipped.
+pub fn encode_registry(encoder: &mut Encoder<'_>) -> Result<(), BufferTooSmall> {
+    for entry in RegistryEntry::all() {
+        let name = match entry.name() {
+            Some(name) => name,
+            None => continue,
+        };
+        if let Ok(value) = entry.read() {
+            encoder.push(name, &value, None)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Block level access to SD and MMC cards through RIOT's [SDIO/SD/MMC
+//! driver](https://doc.riot-os.org/group__drivers__periph__sdmmc.html)
+//!
+//! This is suitable for applications that log data to a card without using a file system. Cards
+//! are accessed in blocks of [BLOCK_SIZE] bytes.
+//!
+//! To use a card through a file system, or through the byte addressed [crate::mtd] API, the
+//! `mtd_sdmmc` (or, for SPI connected cards, `mtd_sdcard`) module provides an MTD device for it.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::sdmmc::{Card, BLOCK_SIZE};
+//! let mut card = Card::get(0).unwrap();
+//! if card.is_present() {
+//!     card.init().unwrap();
+//!     let mut block = [0; BLOCK_SIZE];
+//!     card.read_blocks(0, &mut block).unwrap();
+//! }
+//! ```
+
+use crate::error::{Errno, NegativeErrorExt, NumericError};
+
+/// Size of the blocks in which cards are accessed
+///
+/// Only this block size is supported by all (SDHC/SDXC) cards.
+pub const BLOCK_SIZE: usize = riot_sys::SDMMC_SDHC_BLOCK_SIZE as _;
+
+/// An SD/MMC card slot
+pub struct Card {
+    dev: *mut riot_sys::sdmmc_dev_t,
+}
+
+impl Card {
+    /// Get the card slot with the given index, as configured by the board
+    #[doc(alias = "sdmmc_get_dev")]
+    pub fn get(index: usize) -> Option<Self> {
+        if index >= riot_sys::SDMMC_NUMOF as usize {
+            return None;
+        }
+        // unsafe: OK per C API; the index is checked.
+        let dev = unsafe { riot_sys::sdmmc_get_dev(index as _) };
+        if dev.is_null() {
+            return None;
+        }
+        Some(Card { dev })
+    }
+
+    /// Whether a card is inserted in the slot
+    ///
+    /// Slots without a card detect line always report a card as present.
+    #[doc(alias = "sdmmc_card_present")]
+    pub fn is_present(&self) -> bool {
+        // unsafe: OK per C API
+        unsafe { riot_sys::sdmmc_card_present(self.dev) }
+    }
+
+    /// Identify and initialize the card in the slot
+    ///
+    /// This is done implicitly by the read and write functions on first use; calling it
+    /// explicitly allows checking for a usable card before.
+    #[doc(alias = "sdmmc_card_init")]
+    pub fn init(&mut self) -> Result<(), NumericError> {
+        // unsafe: OK per C API
+        unsafe { riot_sys::sdmmc_card_init(self.dev) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    /// Capacity of the card in bytes
+    #[doc(alias = "sdmmc_get_capacity")]
+    pub fn capacity(&mut self) -> u64 {
+        // unsafe: OK per C API
+        unsafe { riot_sys::sdmmc_get_capacity(self.dev) }
+    }
+
+    /// Number of blocks on the card
+    pub fn block_count(&mut self) -> u32 {
+        (self.capacity() / BLOCK_SIZE as u64) as _
+    }
+
+    /// Read consecutive blocks starting at block number `first` into the buffer
+    ///
+    /// The buffer length needs to be a multiple of [BLOCK_SIZE], otherwise `EINVAL` is returned.
+    #[doc(alias = "sdmmc_read_blocks")]
+    pub fn read_blocks(&mut self, first: u32, buf: &mut [u8]) -> Result<(), NumericError> {
+        let count = Self::block_count_of(buf.len())?;
+        let mut done = 0;
+        // unsafe: OK per C API; the buffer is large enough for count blocks.
+        unsafe {
+            riot_sys::sdmmc_read_blocks(
+                self.dev,
+                first,
+                BLOCK_SIZE as _,
+                count,
+                buf.as_mut_ptr() as *mut _,
+                &mut done,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    /// Write the data to consecutive blocks starting at block number `first`
+    ///
+    /// The data length needs to be a multiple of [BLOCK_SIZE], otherwise `EINVAL` is returned.
+    #[doc(alias = "sdmmc_write_blocks")]
+    pub fn write_blocks(&mut self, first: u32, data: &[u8]) -> Result<(), NumericError> {
+        let count = Self::block_count_of(data.len())?;
+        let mut done = 0;
+        // unsafe: OK per C API; the data is not retained.
+        unsafe {
+            riot_sys::sdmmc_write_blocks(
+                self.dev,
+                first,
+                BLOCK_SIZE as _,
+                count,
+                data.as_ptr() as *const _,
+                &mut done,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    /// Erase `count` blocks starting at block number `first`
+    ///
+    /// Erasing is not necessary before writing, but may speed up later writes.
+    #[doc(alias = "sdmmc_erase_blocks")]
+    pub fn erase_blocks(&mut self, first: u32, count: u16) -> Result<(), NumericError> {
+        // unsafe: OK per C API
+        unsafe { riot_sys::sdmmc_erase_blocks(self.dev, first, count) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    fn block_count_of(len: usize) -> Result<u16, NumericError> {
+        if len % BLOCK_SIZE != 0 {
+            return Err(Errno::Inval.into());
+        }
+        (len / BLOCK_SIZE)
+            .try_into()
+            .map_err(|_| Errno::Inval.into())
+    }
+}
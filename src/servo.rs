@@ -0,0 +1,94 @@
+//! Control of hobby servo motors through RIOT's [servo](https://doc.riot-os.org/group__drivers__servo.html)
+//! driver
+//!
+//! The C driver positions a servo by an 8-bit value that spans the pulse lengths between the
+//! configured minimum and maximum; this wrapper additionally allows setting the position as a
+//! pulse length in microseconds, or as an angle within the servo's calibrated range:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::servo::Servo;
+//! # let params: &'static riot_sys::servo_params_t = unimplemented!();
+//! # let storage: &'static mut core::mem::MaybeUninit<riot_sys::servo_t> = unimplemented!();
+//! let mut servo = Servo::init(storage, params).unwrap();
+//! servo.set_range(270);
+//! servo.set_degrees(135);
+//! ```
+
+use core::mem::MaybeUninit;
+
+use crate::error::{NegativeErrorExt, NumericError};
+
+/// An initialized servo
+///
+/// The C driver's device struct is initialized in place in static storage, as the driver may keep
+/// references to it.
+pub struct Servo {
+    dev: &'static mut riot_sys::servo_t,
+    min_us: u16,
+    max_us: u16,
+    range_degrees: u16,
+}
+
+impl Servo {
+    /// Initialize a servo with the given parameters
+    ///
+    /// The parameters (typically taken from the board's `servo_params`, or built with the PWM
+    /// device and channel the servo is connected to) determine the minimum and maximum pulse
+    /// lengths. The servo's range of motion is initially assumed to be 180°.
+    ///
+    /// The device is initialized in `storage`, which is never moved afterwards.
+    #[doc(alias = "servo_init")]
+    pub fn init(
+        storage: &'static mut MaybeUninit<riot_sys::servo_t>,
+        params: &'static riot_sys::servo_params_t,
+    ) -> Result<Self, NumericError> {
+        // unsafe: OK per C API; the parameters and the device are static.
+        unsafe { riot_sys::servo_init(storage.as_mut_ptr(), params) }.negative_to_error()?;
+        Ok(Servo {
+            // unsafe: Initialized per C API
+            dev: unsafe { &mut *storage.as_mut_ptr() },
+            min_us: params.min_us,
+            max_us: params.max_us,
+            range_degrees: 180,
+        })
+    }
+
+    /// Set the angle the servo moves through between the minimum and maximum pulse length
+    pub fn set_range(&mut self, range_degrees: u16) {
+        self.range_degrees = range_degrees;
+    }
+
+    /// Set the position in the C driver's terms, where 0 is the minimum and 255 the maximum
+    /// pulse length
+    #[doc(alias = "servo_set")]
+    pub fn set_raw(&mut self, position: u8) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::servo_set(self.dev, position) }
+    }
+
+    /// Set the position as a pulse length in microseconds
+    ///
+    /// Values outside the configured minimum and maximum are clamped.
+    pub fn set_us(&mut self, pulse_us: u16) {
+        let span = self.max_us.saturating_sub(self.min_us) as u32;
+        if span == 0 {
+            self.set_raw(0);
+            return;
+        }
+        let offset = pulse_us.clamp(self.min_us, self.max_us) - self.min_us;
+        self.set_raw((offset as u32 * 255 / span) as u8);
+    }
+
+    /// Set the position as an angle from the minimum position
+    ///
+    /// Values beyond the servo's [range](Self::set_range) are clamped.
+    pub fn set_degrees(&mut self, degrees: u16) {
+        if self.range_degrees == 0 {
+            self.set_raw(0);
+            return;
+        }
+        let degrees = degrees.min(self.range_degrees) as u32;
+        self.set_raw((degrees * 255 / self.range_degrees as u32) as u8);
+    }
+}
@@ -25,12 +25,15 @@
 
 use crate::{mutex, stdio};
 use core::ffi::CStr;
+use core::sync::atomic::{AtomicI16, Ordering};
 use riot_sys::libc;
 use riot_sys::{shell_command_t, shell_run_forever, shell_run_once};
 
 mod args;
+mod subcommands;
 
 pub use args::Args;
+pub use subcommands::{dispatch, Subcommand};
 // re-exported only as long as users can't just make a TAIT out of the Args return type.
 pub use args::ArgsIterator;
 
@@ -59,14 +62,30 @@ pub unsafe trait CommandListInternals: Sized {
         linebuffer: &mut [u8],
         cb: F,
     ) -> R {
+        self.try_run_any(linebuffer, cb)
+            .expect("Simultaneously running shells are not supported")
+    }
+
+    // Like run_any, but returns None rather than panicking if another shell is running (also when
+    // this is called from one of its commands).
+    #[inline(always)]
+    fn try_run_any<R, F: Fn(*const riot_sys::shell_command_t, *mut libc::c_char, i32) -> R>(
+        &mut self,
+        linebuffer: &mut [u8],
+        cb: F,
+    ) -> Option<R> {
+        // A command running in this thread holds the lock, so waiting for it would never end
+        if COMMAND_THREAD.load(Ordering::Relaxed) == crate::thread::get_pid().into() {
+            return None;
+        }
+        // Other threads may hold the lock briefly, eg. while finishing a run
         let mut global = CURRENT_SHELL_RUNNER.lock();
         // Actually, if we really needed this, *and* could be sure that the shells are strictly
         // nested and not just started in parallel threads (how would we?), we could just stash
         // away the other callback, do our thing and revert it before leaving this function.
-        assert!(
-            global.is_none(),
-            "Simultaneously running shells are not supported"
-        );
+        if global.is_some() {
+            return None;
+        }
 
         let built = self.build_shell_command::<Self>();
 
@@ -88,7 +107,7 @@ pub unsafe trait CommandListInternals: Sized {
 
         CURRENT_SHELL_RUNNER.lock().take();
 
-        result
+        Some(result)
     }
 
     /// Run your own callback with argc and argv if the called argument is what the implementation
@@ -111,7 +130,9 @@ pub unsafe trait CommandListInternals: Sized {
         // unsafe: A suitable callback is always configured. We can make a &mut out of it for as
         // long as we hold the lock.
         let root = unsafe { &mut *(sleeve.0 as *mut Self) };
+        COMMAND_THREAD.store(crate::thread::get_pid().into(), Ordering::Relaxed);
         let result = root.find_self_and_run(argc, argv, command_index);
+        COMMAND_THREAD.store(riot_sys::KERNEL_PID_UNDEF as _, Ordering::Relaxed);
         drop(root);
         drop(lock);
         result
@@ -186,6 +207,55 @@ pub trait CommandList<const BUFSIZE: usize = { riot_sys::SHELL_DEFAULT_BUFSIZE a
         self.run_once_with_buf(&mut linebuffer)
     }
 
+    /// Execute a single line as if it had been entered at the shell prompt
+    ///
+    /// The line is dispatched to the list's commands as well as to the built-in and static
+    /// commands. It is copied into the line buffer (which the shell tokenizes in place), and thus
+    /// needs to be shorter than the buffer; otherwise, `ENOSPC` is returned. On success, this
+    /// returns whatever the shell returns for the line, which is the command's return value.
+    ///
+    /// As with the other run methods, only one shell can be running at a time; if another one is
+    /// (including when this is called from inside a shell command), `EBUSY` is returned.
+    #[doc(alias = "shell_handle_input_line")]
+    fn run_line_with_buf(
+        &mut self,
+        line: &str,
+        linebuffer: &mut [u8],
+    ) -> Result<i32, crate::error::NumericError> {
+        let terminated = linebuffer
+            .get_mut(..line.len() + 1)
+            .ok_or(crate::error::Errno::NoSpc)?;
+        terminated[..line.len()].copy_from_slice(line.as_bytes());
+        terminated[line.len()] = 0;
+        // unsafe: See unsafe in run_any where it's called; the line is null terminated.
+        self.try_run_any(linebuffer, |built, buf, _len| unsafe {
+            riot_sys::shell_handle_input_line(built, buf)
+        })
+        .ok_or_else(|| crate::error::Errno::Busy.into())
+    }
+
+    /// Execute a single line as if it had been entered at the shell prompt
+    ///
+    /// This can be used to run commands from other sources than stdio, eg. from a boot script:
+    ///
+    /// ```no_run
+    /// # #![no_std]
+    /// # use riot_wrappers::shell::{self, CommandList};
+    /// let script = "ifconfig\nps\n";
+    /// let mut commands = shell::new();
+    /// for line in script.lines() {
+    ///     commands.run_line(line).unwrap();
+    /// }
+    /// ```
+    ///
+    /// The line buffer is allocated inside this function with the size configured as part of the
+    /// trait type; see [.run_line_with_buf()](CommandList::run_line_with_buf) for details.
+    #[doc(alias = "shell_handle_input_line")]
+    fn run_line(&mut self, line: &str) -> Result<i32, crate::error::NumericError> {
+        let mut linebuffer = [0; BUFSIZE];
+        self.run_line_with_buf(line, &mut linebuffer)
+    }
+
     /// Extend the list of commands by an additional one.
     ///
     /// The handler will be called every time the command is entered, and is passed the arguments
@@ -228,6 +298,10 @@ unsafe impl Send for SleevedCommandList {}
 
 static CURRENT_SHELL_RUNNER: mutex::Mutex<Option<SleevedCommandList>> = mutex::Mutex::new(None);
 
+/// Thread that is running a command from CURRENT_SHELL_RUNNER (and thus holds its lock), or
+/// KERNEL_PID_UNDEF
+static COMMAND_THREAD: AtomicI16 = AtomicI16::new(riot_sys::KERNEL_PID_UNDEF as _);
+
 /// Internal helper that is used to create the linear [`riot_sys::shell_command_t`] structure that a
 /// command list needs to pass to RIOT
 ///
@@ -1,6 +1,6 @@
 use riot_sys::libc;
 
-use crate::helpers::PointerToCStr;
+use crate::cstr_helpers::PointerToCStr;
 
 /// Newtype around an (argc, argv) C style string array that presents itself as much as an `&'a
 /// [&'a str]` as possible. (Slicing is not implemented for reasons of laziness).
@@ -52,6 +52,14 @@ impl<'a> Args<'a> {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    /// The arguments without the first one
+    ///
+    /// This is what a subcommand gets passed as its arguments (with its own name first); see
+    /// [dispatch](super::dispatch).
+    pub fn tail(&self) -> Args<'a> {
+        Args(self.0.get(1..).unwrap_or(&[]))
+    }
 }
 
 /// Iterator of [Args], created using [Args::iter()]
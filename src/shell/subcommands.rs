@@ -0,0 +1,127 @@
+//! Dispatch of a shell command to subcommands
+
+use core::fmt::Write;
+
+use super::Args;
+use crate::stdio::table::{Column, Table};
+use crate::stdio::Stdio;
+
+/// A subcommand that [dispatch] can select
+///
+/// Like commands, subcommands get passed their arguments with their own name first. A
+/// subcommand's handler can call [dispatch] again to build deeper hierarchies.
+#[derive(Copy, Clone)]
+pub struct Subcommand<'a> {
+    name: &'a str,
+    desc: &'a str,
+    handler: fn(&mut Stdio, Args<'_>) -> i32,
+}
+
+impl<'a> Subcommand<'a> {
+    pub const fn new(
+        name: &'a str,
+        desc: &'a str,
+        handler: fn(&mut Stdio, Args<'_>) -> i32,
+    ) -> Self {
+        Subcommand {
+            name,
+            desc,
+            handler,
+        }
+    }
+}
+
+impl<'a> core::fmt::Debug for Subcommand<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Subcommand")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+fn usage(stdio: &mut Stdio, command: &str, subcommands: &[Subcommand<'_>]) {
+    let _ = writeln!(stdio, "Usage: {} <subcommand> [args...]", command);
+    let width = subcommands
+        .iter()
+        .map(|s| s.name.len())
+        .chain(core::iter::once("help".len()))
+        .max()
+        .unwrap_or(0);
+    let columns = [
+        Column::left("", width.min(u8::MAX as usize - 1) as u8 + 1),
+        Column::left("", 0),
+    ];
+    let mut table = Table::new(stdio, &columns);
+    for subcommand in subcommands {
+        let _ = table.row(&[&subcommand.name, &subcommand.desc]);
+    }
+    let _ = table.row(&[
+        &"help",
+        &"Show this list, or the description of a subcommand",
+    ]);
+}
+
+/// Run the subcommand selected by the first argument after the command name
+///
+/// This is used as the body of a command that groups several others:
+///
+/// ```no_run
+/// # #![no_std]
+/// use riot_wrappers::shell::{dispatch, Args, Subcommand};
+/// use riot_wrappers::stdio::Stdio;
+///
+/// fn ifconfig(_stdio: &mut Stdio, _args: Args<'_>) -> i32 {
+///     // ...
+///     0
+/// }
+/// # fn ping(_stdio: &mut Stdio, _args: Args<'_>) -> i32 { 0 }
+///
+/// fn net(stdio: &mut Stdio, args: Args<'_>) -> i32 {
+///     dispatch(
+///         stdio,
+///         args,
+///         &[
+///             Subcommand::new("ifconfig", "Show network interfaces", ifconfig),
+///             Subcommand::new("ping", "Send echo requests", ping),
+///         ],
+///     )
+/// }
+///
+/// riot_wrappers::static_command!(net_command, "net", "Network tools", net);
+/// ```
+///
+/// Entering `net ping ::1` then calls `ping` with the arguments `ping ::1`. `net help` (or `net`
+/// on its own) lists the subcommands, and `net help ping` shows the description of `ping`.
+/// Unknown subcommands are reported along with that list, and result in 1 being returned;
+/// otherwise, the subcommand's return value is returned.
+pub fn dispatch(stdio: &mut Stdio, args: Args<'_>, subcommands: &[Subcommand<'_>]) -> i32 {
+    let command = args.get(0).unwrap_or("");
+    let selected = match args.get(1) {
+        None => {
+            usage(stdio, command, subcommands);
+            return 1;
+        }
+        Some("help") => {
+            if let Some(name) = args.get(2) {
+                if let Some(subcommand) = subcommands.iter().find(|s| s.name == name) {
+                    let _ = writeln!(stdio, "{} {}: {}", command, name, subcommand.desc);
+                    return 0;
+                }
+                let _ = writeln!(stdio, "{}: unknown subcommand '{}'", command, name);
+                usage(stdio, command, subcommands);
+                return 1;
+            }
+            usage(stdio, command, subcommands);
+            return 0;
+        }
+        Some(name) => name,
+    };
+    match subcommands.iter().find(|s| s.name == selected) {
+        Some(subcommand) => (subcommand.handler)(stdio, args.tail()),
+        None => {
+            let _ = writeln!(stdio, "{}: unknown subcommand '{}'", command, selected);
+            usage(stdio, command, subcommands);
+            1
+        }
+    }
+}
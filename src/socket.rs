@@ -19,6 +19,88 @@ impl UdpEp {
         self.0.port = port;
         self
     }
+
+    /// Restrict the endpoint to the network interface with the given identifier
+    ///
+    /// Passing `SOCK_ADDR_ANY_NETIF` (0) removes any restriction.
+    pub fn with_netif(mut self, netif: u16) -> Self {
+        self.0.netif = netif;
+        self
+    }
+
+    /// Restrict the endpoint to the given network interface
+    ///
+    /// This is necessary for link-local addresses on nodes with more than one interface.
+    #[cfg(riot_module_gnrc)]
+    pub fn on_interface(self, netif: &crate::gnrc::Netif) -> Self {
+        let pid: riot_sys::kernel_pid_t = netif.pid().into();
+        self.with_netif(pid as _)
+    }
+
+    /// The network interface the endpoint is restricted to, if any
+    pub fn netif(&self) -> Option<u16> {
+        match self.0.netif as u32 {
+            riot_sys::SOCK_ADDR_ANY_NETIF => None,
+            n => Some(n as _),
+        }
+    }
+
+    /// Set the endpoint's address to the given IPv6 address
+    #[cfg(all(riot_module_gnrc, riot_module_ipv6))]
+    pub fn with_ipv6_addr(mut self, addr: &crate::gnrc::ipv6::Address) -> Self {
+        self.0.family = riot_sys::AF_INET6 as _;
+        self.0.addr.ipv6 = *addr.raw();
+        self
+    }
+
+    /// Set the endpoint's address to the given IPv4 address
+    pub fn with_ipv4_addr(mut self, addr: [u8; 4]) -> Self {
+        self.0.family = riot_sys::AF_INET as _;
+        self.0.addr.ipv4 = addr;
+        self
+    }
+
+    /// Build an endpoint on the given interface's first link-local address
+    ///
+    /// This is convenient for binding a socket that should only serve the link of a particular
+    /// interface. It returns None if the interface has no link-local address (yet).
+    #[cfg(all(riot_module_gnrc, riot_module_ipv6))]
+    pub fn ipv6_link_local(netif: &crate::gnrc::Netif) -> Option<Self> {
+        let addrs = netif.ipv6_addrs().ok()?;
+        let addr = addrs.iter().find(|a| a.is_link_local())?;
+        Some(Self::ipv6_any().with_ipv6_addr(addr).on_interface(netif))
+    }
+
+    /// Enumerate the local addresses that are suitable as source addresses for communicating with
+    /// the given remote address
+    ///
+    /// For a link-local remote address, these are the link-local addresses of all interfaces;
+    /// otherwise, they are all unicast addresses of larger scope. Each is produced as an endpoint
+    /// restricted to its interface (with port 0, which can be changed using
+    /// [with_port](Self::with_port)), ready to be used as the local endpoint of a socket.
+    ///
+    /// On nodes with several global prefixes, this allows the application to pick which one it
+    /// uses, rather than leaving that to the stack's source address selection.
+    #[cfg(all(riot_module_gnrc, riot_module_ipv6))]
+    pub fn local_candidates_for(remote: &crate::gnrc::ipv6::Address) -> impl Iterator<Item = Self> {
+        let remote_link_local = remote.is_link_local();
+        crate::gnrc::Netif::all().flat_map(move |netif| {
+            let addrs = netif.ipv6_addrs().ok();
+            let count = addrs.as_ref().map(|a| a.len()).unwrap_or(0);
+            (0..count).filter_map(move |i| {
+                let addr = addrs.as_ref()?[i];
+                let suitable = if remote_link_local {
+                    addr.is_link_local()
+                } else {
+                    !addr.is_link_local() && !addr.is_multicast() && !addr.is_unspecified()
+                };
+                if !suitable {
+                    return None;
+                }
+                Some(Self::ipv6_any().with_ipv6_addr(&addr).on_interface(&netif))
+            })
+        })
+    }
 }
 
 impl From<riot_sys::sock_udp_ep_t> for UdpEp {
@@ -64,6 +146,80 @@ impl Into<riot_sys::sock_udp_ep_t> for UdpEp {
     }
 }
 
+/// Auxiliary data about a received datagram
+///
+/// Each piece of information is only available if the respective `sock_aux_*` module is in use
+/// (`sock_aux_local`, `sock_aux_timestamp`, `sock_aux_rssi`, `sock_aux_ttl`), and if the network
+/// stack provides it for the datagram; otherwise, it is None.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct RxAux {
+    /// The local endpoint the datagram was sent to
+    ///
+    /// This is particularly useful on sockets bound to an unspecified address, where the reply
+    /// should be sent from the address the request was sent to.
+    pub local: Option<UdpEp>,
+    /// The time the datagram was received, in nanoseconds
+    ///
+    /// The time base depends on the network device; typically, this is a hardware timestamp.
+    pub timestamp_ns: Option<u64>,
+    /// Signal strength of the received frame, in dBm
+    pub rssi: Option<i16>,
+    /// Hop limit (time to live) of the received packet
+    pub ttl: Option<u8>,
+}
+
+impl RxAux {
+    /// Build the C struct that requests all information this build can provide
+    pub(crate) fn request() -> riot_sys::sock_udp_aux_rx_t {
+        let mut aux: riot_sys::sock_udp_aux_rx_t = Default::default();
+        #[cfg(riot_module_sock_aux_local)]
+        {
+            aux.flags |= riot_sys::SOCK_AUX_GET_LOCAL as riot_sys::sock_aux_flags_t;
+        }
+        #[cfg(riot_module_sock_aux_timestamp)]
+        {
+            aux.flags |= riot_sys::SOCK_AUX_GET_TIMESTAMP as riot_sys::sock_aux_flags_t;
+        }
+        #[cfg(riot_module_sock_aux_rssi)]
+        {
+            aux.flags |= riot_sys::SOCK_AUX_GET_RSSI as riot_sys::sock_aux_flags_t;
+        }
+        #[cfg(riot_module_sock_aux_ttl)]
+        {
+            aux.flags |= riot_sys::SOCK_AUX_GET_TTL as riot_sys::sock_aux_flags_t;
+        }
+        aux
+    }
+
+    /// Extract the information from a C struct that was filled by a receive function
+    ///
+    /// The stack clears the flags of all requested information it provided.
+    pub(crate) fn from_filled(aux: &riot_sys::sock_udp_aux_rx_t) -> Self {
+        #[allow(unused_mut)]
+        let mut result = Self::default();
+        #[allow(unused_variables)]
+        let provided = |flag: u32| aux.flags & flag as riot_sys::sock_aux_flags_t == 0;
+        #[cfg(riot_module_sock_aux_local)]
+        if provided(riot_sys::SOCK_AUX_GET_LOCAL as _) {
+            result.local = Some(UdpEp(aux.local));
+        }
+        #[cfg(riot_module_sock_aux_timestamp)]
+        if provided(riot_sys::SOCK_AUX_GET_TIMESTAMP as _) {
+            result.timestamp_ns = Some(aux.timestamp);
+        }
+        #[cfg(riot_module_sock_aux_rssi)]
+        if provided(riot_sys::SOCK_AUX_GET_RSSI as _) {
+            result.rssi = Some(aux.rssi);
+        }
+        #[cfg(riot_module_sock_aux_ttl)]
+        if provided(riot_sys::SOCK_AUX_GET_TTL as _) {
+            result.ttl = Some(aux.ttl);
+        }
+        result
+    }
+}
+
 #[cfg(feature = "with_embedded_nal")]
 mod nal_impls {
     use super::*;
@@ -134,3 +290,8 @@ mod nal_impls {
         }
     }
 }
+
+#[cfg(riot_module_sock_tcp)]
+pub mod tcp;
+#[cfg(riot_module_sock_udp)]
+pub mod udp_bench;
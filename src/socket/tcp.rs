@@ -0,0 +1,221 @@
+//! TCP servers on the [sock_tcp API](https://doc.riot-os.org/group__net__sock__tcp.html)
+//!
+//! A listening socket needs memory for its queue of connections, which RIOT keeps referencing for
+//! as long as the socket listens. That memory is provided as a [ListenerStorage], which is pinned
+//! and borrowed by the [Listener] created from it. Connections obtained by
+//! [accepting](Listener::accept) borrow the listener in turn, so neither can go away while
+//! connections are in use.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::socket::{UdpEp, tcp::{ListenerStorage, Listener}};
+//! let storage = ListenerStorage::<2>::new();
+//! pin_utils::pin_mut!(storage);
+//! let listener = Listener::listen(storage, &UdpEp::ipv6_any().with_port(1234)).unwrap();
+//! loop {
+//!     let mut connection = listener.accept(None).unwrap();
+//!     let mut buf = [0; 64];
+//!     let len = connection.read(&mut buf, Some(1_000_000)).unwrap();
+//!     connection.write(&buf[..len]).unwrap();
+//!     connection.disconnect();
+//! }
+//! ```
+
+use core::marker::{PhantomData, PhantomPinned};
+use core::pin::Pin;
+
+use super::UdpEp;
+use crate::error::{NegativeErrorExt, NumericError};
+
+/// Convert an optional timeout in microseconds into the sock API's representation
+fn timeout_to_c(timeout_us: Option<u32>) -> u32 {
+    timeout_us.unwrap_or(riot_sys::SOCK_NO_TIMEOUT as _)
+}
+
+/// Memory for a [Listener] that can hold up to `N` connections at the same time
+pub struct ListenerStorage<const N: usize> {
+    queue: riot_sys::sock_tcp_queue_t,
+    sockets: [riot_sys::sock_tcp_t; N],
+    // Set while RIOT holds pointers into the other fields. Listening is stopped when the storage
+    // is dropped at the latest, which is guaranteed to happen before its memory is reused as it is
+    // pinned -- unlike the listener's drop, which can be skipped by forgetting it.
+    listening: bool,
+    _pinned: PhantomPinned,
+}
+
+impl<const N: usize> ListenerStorage<N> {
+    pub fn new() -> Self {
+        ListenerStorage {
+            queue: Default::default(),
+            sockets: [Default::default(); N],
+            listening: false,
+            _pinned: PhantomPinned,
+        }
+    }
+}
+
+impl<const N: usize> Drop for ListenerStorage<N> {
+    fn drop(&mut self) {
+        if self.listening {
+            // unsafe: OK per C API; any listener and its connections borrowed the storage, so
+            // they are gone (or were forgotten, which is why this is needed).
+            unsafe { riot_sys::sock_tcp_stop_listen(&mut self.queue) }
+        }
+    }
+}
+
+impl<const N: usize> Default for ListenerStorage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A TCP socket listening for incoming connections
+///
+/// Dropping the listener stops listening.
+pub struct Listener<'a> {
+    queue: *mut riot_sys::sock_tcp_queue_t,
+    listening: *mut bool,
+    _storage: PhantomData<&'a mut ()>,
+}
+
+impl<'a> Listener<'a> {
+    /// Start listening on the given local endpoint
+    #[doc(alias = "sock_tcp_listen")]
+    pub fn listen<const N: usize>(
+        storage: Pin<&'a mut ListenerStorage<N>>,
+        local: &UdpEp,
+    ) -> Result<Self, NumericError> {
+        // unsafe: The storage stays pinned, and we don't move out of it.
+        let storage = unsafe { storage.get_unchecked_mut() };
+        if storage.listening {
+            // A previous listener on this storage was forgotten
+            // unsafe: OK per C API; that listener's borrow has ended.
+            unsafe { riot_sys::sock_tcp_stop_listen(&mut storage.queue) };
+            storage.listening = false;
+        }
+        // TCP and UDP endpoints are the same type in C (struct _sock_tl_ep)
+        let local: &riot_sys::sock_udp_ep_t = local.as_ref();
+        // unsafe: OK per C API; storage is borrowed for as long as the listener exists
+        unsafe {
+            riot_sys::sock_tcp_listen(
+                &mut storage.queue,
+                local as *const _ as *const _,
+                storage.sockets.as_mut_ptr(),
+                N as _,
+                0,
+            )
+        }
+        .negative_to_error()?;
+        storage.listening = true;
+        Ok(Listener {
+            queue: &mut storage.queue,
+            listening: &mut storage.listening,
+            _storage: PhantomData,
+        })
+    }
+
+    /// Wait for an incoming connection
+    ///
+    /// The timeout is given in microseconds; None waits indefinitely. The error is `ETIMEDOUT` if
+    /// no connection came in in time, `ENOMEM` if all connection slots are in use, and
+    /// `EAGAIN` if the timeout was 0 and no connection is pending.
+    #[doc(alias = "sock_tcp_accept")]
+    pub fn accept(&self, timeout_us: Option<u32>) -> Result<Connection<'_>, NumericError> {
+        let mut sock = core::ptr::null_mut();
+        // unsafe: OK per C API
+        unsafe { riot_sys::sock_tcp_accept(self.queue, &mut sock, timeout_to_c(timeout_us)) }
+            .negative_to_error()?;
+        Ok(Connection {
+            sock,
+            _listener: PhantomData,
+        })
+    }
+
+    /// The local endpoint the listener is bound to
+    #[doc(alias = "sock_tcp_queue_get_local")]
+    pub fn local(&self) -> Result<UdpEp, NumericError> {
+        let mut ep: riot_sys::sock_udp_ep_t = Default::default();
+        // unsafe: OK per C API
+        unsafe { riot_sys::sock_tcp_queue_get_local(self.queue, &mut ep as *mut _ as *mut _) }
+            .negative_to_error()?;
+        Ok(ep.into())
+    }
+}
+
+impl<'a> Drop for Listener<'a> {
+    #[doc(alias = "sock_tcp_stop_listen")]
+    fn drop(&mut self) {
+        // unsafe: OK per C API; no connections are left as they borrow the listener. The flag
+        // is in the storage, which outlives the listener.
+        unsafe {
+            riot_sys::sock_tcp_stop_listen(self.queue);
+            *self.listening = false;
+        }
+    }
+}
+
+/// An accepted TCP connection
+///
+/// Dropping the connection disconnects it.
+pub struct Connection<'l> {
+    sock: *mut riot_sys::sock_tcp_t,
+    _listener: PhantomData<&'l Listener<'l>>,
+}
+
+impl<'l> Connection<'l> {
+    /// Read data that was received on the connection into `buf`, returning the number of bytes
+    ///
+    /// The timeout is given in microseconds; None waits indefinitely. A return value of 0
+    /// indicates that the peer closed the connection.
+    #[doc(alias = "sock_tcp_read")]
+    pub fn read(&mut self, buf: &mut [u8], timeout_us: Option<u32>) -> Result<usize, NumericError> {
+        // unsafe: OK per C API
+        let len = unsafe {
+            riot_sys::sock_tcp_read(
+                self.sock,
+                buf.as_mut_ptr() as *mut _,
+                buf.len() as _,
+                timeout_to_c(timeout_us),
+            )
+        }
+        .negative_to_error()?;
+        Ok(len as _)
+    }
+
+    /// Send data on the connection, returning the number of bytes that were sent
+    #[doc(alias = "sock_tcp_write")]
+    pub fn write(&mut self, data: &[u8]) -> Result<usize, NumericError> {
+        // unsafe: OK per C API
+        let len = unsafe {
+            riot_sys::sock_tcp_write(self.sock, data.as_ptr() as *const _, data.len() as _)
+        }
+        .negative_to_error()?;
+        Ok(len as _)
+    }
+
+    /// The peer's endpoint
+    #[doc(alias = "sock_tcp_get_remote")]
+    pub fn remote(&self) -> Result<UdpEp, NumericError> {
+        let mut ep: riot_sys::sock_udp_ep_t = Default::default();
+        // unsafe: OK per C API
+        unsafe { riot_sys::sock_tcp_get_remote(self.sock, &mut ep as *mut _ as *mut _) }
+            .negative_to_error()?;
+        Ok(ep.into())
+    }
+
+    /// Close the connection, freeing its slot in the listener's storage
+    ///
+    /// This is equivalent to dropping the connection, but more explicit.
+    #[doc(alias = "sock_tcp_disconnect")]
+    pub fn disconnect(self) {
+        // Drop does the work
+    }
+}
+
+impl<'l> Drop for Connection<'l> {
+    fn drop(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::sock_tcp_disconnect(self.sock) }
+    }
+}
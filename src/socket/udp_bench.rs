@@ -0,0 +1,295 @@
+//! UDP echo server and measurement client for validating links
+//!
+//! This is the Rust side of what `iperf`-like C examples do: One node runs the [echo_server],
+//! another runs [run_client] against it, which sends numbered datagrams of configurable size at
+//! a configurable rate, waits for each to be echoed, and reports loss, round trip times and
+//! throughput:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::println;
+//! use riot_wrappers::socket::{udp_bench::{run_client, ClientConfig}, UdpEp};
+//! # let server = UdpEp::ipv6_any();
+//!
+//! let config = ClientConfig {
+//!     payload_size: 100,
+//!     count: 50,
+//!     interval_us: 20_000,
+//!     ..Default::default()
+//! };
+//! let mut buf = [0; 100];
+//! let report = run_client(&UdpEp::ipv6_any(), &server.with_port(7), &config, &mut buf).unwrap();
+//! println!("{}", report);
+//! ```
+//!
+//! Any UDP echo service (eg. the one on port 7 of many hosts) can serve as the remote end.
+//!
+//! The client is only available if the ztimer_usec module is built.
+
+use core::mem::MaybeUninit;
+
+use super::UdpEp;
+use crate::error::{NegativeErrorExt, NumericError};
+
+/// A sock_udp socket that is closed when dropped
+struct Socket<'a>(&'a mut riot_sys::sock_udp_t);
+
+impl<'a> Socket<'a> {
+    fn create(
+        storage: &'a mut riot_sys::sock_udp_t,
+        local: &UdpEp,
+        remote: Option<&UdpEp>,
+    ) -> Result<Self, NumericError> {
+        let remote: *const riot_sys::sock_udp_ep_t = match remote {
+            Some(r) => r.as_ref(),
+            None => core::ptr::null(),
+        };
+        // unsafe: OK per C API; the storage outlives the Socket, which closes it on drop.
+        unsafe { riot_sys::sock_udp_create(storage, local.as_ref(), remote, 0) }
+            .negative_to_error()?;
+        Ok(Socket(storage))
+    }
+
+    /// Send to the given remote, or to the socket's remote if None
+    fn send(&mut self, data: &[u8], remote: Option<&UdpEp>) -> Result<(), NumericError> {
+        let remote: *const riot_sys::inline::sock_udp_ep_t = match remote {
+            Some(r) => r.as_ref(),
+            None => core::ptr::null(),
+        };
+        // unsafe: OK per C API
+        unsafe {
+            riot_sys::sock_udp_send(
+                crate::inline_cast_mut(&mut *self.0 as *mut _),
+                data.as_ptr() as _,
+                data.len() as _,
+                remote,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    fn recv(&mut self, buf: &mut [u8], timeout_us: u32) -> Result<(usize, UdpEp), NumericError> {
+        let mut remote = MaybeUninit::uninit();
+        // unsafe: OK per C API
+        let read = unsafe {
+            riot_sys::sock_udp_recv(
+                crate::inline_cast_mut(&mut *self.0 as *mut _),
+                buf.as_mut_ptr() as _,
+                buf.len() as _,
+                timeout_us,
+                crate::inline_cast_mut(remote.as_mut_ptr() as *mut _),
+            )
+        }
+        .negative_to_error()?;
+        // unsafe: Set by C function on success
+        let remote = UdpEp(unsafe { remote.assume_init() });
+        Ok((read as usize, remote))
+    }
+}
+
+impl<'a> Drop for Socket<'a> {
+    fn drop(&mut self) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::sock_udp_close(self.0) };
+    }
+}
+
+/// Send back every datagram received on `local` to its sender
+///
+/// Datagrams larger than `buf` are dropped. This only returns if the socket can not be created.
+#[doc(alias = "sock_udp_create")]
+pub fn echo_server(local: &UdpEp, buf: &mut [u8]) -> Result<crate::Never, NumericError> {
+    let mut storage = Default::default();
+    let mut socket = Socket::create(&mut storage, local, None)?;
+    loop {
+        match socket.recv(buf, riot_sys::SOCK_NO_TIMEOUT as _) {
+            // Sending errors (eg. from a full send queue) are just like lost packets to the client
+            Ok((len, remote)) => socket.send(&buf[..len], Some(&remote)).ok(),
+            // Oversized datagrams and the likes; the client will count them as lost
+            Err(_) => None,
+        };
+    }
+}
+
+/// Parameters of a [run_client] measurement
+#[derive(Debug, Copy, Clone)]
+pub struct ClientConfig {
+    /// Size of each datagram's payload, including the 4 byte sequence number
+    pub payload_size: usize,
+    /// Number of datagrams to send
+    pub count: u32,
+    /// Time between the start of two consecutive sends
+    ///
+    /// If a reply takes longer than this, the next datagram is sent right after it arrived (or
+    /// timed out); the rate is then limited by the round trip time.
+    pub interval_us: u32,
+    /// Time after which a datagram that was not echoed is considered lost
+    pub timeout_us: u32,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            payload_size: 64,
+            count: 10,
+            interval_us: 100_000,
+            timeout_us: 1_000_000,
+        }
+    }
+}
+
+/// Statistics produced by [run_client]
+///
+/// The [Display] implementation produces a one-line summary.
+///
+/// [Display]: core::fmt::Display
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct Report {
+    /// Number of datagrams sent, including those the network stack refused
+    pub sent: u32,
+    /// Number of datagrams that were echoed in time
+    pub received: u32,
+    /// Number of replies that arrived after their timeout, or that did not match what was sent
+    pub stray: u32,
+    /// Payload bytes that were echoed in time
+    pub bytes: u64,
+    /// Shortest round trip time (0 if nothing was received)
+    pub rtt_min_us: u32,
+    /// Longest round trip time
+    pub rtt_max_us: u32,
+    rtt_sum_us: u64,
+    /// Time from the first send to the end of the last reply or timeout
+    pub duration_us: u32,
+}
+
+impl Report {
+    /// Number of datagrams that were sent but not echoed in time
+    pub fn lost(&self) -> u32 {
+        self.sent - self.received
+    }
+
+    /// Average round trip time of the echoed datagrams
+    pub fn rtt_avg_us(&self) -> Option<u32> {
+        match self.received {
+            0 => None,
+            n => Some((self.rtt_sum_us / n as u64) as u32),
+        }
+    }
+
+    /// Echoed payload data rate in bits per second, counted in one direction
+    pub fn throughput_bps(&self) -> u64 {
+        match self.duration_us {
+            0 => 0,
+            d => self.bytes * 8 * 1_000_000 / d as u64,
+        }
+    }
+
+    fn record(&mut self, len: usize, rtt_us: u32) {
+        if self.received == 0 || rtt_us < self.rtt_min_us {
+            self.rtt_min_us = rtt_us;
+        }
+        self.rtt_max_us = self.rtt_max_us.max(rtt_us);
+        self.rtt_sum_us += rtt_us as u64;
+        self.received += 1;
+        self.bytes += len as u64;
+    }
+}
+
+impl core::fmt::Display for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} sent, {} received, {} lost, {} stray; {} bytes in {} us ({} bit/s)",
+            self.sent,
+            self.received,
+            self.lost(),
+            self.stray,
+            self.bytes,
+            self.duration_us,
+            self.throughput_bps()
+        )?;
+        if let Some(avg) = self.rtt_avg_us() {
+            write!(
+                f,
+                "; rtt min/avg/max {}/{}/{} us",
+                self.rtt_min_us, avg, self.rtt_max_us
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Measure loss, round trip times and throughput against an echo server at `remote`
+///
+/// Datagrams are sent from `local` (typically [UdpEp::ipv6_any]), one at a time: Each is
+/// prefixed with its big-endian sequence number, and the next one is only sent after its echo
+/// arrived or `timeout_us` expired. `buf` is used for both sending and receiving, and needs to
+/// hold at least `payload_size` bytes.
+///
+/// Errors are only returned if the configuration is unusable (`-EINVAL`), the socket can not be
+/// created, or receiving fails for reasons other than timeouts; failed sends are counted as
+/// lost.
+#[cfg(riot_module_ztimer_usec)]
+pub fn run_client(
+    local: &UdpEp,
+    remote: &UdpEp,
+    config: &ClientConfig,
+    buf: &mut [u8],
+) -> Result<Report, NumericError> {
+    let size = config.payload_size;
+    if size < 4 || size > buf.len() {
+        return Err(NumericError::from_constant(riot_sys::EINVAL as _));
+    }
+
+    let mut storage = Default::default();
+    let mut socket = Socket::create(&mut storage, local, Some(remote))?;
+
+    let clock = crate::ztimer::Clock::usec();
+    let start = clock.now().0;
+    let elapsed = || clock.now().0.wrapping_sub(start);
+
+    let mut report = Report::default();
+    for seq in 0..config.count {
+        let due = seq as u64 * config.interval_us as u64;
+        let now = elapsed() as u64;
+        if due > now {
+            clock.sleep_ticks((due - now).min(u32::MAX as u64) as u32);
+        }
+
+        let tag = seq.to_be_bytes();
+        for (i, byte) in buf[..size].iter_mut().enumerate() {
+            *byte = match i {
+                0..=3 => tag[i],
+                _ => i as u8,
+            };
+        }
+
+        let sent_at = elapsed();
+        report.sent += 1;
+        if socket.send(&buf[..size], None).is_err() {
+            continue;
+        }
+
+        loop {
+            let waited = elapsed().wrapping_sub(sent_at);
+            if waited >= config.timeout_us {
+                break;
+            }
+            match socket.recv(buf, config.timeout_us - waited) {
+                Ok((len, _)) if len == size && buf[..4] == tag => {
+                    report.record(len, elapsed().wrapping_sub(sent_at));
+                    break;
+                }
+                Ok(_) => report.stray += 1,
+                Err(e) if e.errno() == Some(crate::error::Errno::TimedOut) => break,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    report.duration_us = elapsed();
+
+    Ok(report)
+}
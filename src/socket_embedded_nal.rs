@@ -5,7 +5,7 @@ use core::convert::TryInto;
 use core::mem::MaybeUninit;
 
 use crate::error::{NegativeErrorExt, NumericError};
-use crate::socket::UdpEp;
+use crate::socket::{RxAux, UdpEp};
 
 use embedded_nal::SocketAddr;
 
@@ -106,6 +106,72 @@ impl<'a, const UDPCOUNT: usize> StackAccessor<'a, UDPCOUNT> {
         Ok(&mut self.stack.udp_sockets[last] as *mut _)
     }
 
+    /// Bind the socket to a complete local endpoint
+    ///
+    /// Unlike [embedded_nal::UdpFullStack::bind], which only takes a port and listens on all
+    /// addresses, this allows binding to a concrete local address (and, through the endpoint's
+    /// netif, to a concrete interface). Responses sent through the socket then use that address
+    /// as their source address. Suitable endpoints can be found through
+    /// [UdpEp::local_candidates_for].
+    pub fn bind_to(
+        &mut self,
+        handle: &mut UdpSocket<'a>,
+        local: &UdpEp,
+    ) -> Result<(), NumericError> {
+        self.create(handle, local, None)
+    }
+
+    /// Connect the socket to a remote endpoint, sending from a concrete local endpoint
+    ///
+    /// This is like [embedded_nal::UdpClientStack::connect], but does not leave the choice of the
+    /// source address to the stack.
+    pub fn connect_from(
+        &mut self,
+        handle: &mut UdpSocket<'a>,
+        local: &UdpEp,
+        remote: SocketAddr,
+    ) -> Result<(), NumericError> {
+        let remote = remote.into();
+
+        self.create(handle, local, Some(&remote))
+    }
+
+    /// Receive a datagram like [embedded_nal::UdpClientStack::receive], and report auxiliary
+    /// information about it
+    ///
+    /// See [RxAux] for which information is available.
+    #[doc(alias = "sock_udp_recv_aux")]
+    pub fn receive_with_aux(
+        &mut self,
+        socket: &mut UdpSocket<'a>,
+        buffer: &mut [u8],
+    ) -> Result<(usize, SocketAddr, RxAux), nb::Error<NumericError>> {
+        let socket = socket.access()?;
+
+        let mut remote = MaybeUninit::uninit();
+        let mut aux = RxAux::request();
+
+        // unsafe: OK per C API
+        let read = (unsafe {
+            riot_sys::sock_udp_recv_aux(
+                socket,
+                buffer.as_mut_ptr() as _,
+                buffer.len().try_into().unwrap(),
+                0,
+                remote.as_mut_ptr(),
+                &mut aux,
+            )
+        })
+        .negative_to_error()
+        .map(|e| e as usize)
+        .map_err(|e| e.again_is_wouldblock())?;
+
+        // unsafe: Set by C function
+        let remote = UdpEp(unsafe { remote.assume_init() });
+
+        Ok((read, remote.into(), RxAux::from_filled(&aux)))
+    }
+
     /// Wrapper around sock_udp_create
     fn create(
         &mut self,
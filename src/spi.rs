@@ -76,3 +76,112 @@ impl<'a> blocking::spi::Transfer<u8> for AcquiredSPI<'a> {
         Ok(words)
     }
 }
+
+/// An SPI bus along with the chip select line and bus configuration of a particular device on it
+///
+/// Unlike [AcquiredSPI], this acquires the bus only for the duration of each transaction, and is
+/// thus suitable for use with the [embedded_hal_1::spi::SpiDevice] trait, which it implements if
+/// the `ztimer_usec` module is available (which is used for delays inside transactions).
+#[cfg(feature = "with_embedded_hal_1")]
+pub struct SPIBusDevice {
+    pub bus: spi_t,
+    pub cs: spi_cs_t,
+    pub mode: spi_mode_t,
+    pub clk: spi_clk_t,
+}
+
+#[cfg(feature = "with_embedded_hal_1")]
+impl embedded_hal_1::spi::ErrorType for SPIBusDevice {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "with_embedded_hal_1")]
+impl SPIBusDevice {
+    /// Transfer bytes while the bus is acquired; either side may be absent, in which case RIOT
+    /// sends dummy bytes or discards the received ones.
+    fn transfer_bytes(
+        &mut self,
+        out: Option<&[u8]>,
+        in_: Option<&mut [u8]>,
+        len: usize,
+        cont: bool,
+    ) {
+        let out = out.map(|o| o.as_ptr()).unwrap_or(core::ptr::null());
+        let in_ = in_.map(|i| i.as_mut_ptr()).unwrap_or(core::ptr::null_mut());
+        unsafe {
+            spi_transfer_bytes(
+                self.bus,
+                self.cs,
+                cont,
+                out as *const _,
+                in_ as *mut _,
+                len as _,
+            )
+        };
+    }
+}
+
+#[cfg(all(feature = "with_embedded_hal_1", riot_module_ztimer_usec))]
+impl embedded_hal_1::spi::SpiDevice for SPIBusDevice {
+    fn transaction(
+        &mut self,
+        operations: &mut [embedded_hal_1::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        use embedded_hal_1::spi::Operation;
+
+        unsafe { spi_acquire(self.bus, self.cs, self.mode, self.clk) };
+
+        // Chip select stays asserted until the last transfer is done; delays after it do not
+        // need it asserted.
+        let last_transfer = operations
+            .iter()
+            .rposition(|o| !matches!(o, Operation::DelayNs(_)));
+        for (i, operation) in operations.iter_mut().enumerate() {
+            let cont = last_transfer.map_or(false, |last| i < last);
+            match operation {
+                Operation::Read(buffer) => {
+                    let len = buffer.len();
+                    self.transfer_bytes(None, Some(buffer), len, cont)
+                }
+                Operation::Write(bytes) => {
+                    self.transfer_bytes(Some(bytes), None, bytes.len(), cont)
+                }
+                Operation::Transfer(read, write) => {
+                    let common = read.len().min(write.len());
+                    let (read, read_rest) = read.split_at_mut(common);
+                    let (write, write_rest) = write.split_at(common);
+                    let any_rest = !read_rest.is_empty() || !write_rest.is_empty();
+                    self.transfer_bytes(Some(write), Some(read), common, cont || any_rest);
+                    // At most one of these is non-empty
+                    if !read_rest.is_empty() {
+                        let len = read_rest.len();
+                        self.transfer_bytes(None, Some(read_rest), len, cont);
+                    }
+                    if !write_rest.is_empty() {
+                        self.transfer_bytes(Some(write_rest), None, write_rest.len(), cont);
+                    }
+                }
+                Operation::TransferInPlace(words) => {
+                    let len = words.len();
+                    let ptr = words.as_mut_ptr();
+                    unsafe {
+                        spi_transfer_bytes(
+                            self.bus,
+                            self.cs,
+                            cont,
+                            ptr as *const _,
+                            ptr as *mut _,
+                            len as _,
+                        )
+                    };
+                }
+                Operation::DelayNs(ns) => {
+                    crate::ztimer::Clock::usec().spin_ticks(*ns / 1000 + (*ns % 1000 != 0) as u32);
+                }
+            }
+        }
+
+        unsafe { spi_release(self.bus) };
+        Ok(())
+    }
+}
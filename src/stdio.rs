@@ -24,6 +24,9 @@ impl ::core::fmt::Write for Stdio {
             return Ok(());
         }
 
+        #[cfg(feature = "stdio_capture")]
+        capture::record(data);
+
         let result = unsafe { stdio_write(transmute(data.as_ptr()), len as _) };
 
         if result >= 0 {
@@ -45,6 +48,118 @@ impl Stdio {
     }
 }
 
+pub mod table;
+
+/// In-memory copy of the output written through [Stdio]
+///
+/// While capturing is active, everything written through [Stdio] (and thus through [println] and
+/// [dbg]) is also stored in a ring buffer of [SIZE](capture::SIZE) bytes, from which the most
+/// recent output can be inspected. Output of C code (eg. `printf`) is not captured.
+///
+/// This is intended for tests on the native board that check what a component printed:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::{println, stdio::capture};
+/// capture::start();
+/// println!("Temperature: {}", 23);
+/// assert!(capture::contains(b"Temperature: 23"));
+/// capture::stop();
+/// ```
+///
+/// This module is only available with the `stdio_capture` feature.
+#[cfg(feature = "stdio_capture")]
+pub mod capture {
+    use core::cell::UnsafeCell;
+
+    /// Number of most recent bytes that are retained
+    pub const SIZE: usize = 1024;
+
+    struct Capture {
+        enabled: bool,
+        data: [u8; SIZE],
+        /// Index of the oldest byte
+        start: usize,
+        len: usize,
+    }
+
+    struct CaptureCell(UnsafeCell<Capture>);
+
+    // Only accessed with interrupts disabled
+    unsafe impl Sync for CaptureCell {}
+
+    static CAPTURE: CaptureCell = CaptureCell(UnsafeCell::new(Capture {
+        enabled: false,
+        data: [0; SIZE],
+        start: 0,
+        len: 0,
+    }));
+
+    fn with_capture<R>(f: impl FnOnce(&mut Capture) -> R) -> R {
+        // unsafe: Interrupts are off, and no references escape the closure.
+        crate::interrupt::free(|_| f(unsafe { &mut *CAPTURE.0.get() }))
+    }
+
+    pub(super) fn record(data: &[u8]) {
+        with_capture(|c| {
+            if !c.enabled {
+                return;
+            }
+            for &byte in data {
+                let end = (c.start + c.len) % SIZE;
+                c.data[end] = byte;
+                if c.len == SIZE {
+                    c.start = (c.start + 1) % SIZE;
+                } else {
+                    c.len += 1;
+                }
+            }
+        })
+    }
+
+    /// Discard any captured output, and start capturing
+    pub fn start() {
+        with_capture(|c| {
+            c.enabled = true;
+            c.start = 0;
+            c.len = 0;
+        })
+    }
+
+    /// Stop capturing; the output captured so far stays available
+    pub fn stop() {
+        with_capture(|c| c.enabled = false)
+    }
+
+    /// Discard any captured output
+    pub fn clear() {
+        with_capture(|c| {
+            c.start = 0;
+            c.len = 0;
+        })
+    }
+
+    /// Copy the captured output (oldest first) into `buf`, returning the number of bytes copied
+    ///
+    /// If the buffer is too small, only the oldest part of the output is copied.
+    pub fn read(buf: &mut [u8]) -> usize {
+        with_capture(|c| {
+            let len = c.len.min(buf.len());
+            for (i, slot) in buf[..len].iter_mut().enumerate() {
+                *slot = c.data[(c.start + i) % SIZE];
+            }
+            len
+        })
+    }
+
+    /// Whether the captured output contains the given sequence of bytes
+    pub fn contains(needle: &[u8]) -> bool {
+        let mut buf = [0; SIZE];
+        let len = read(&mut buf);
+        needle.is_empty() || buf[..len].windows(needle.len()).any(|w| w == needle)
+    }
+}
+
 // Copied and adapted from Rust 1.32.0
 #[macro_export]
 macro_rules! dbg {
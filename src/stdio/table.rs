@@ -0,0 +1,140 @@
+//! Aligned tabular output
+//!
+//! A [Table] writes rows of values in columns of fixed width, as diagnostic shell commands
+//! typically do:
+//!
+//! ```no_run
+//! # #![no_std]
+//! use riot_wrappers::stdio::{Stdio, table::{Column, Table}};
+//!
+//! let mut table = Table::new(
+//!     Stdio {},
+//!     &[
+//!         Column::left("name", 16),
+//!         Column::right("prio", 4),
+//!         Column::right("stack", 6),
+//!     ],
+//! );
+//! table.header().ok();
+//! for thread in riot_wrappers::thread::all_threads() {
+//!     let name = thread.name.as_deref().unwrap_or("-");
+//!     let stack = thread.stack.map(|s| s.size()).unwrap_or(0);
+//!     table.row(&[&name, &thread.priority, &stack]).ok();
+//! }
+//! ```
+//!
+//! Padding is done here rather than through the width and alignment parameters of format
+//! strings, which keeps the formatting code for a table in one place rather than in every
+//! format string. Values that are wider than their column are written in full, shifting the
+//! rest of the row.
+
+use core::fmt::{Display, Result, Write};
+
+/// How values are placed within their [Column]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Description of a column of a [Table]
+#[derive(Debug, Copy, Clone)]
+pub struct Column {
+    header: &'static str,
+    width: u8,
+    align: Align,
+}
+
+impl Column {
+    /// A column with left-aligned values (typically text)
+    pub const fn left(header: &'static str, width: u8) -> Self {
+        Column {
+            header,
+            width,
+            align: Align::Left,
+        }
+    }
+
+    /// A column with right-aligned values (typically numbers)
+    pub const fn right(header: &'static str, width: u8) -> Self {
+        Column {
+            header,
+            width,
+            align: Align::Right,
+        }
+    }
+}
+
+/// Writer that only counts how many characters would be written
+struct Count(usize);
+
+impl Write for Count {
+    fn write_str(&mut self, s: &str) -> Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+/// Writer of rows of aligned values; see the [module level documentation](self) for usage
+pub struct Table<'a, W: Write> {
+    out: W,
+    columns: &'a [Column],
+}
+
+impl<'a, W: Write> Table<'a, W> {
+    pub fn new(out: W, columns: &'a [Column]) -> Self {
+        Table { out, columns }
+    }
+
+    fn pad(&mut self, n: usize) -> Result {
+        for _ in 0..n {
+            self.out.write_char(' ')?;
+        }
+        Ok(())
+    }
+
+    fn cell(&mut self, column: &Column, value: &dyn Display, last: bool) -> Result {
+        let mut count = Count(0);
+        write!(count, "{}", value)?;
+        let padding = (column.width as usize).saturating_sub(count.0);
+        if column.align == Align::Right {
+            self.pad(padding)?;
+        }
+        write!(self.out, "{}", value)?;
+        // Trailing spaces would only be noise at the end of the line
+        if column.align == Align::Left && !last {
+            self.pad(padding)?;
+        }
+        Ok(())
+    }
+
+    /// Write a line with the column headers
+    pub fn header(&mut self) -> Result {
+        let columns = self.columns;
+        self.line(columns.iter().map(|c| &c.header as &dyn Display))
+    }
+
+    /// Write a line with one value per column
+    ///
+    /// Values beyond the number of columns are ignored; missing values leave their columns
+    /// empty.
+    pub fn row(&mut self, values: &[&dyn Display]) -> Result {
+        self.line(values.iter().copied())
+    }
+
+    fn line<'v>(&mut self, values: impl Iterator<Item = &'v dyn Display>) -> Result {
+        let columns = self.columns;
+        for (i, (column, value)) in columns.iter().zip(values).enumerate() {
+            if i != 0 {
+                self.out.write_char(' ')?;
+            }
+            self.cell(column, value, i + 1 == columns.len())?;
+        }
+        self.out.write_char('\n')
+    }
+
+    /// Stop writing the table, giving back the writer
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
@@ -0,0 +1,44 @@
+//! Triggering firmware updates through the [SUIT worker](https://doc.riot-os.org/group__sys__suit.html)
+//!
+//! The SUIT worker processes a manifest in its own thread: It verifies the manifest, fetches the
+//! payloads it references, writes them to the update slot, and (by default) reboots into the new
+//! firmware. The manifest is either fetched from a URL ([trigger]) or passed in directly
+//! ([trigger_manifest]); the [coap] module provides a resource that accepts manifests over CoAP.
+//!
+//! When processing has finished, the worker calls `suit_worker_done_cb`; RIOT's default
+//! implementation logs the outcome and reboots on success. Applications that provide their own
+//! should pass the result on to [coap::worker_done] if they use the CoAP resource.
+
+use crate::error::{NegativeErrorExt, NumericError};
+
+#[cfg(feature = "with_coap_handler")]
+pub mod coap;
+
+/// Fetch the manifest from the URL, and process it
+///
+/// If the worker is already processing a manifest, this blocks until that has finished.
+#[doc(alias = "suit_worker_trigger")]
+pub fn trigger(url: &str) {
+    // unsafe: OK per C API; the URL is copied into the worker's buffer before this returns.
+    unsafe { riot_sys::suit_worker_trigger(url.as_ptr() as _, url.len() as _) };
+}
+
+/// Process the given manifest
+///
+/// The manifest is copied into the worker's manifest buffer. This fails with `EAGAIN` if the
+/// worker is busy processing another manifest, and with `ENOMEM` if the manifest exceeds the
+/// worker's buffer (`CONFIG_SUIT_MANIFEST_BUFSIZE`).
+#[doc(alias = "suit_worker_trigger_prepared")]
+pub fn trigger_manifest(manifest: &[u8]) -> Result<(), NumericError> {
+    let mut buffer: *mut u8 = core::ptr::null_mut();
+    let mut size = manifest.len() as _;
+    // unsafe: OK per C API
+    unsafe { riot_sys::suit_worker_try_prepare(&mut buffer, &mut size) }.negative_to_error()?;
+    // unsafe: The worker lock is held, and try_prepare guarantees the buffer to be large enough
+    let target = unsafe { core::slice::from_raw_parts_mut(buffer, manifest.len()) };
+    target.copy_from_slice(manifest);
+    // unsafe: OK per C API; this passes the prepared buffer back to the worker, which releases
+    // the lock when done.
+    unsafe { riot_sys::suit_worker_trigger_prepared(buffer, manifest.len() as _) };
+    Ok(())
+}
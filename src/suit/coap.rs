@@ -0,0 +1,249 @@
+//! A CoAP resource that accepts SUIT manifests
+//!
+//! [ManifestResource] takes a manifest in a POST request (block-wise using Block1 if it exceeds a
+//! single message), hands it to the SUIT worker, and reports the state of the update in
+//! responses to GET:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gcoap::{self, SingleHandlerListener};
+//! # use riot_wrappers::coap_handler::GcoapHandler;
+//! use riot_wrappers::suit::coap::ManifestResource;
+//!
+//! let mut handler = GcoapHandler(ManifestResource::<1024>::new());
+//! let mut listener = SingleHandlerListener::new(
+//!     cstr::cstr!("/suit/manifest"),
+//!     riot_sys::COAP_GET | riot_sys::COAP_POST,
+//!     &mut handler,
+//! );
+//! gcoap::scope(|greg| {
+//!     greg.register(&mut listener);
+//!     // ...
+//! });
+//! ```
+//!
+//! The state is reported as text:
+//!
+//! * `idle`: No manifest was received yet
+//! * `receiving <bytes>`: A block-wise transfer is in progress
+//! * `processing`: The worker is processing the manifest
+//! * `done`: The worker has reported success
+//! * `failed <error>`: The worker (or the attempt to start it) failed with the given error
+//!
+//! Completion is only known if the worker's result is passed to [worker_done]; otherwise, the
+//! state remains at `processing` (which, with RIOT's default `suit_worker_done_cb`, is ended by a
+//! reboot on success anyway):
+//!
+//! ```no_run
+//! #[no_mangle]
+//! extern "C" fn suit_worker_done_cb(result: i32) {
+//!     riot_wrappers::suit::coap::worker_done(result);
+//! }
+//! ```
+//!
+//! There is no access control beyond what the CoAP stack provides (eg. through OSCORE); as with
+//! any SUIT transport, authenticity is ensured by the manifest's signature.
+
+use core::convert::TryInto;
+use core::fmt::Write;
+
+use coap_message::{MessageOption, MutableWritableMessage, ReadableMessage};
+use coap_numbers::{code, option};
+
+use crate::coap_handler::block::Block;
+use crate::mutex::Mutex;
+
+/// Result of the last run of the worker, as reported through [worker_done]
+static RESULT: Mutex<Option<i32>> = Mutex::new(None);
+
+/// Report the result of the SUIT worker's processing to [ManifestResource]s
+///
+/// This is to be called from the application's `suit_worker_done_cb`; `result` is 0 on success,
+/// and a negative error otherwise.
+pub fn worker_done(result: i32) {
+    *RESULT.lock() = Some(result);
+}
+
+#[derive(Copy, Clone, Debug)]
+enum State {
+    Idle,
+    Receiving,
+    Processing,
+    Done,
+    Failed(i32),
+}
+
+/// Longest text that is reported in responses to GET
+type Text = heapless::String<24>;
+
+/// Request data of a [ManifestResource]: What is to be sent in the response
+pub struct RequestData(Result<Action, u8>);
+
+enum Action {
+    Status(Text),
+    Received { block1: Option<Block>, code: u8 },
+}
+
+/// A [coap_handler::Handler] that takes SUIT manifests of up to `N` bytes
+pub struct ManifestResource<const N: usize> {
+    buffer: [u8; N],
+    received: usize,
+    state: State,
+}
+
+impl<const N: usize> ManifestResource<N> {
+    pub const fn new() -> Self {
+        ManifestResource {
+            buffer: [0; N],
+            received: 0,
+            state: State::Idle,
+        }
+    }
+
+    fn update_state(&mut self) {
+        if let State::Processing = self.state {
+            match RESULT.lock().take() {
+                Some(0) => self.state = State::Done,
+                Some(e) => self.state = State::Failed(e),
+                None => (),
+            }
+        }
+    }
+
+    fn status(&mut self) -> Text {
+        self.update_state();
+        let mut text = Text::new();
+        // All variants fit in the text
+        let _ = match self.state {
+            State::Idle => write!(text, "idle"),
+            State::Receiving => write!(text, "receiving {}", self.received),
+            State::Processing => write!(text, "processing"),
+            State::Done => write!(text, "done"),
+            State::Failed(e) => write!(text, "failed {}", e),
+        };
+        text
+    }
+
+    /// Store a part of the manifest, and trigger the worker if it was the last
+    ///
+    /// Returns the response code.
+    fn receive(&mut self, block1: Option<Block>, payload: &[u8]) -> Result<u8, u8> {
+        self.update_state();
+        if let State::Processing = self.state {
+            return Err(code::SERVICE_UNAVAILABLE);
+        }
+
+        let offset = block1.map(|b| b.offset()).unwrap_or(0);
+        if offset == 0 {
+            self.received = 0;
+        } else if offset != self.received || !matches!(self.state, State::Receiving) {
+            return Err(code::REQUEST_ENTITY_INCOMPLETE);
+        }
+        let end = offset + payload.len();
+        if end > N {
+            self.state = State::Idle;
+            return Err(code::REQUEST_ENTITY_TOO_LARGE);
+        }
+        self.buffer[offset..end].copy_from_slice(payload);
+        self.received = end;
+
+        if block1.map(|b| b.more).unwrap_or(false) {
+            self.state = State::Receiving;
+            return Ok(code::CONTINUE);
+        }
+
+        // Discard any result of an earlier run
+        RESULT.lock().take();
+        match super::trigger_manifest(&self.buffer[..self.received]) {
+            Ok(()) => {
+                self.state = State::Processing;
+                Ok(code::CHANGED)
+            }
+            Err(e) => {
+                self.state = State::Failed(e.to_negative());
+                Err(code::SERVICE_UNAVAILABLE)
+            }
+        }
+    }
+
+    fn extract(&mut self, request: &impl ReadableMessage) -> Result<Action, u8> {
+        let method: u8 = request.code().into();
+        match method {
+            code::GET => Ok(Action::Status(self.status())),
+            code::POST => {
+                let block1 = match request.options().find(|o| o.number() == option::BLOCK1) {
+                    None => None,
+                    Some(o) => Some(Block::parse(o.value()).ok_or(code::BAD_REQUEST)?),
+                };
+                let code = self.receive(block1, request.payload())?;
+                Ok(Action::Received { block1, code })
+            }
+            _ => Err(code::METHOD_NOT_ALLOWED),
+        }
+    }
+}
+
+impl<const N: usize> Default for ManifestResource<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn set_code(response: &mut impl MutableWritableMessage, code: u8) {
+    response.set_code(
+        code.try_into()
+            .map_err(|_| "Message type can't express response code")
+            .unwrap(),
+    );
+}
+
+impl<const N: usize> coap_handler::Handler for ManifestResource<N> {
+    type RequestData = RequestData;
+
+    fn extract_request_data(&mut self, request: &impl ReadableMessage) -> Self::RequestData {
+        RequestData(self.extract(request))
+    }
+
+    fn estimate_length(&mut self, _request: &Self::RequestData) -> usize {
+        Text::new().capacity() + 8
+    }
+
+    fn build_response(
+        &mut self,
+        response: &mut impl MutableWritableMessage,
+        request: Self::RequestData,
+    ) {
+        match request.0 {
+            Ok(Action::Status(text)) => {
+                set_code(response, code::CONTENT);
+                // text/plain;charset=utf-8 is 0, which is encoded as an empty option value
+                response.add_option(
+                    option::CONTENT_FORMAT
+                        .try_into()
+                        .map_err(|_| "Message type can't express option")
+                        .unwrap(),
+                    &[],
+                );
+                response.set_payload(text.as_bytes());
+            }
+            Ok(Action::Received { block1, code }) => {
+                set_code(response, code);
+                if let Some(block1) = block1 {
+                    let (value, len) = block1.encode();
+                    response.add_option(
+                        option::BLOCK1
+                            .try_into()
+                            .map_err(|_| "Message type can't express option")
+                            .unwrap(),
+                        &value[..len],
+                    );
+                }
+                response.set_payload(b"");
+            }
+            Err(code) => {
+                set_code(response, code);
+                response.set_payload(b"");
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Tools for running tests on a RIOT board (or on native)
+//!
+//! The main contribution of this module is the [riot_test] macro, which takes the place of
+//! [riot_main](crate::riot_main) in test applications. It runs a list of test functions in
+//! sequence and reports their outcome on stdio in the [Test Anything Protocol] (TAP) format:
+//!
+//! ```text
+//! 1..2
+//! ok 1 - adds_up
+//! Error: "Values differ"
+//! not ok 2 - subtracts # status 1
+//! # 1 of 2 tests failed
+//! FAILURE
+//! ```
+//!
+//! The final `SUCCESS` or `FAILURE` line is what RIOT's Python test runners (`tests/01-run.py`)
+//! typically wait for. When running on native, the process exits after the tests with a status
+//! that indicates whether all tests passed, so the test application can also be run from CI
+//! without any test runner.
+//!
+//! Tests are run in the main thread, one after the other. As panics can not be caught, a panicking
+//! test ends the run (and, as no `SUCCESS` is printed, fails it); tests should rather indicate
+//! failure by returning an error.
+//!
+//! [Test Anything Protocol]: https://testanything.org/
+
+use crate::stdio::println;
+
+/// A single test registered through [riot_test]
+///
+/// This is only public because it is used by the macro; it is not expected to be constructed
+/// manually.
+#[doc(hidden)]
+pub struct TestCase {
+    pub name: &'static str,
+    pub function: fn() -> i32,
+}
+
+/// Run all tests, print their outcomes, and return the process status
+///
+/// On native, this does not return but exits the process with that status.
+#[doc(hidden)]
+pub fn run(tests: &[TestCase]) -> i32 {
+    println!("1..{}", tests.len());
+
+    let mut failed = 0;
+    for (i, test) in tests.iter().enumerate() {
+        match (test.function)() {
+            0 => println!("ok {} - {}", i + 1, test.name),
+            status => {
+                println!("not ok {} - {} # status {}", i + 1, test.name, status);
+                failed += 1;
+            }
+        }
+    }
+
+    let status = if failed == 0 {
+        println!("# all {} tests passed", tests.len());
+        println!("SUCCESS");
+        0
+    } else {
+        println!("# {} of {} tests failed", failed, tests.len());
+        println!("FAILURE");
+        1
+    };
+
+    exit(status)
+}
+
+/// Return the status, or (on native, where this has a meaning) terminate the process with it.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn exit(status: i32) -> i32 {
+    extern "C" {
+        // The host's exit function; RIOT's native port leaves it accessible.
+        fn exit(status: core::ffi::c_int) -> !;
+    }
+    // unsafe: Terminates the process, which is what is intended at the end of a test run
+    unsafe { exit(status) }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn exit(status: i32) -> i32 {
+    status
+}
+
+/// To run a set of tests as the application, run the `riot_test!` macro with the names of the test
+/// functions as an item (ie. top level in a module) in your crate. Each function needs to take no
+/// arguments, and return something that implements the [Termination](crate::main::Termination)
+/// trait (typically `()` or a [Result] with a [Debug](core::fmt::Debug) error).
+///
+/// This replaces the [riot_main](crate::riot_main) macro, and produces output as described in the
+/// [module level documentation](crate::testing).
+///
+/// Example:
+///
+/// ```
+/// # #![no_std]
+/// # use riot_wrappers::riot_test;
+/// riot_test!(adds_up, fails_gracefully);
+///
+/// fn adds_up() {
+///     assert_eq!(1 + 1, 2);
+/// }
+///
+/// fn fails_gracefully() -> Result<(), &'static str> {
+///     Err("Not implemented yet")
+/// }
+/// ```
+#[macro_export]
+macro_rules! riot_test {
+    ($($test:ident),* $(,)?) => {
+        #[export_name = "main"]
+        pub extern "C" fn c_main() -> i32 {
+            $crate::testing::run(&[
+                $(
+                    $crate::testing::TestCase {
+                        name: stringify!($test),
+                        function: || $crate::main::Termination::report($test()),
+                    },
+                )*
+            ])
+        }
+    };
+}
@@ -25,9 +25,28 @@ mod tokenparts;
 pub use tokenparts::TokenParts;
 pub use tokenparts::{EndToken, InIsr, InThread, StartToken, TerminationToken, ValueInThread};
 
+#[cfg(riot_module_core_thread_flags)]
+pub mod flags;
+
+#[cfg(riot_module_sched_cb)]
+pub(crate) mod switch_hook;
+#[cfg(riot_module_sched_cb)]
+pub use switch_hook::{set_switch_hook, SetSwitchHookError, SwitchHook};
+
+mod local;
+pub use local::{ThreadLocal, ThreadLocalError};
+
 mod stack_stats;
-pub use stack_stats::{StackStats, StackStatsError};
+pub use stack_stats::{StackChecker, StackMonitor, StackStats, StackStatsError, StackWatermark};
+
+#[cfg(riot_module_schedstatistics)]
+mod sched_stats;
+#[cfg(riot_module_schedstatistics)]
+pub use sched_stats::SchedStats;
+#[cfg(all(riot_module_schedstatistics, riot_module_core_idle_thread))]
+pub use sched_stats::{cpu_usage, CpuUsage};
 
 /// Error returned by PID methods when no thread with that PID exists
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct NoSuchThread;
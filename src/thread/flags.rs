@@ -0,0 +1,217 @@
+//! Wrappers for [thread flags](https://doc.riot-os.org/group__core__thread__flags.html)
+//!
+//! Thread flags are a light-weight way of signalling a thread: Any thread or interrupt can
+//! [set] flags on a thread, and the thread can wait for any or all of a set of flags to be set.
+//! Unlike messages, flags carry no data and do not queue up: Setting a flag that is already set
+//! has no further effect.
+//!
+//! ```no_run
+//! # #![no_std]
+//! use riot_wrappers::thread::flags::{self, Flags};
+//!
+//! const DATA_READY: Flags = Flags::bit(0);
+//! const SHUTDOWN: Flags = Flags::bit(1);
+//!
+//! // In an interrupt handler, with `worker` being the thread's KernelPID:
+//! # let worker = riot_wrappers::thread::get_pid();
+//! flags::set(worker, DATA_READY).ok();
+//!
+//! // In the worker thread:
+//! loop {
+//!     let woken = flags::wait_any(DATA_READY | SHUTDOWN);
+//!     if woken.contains(SHUTDOWN) {
+//!         break;
+//!     }
+//!     // ... process data ...
+//! }
+//! ```
+//!
+//! Some flags have meaning to RIOT (see [Flags::TIMEOUT] and [Flags::MSG_WAITING]), and some are
+//! used by other parts of this crate; applications best use the low bits. The bits this crate
+//! uses are:
+//!
+//! * bit 10: [crate::thread::JOIN_FLAG]
+//! * bit 11: [crate::offload::OFFLOAD_FLAG]
+//! * bit 12: [crate::embassy::EXECUTOR_FLAG]
+//! * bit 13: [crate::ztimer::SLEEP_CANCEL_FLAG]
+//!
+//! Each of them is only used in threads that use the respective component. New flags used by this
+//! crate need to be added to this list, and must not share a bit with any of the above.
+
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+use super::{KernelPID, NoSuchThread};
+
+/// A set of thread flags
+///
+/// This can be combined and queried like the types produced by the `bitflags` crate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Flags(riot_sys::thread_flags_t);
+
+impl Flags {
+    /// Flag set by `ztimer_set_timeout_flag` when the timeout passed
+    pub const TIMEOUT: Flags = Flags(riot_sys::THREAD_FLAG_TIMEOUT as _);
+
+    /// Flag set when a message becomes available in the thread's message queue
+    pub const MSG_WAITING: Flags = Flags(riot_sys::THREAD_FLAG_MSG_WAITING as _);
+
+    /// The set containing no flags
+    pub const fn empty() -> Self {
+        Flags(0)
+    }
+
+    /// The set containing all flags
+    pub const fn all() -> Self {
+        Flags(!0)
+    }
+
+    /// The set containing only the `n`th flag
+    ///
+    /// ## Panics
+    ///
+    /// This panics (at build time if used in a const) if there is no such flag.
+    pub const fn bit(n: u8) -> Self {
+        assert!(
+            (n as usize) < core::mem::size_of::<riot_sys::thread_flags_t>() * 8,
+            "No such thread flag"
+        );
+        Flags(1 << n)
+    }
+
+    /// Build a set from its C representation
+    pub const fn from_bits(bits: riot_sys::thread_flags_t) -> Self {
+        Flags(bits)
+    }
+
+    /// The C representation of the set
+    pub const fn bits(&self) -> riot_sys::thread_flags_t {
+        self.0
+    }
+
+    /// True if no flags are in the set
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// True if all flags of `other` are in the set
+    pub const fn contains(&self, other: Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// True if any flag of `other` is in the set
+    pub const fn intersects(&self, other: Flags) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for Flags {
+    type Output = Flags;
+
+    fn bitor(self, other: Flags) -> Flags {
+        Flags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Flags {
+    fn bitor_assign(&mut self, other: Flags) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for Flags {
+    type Output = Flags;
+
+    fn bitand(self, other: Flags) -> Flags {
+        Flags(self.0 & other.0)
+    }
+}
+
+impl BitAndAssign for Flags {
+    fn bitand_assign(&mut self, other: Flags) {
+        self.0 &= other.0;
+    }
+}
+
+impl Not for Flags {
+    type Output = Flags;
+
+    fn not(self) -> Flags {
+        Flags(!self.0)
+    }
+}
+
+/// Set flags on a thread, waking it up if it is waiting for them
+///
+/// This can be called from any thread, and from interrupts.
+#[doc(alias = "thread_flags_set")]
+pub fn set(thread: KernelPID, flags: Flags) -> Result<(), NoSuchThread> {
+    // Interrupts are disabled so that the thread can not terminate between the lookup and the
+    // setting of the flags.
+    crate::interrupt::free(|_| {
+        let thread = thread.thread()?;
+        // unsafe: OK per C API; the thread pointer was just obtained from a valid PID.
+        unsafe { riot_sys::thread_flags_set(thread as *mut _, flags.0) };
+        Ok(())
+    })
+}
+
+/// Clear flags of the current thread, returning which of them were set
+///
+/// ## Panics
+///
+/// This panics when called in an interrupt context.
+#[doc(alias = "thread_flags_clear")]
+pub fn clear(mask: Flags) -> Flags {
+    in_thread();
+    // unsafe: OK per C API
+    Flags(unsafe { riot_sys::thread_flags_clear(mask.0) })
+}
+
+/// Wait until any of the flags in `mask` are set on the current thread
+///
+/// All flags of the mask that were set are cleared and returned.
+///
+/// ## Panics
+///
+/// This panics when called in an interrupt context.
+#[doc(alias = "thread_flags_wait_any")]
+pub fn wait_any(mask: Flags) -> Flags {
+    in_thread();
+    // unsafe: OK per C API
+    Flags(unsafe { riot_sys::thread_flags_wait_any(mask.0) })
+}
+
+/// Wait until all of the flags in `mask` are set on the current thread
+///
+/// The flags of the mask are cleared and returned.
+///
+/// ## Panics
+///
+/// This panics when called in an interrupt context.
+#[doc(alias = "thread_flags_wait_all")]
+pub fn wait_all(mask: Flags) -> Flags {
+    in_thread();
+    // unsafe: OK per C API
+    Flags(unsafe { riot_sys::thread_flags_wait_all(mask.0) })
+}
+
+/// Wait until any of the flags in `mask` are set on the current thread
+///
+/// Only one of the set flags (the lowest) is cleared and returned; others stay set for later
+/// calls.
+///
+/// ## Panics
+///
+/// This panics when called in an interrupt context.
+#[doc(alias = "thread_flags_wait_one")]
+pub fn wait_one(mask: Flags) -> Flags {
+    in_thread();
+    // unsafe: OK per C API
+    Flags(unsafe { riot_sys::thread_flags_wait_one(mask.0) })
+}
+
+fn in_thread() {
+    super::InThread::new()
+        .expect("Thread flags of the current thread are only accessible in threads");
+}
@@ -0,0 +1,161 @@
+use core::cell::UnsafeCell;
+
+use super::{get_pid, KernelPID};
+
+/// Error returned by [ThreadLocal::with]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ThreadLocalError {
+    /// All entries are in use by other threads
+    Full,
+    /// The current thread's entry is already being accessed further up the call stack
+    Reentrant,
+}
+
+#[derive(Copy, Clone)]
+struct Key {
+    pid: riot_sys::kernel_pid_t,
+    /// Identifies the thread beyond its PID: The thread_t is placed in the thread's stack, so a
+    /// thread that later gets the same PID typically has a different one -- unless it is created
+    /// on the same stack, see the [ThreadLocal] documentation.
+    thread: *const riot_sys::thread_t,
+    in_use: bool,
+}
+
+impl Key {
+    fn is_alive(&self) -> bool {
+        KernelPID(self.pid).thread().ok() == Some(self.thread)
+    }
+}
+
+/// A container that holds a separate value for each thread that accesses it
+///
+/// This serves where other platforms would use thread local storage, eg. for a logging context
+/// or a scratch buffer that each thread needs its own copy of. Values are created when a thread
+/// first accesses the container, and live in a table of `N` entries; an entry is reused once its
+/// thread has ended.
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::thread::ThreadLocal;
+/// static LOG_PREFIX: ThreadLocal<heapless::String<16>, 4> = ThreadLocal::new();
+///
+/// LOG_PREFIX
+///     .with(heapless::String::new, |prefix| {
+///         prefix.clear();
+///         prefix.push_str("sensor: ").unwrap();
+///     })
+///     .unwrap();
+/// ```
+///
+/// Values of ended threads are only dropped when their entry is reused.
+///
+/// RIOT does not tell threads apart beyond their PID and the location of their thread control
+/// block (which lives in the thread's stack). Thus, a thread that is created on the same stack as
+/// an ended thread and gets the same PID (as is common when a thread is restarted on a static
+/// stack) is indistinguishable from the old thread, and finds the old thread's value.
+pub struct ThreadLocal<T, const N: usize> {
+    /// Only accessed in critical sections
+    keys: UnsafeCell<[Option<Key>; N]>,
+    values: [UnsafeCell<Option<T>>; N],
+}
+
+// Each value is only accessed by the thread whose key is in the same position, except for the
+// dropping of values of ended threads (which thus needs T: Send).
+unsafe impl<T: Send, const N: usize> Sync for ThreadLocal<T, N> {}
+
+impl<T, const N: usize> ThreadLocal<T, N> {
+    const EMPTY: UnsafeCell<Option<T>> = UnsafeCell::new(None);
+
+    /// Create a container in which no thread has a value yet
+    pub const fn new() -> Self {
+        ThreadLocal {
+            keys: UnsafeCell::new([None; N]),
+            values: [Self::EMPTY; N],
+        }
+    }
+
+    /// Run a closure on the current thread's value, creating it using `init` if the thread has
+    /// none yet
+    ///
+    /// ## Panics
+    ///
+    /// This panics when called in an interrupt context.
+    pub fn with<R>(
+        &self,
+        init: impl FnOnce() -> T,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Result<R, ThreadLocalError> {
+        crate::thread::InThread::new()
+            .expect("ThreadLocal may only be accessed from within threads");
+        let pid = get_pid();
+        let thread = pid.thread().expect("The current thread exists");
+
+        let (index, fresh) = crate::interrupt::free(|_| {
+            // unsafe: Keys are only accessed in critical sections
+            let keys = unsafe { &mut *self.keys.get() };
+            if let Some(index) = keys
+                .iter()
+                .position(|k| matches!(k, Some(k) if k.pid == pid.0 && k.thread == thread))
+            {
+                let key = keys[index].as_mut().expect("Found above");
+                if key.in_use {
+                    return Err(ThreadLocalError::Reentrant);
+                }
+                key.in_use = true;
+                return Ok((index, false));
+            }
+            let index = keys
+                .iter()
+                .position(|k| k.map(|k| !k.is_alive()).unwrap_or(true))
+                .ok_or(ThreadLocalError::Full)?;
+            keys[index] = Some(Key {
+                pid: pid.0,
+                thread,
+                in_use: true,
+            });
+            Ok((index, true))
+        })?;
+
+        // Released even if init or f panic (and the thread continues, eg. because the panic was
+        // caught), so that the thread does not find its value stuck in use.
+        let _release = Release {
+            keys: &self.keys,
+            index,
+        };
+
+        // unsafe: The key is marked in use by this thread, so no other access to the value happens
+        // until it is released.
+        let value = unsafe { &mut *self.values[index].get() };
+        if fresh {
+            // Dropping any value of an ended thread, outside the critical section as that can take
+            // arbitrarily long
+            *value = None;
+        }
+        Ok(f(value.get_or_insert_with(init)))
+    }
+}
+
+/// Guard that marks a key as not in use any more when dropped
+struct Release<'a, const N: usize> {
+    keys: &'a UnsafeCell<[Option<Key>; N]>,
+    index: usize,
+}
+
+impl<const N: usize> Drop for Release<'_, N> {
+    fn drop(&mut self) {
+        crate::interrupt::free(|_| {
+            // unsafe: Keys are only accessed in critical sections
+            let keys = unsafe { &mut *self.keys.get() };
+            if let Some(key) = &mut keys[self.index] {
+                key.in_use = false;
+            }
+        });
+    }
+}
+
+impl<T, const N: usize> Default for ThreadLocal<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
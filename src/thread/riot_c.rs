@@ -1,17 +1,24 @@
 //! RIOT (C) thread implementation
 use riot_sys as raw;
 
-use super::{NoSuchThread, StackStats, StackStatsError};
-use crate::helpers::PointerToCStr;
+use super::{InThread, NoSuchThread, StackStats, StackStatsError};
+use crate::cstr_helpers::PointerToCStr;
 
 /// Offloaded tools for creation
 mod creation;
-pub use creation::{scope, spawn, CountedThread, CountingThreadScope};
+#[cfg(feature = "set_panic_handler")]
+pub(crate) use creation::record_panicked_thread;
+#[cfg(riot_module_core_thread_flags)]
+pub use creation::JOIN_FLAG;
+pub use creation::{
+    scope, spawn, Builder, CountedThread, CountingThreadScope, Outcome, TrackedThread,
+};
 
 /// Wrapper around a valid (not necessarily running, but in-range) [riot_sys::kernel_pid_t] that
 /// provides access to thread details and signaling.
 // Possible optimization: Make this NonZero
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct KernelPID(pub(crate) raw::kernel_pid_t);
 
 pub(crate) mod pid_converted {
@@ -51,6 +58,7 @@ mod status_converted {
 
 
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum Status {
     // I would not rely on any properties of the assigned values, but it might make the conversion
@@ -113,6 +121,14 @@ impl KernelPID {
             .map(|i| KernelPID::new(i).expect("Should be valid by construction"))
     }
 
+    /// Find a thread by its name
+    ///
+    /// If several threads have the same name, the one with the lowest PID is returned. As thread
+    /// names are only available with develhelp, this always returns None without.
+    pub fn find_by_name(name: &str) -> Option<KernelPID> {
+        Self::all_pids().find(|pid| pid.get_name() == Some(name))
+    }
+
     pub fn get_name(&self) -> Option<&str> {
         let ptr = unsafe { raw::thread_getname(self.0) };
 
@@ -146,7 +162,7 @@ impl KernelPID {
 
     /// Pick the thread_t out of sched_threads for the PID
     #[doc(alias = "thread_get")]
-    fn thread(&self) -> Result<*const riot_sys::thread_t, NoSuchThread> {
+    pub(crate) fn thread(&self) -> Result<*const riot_sys::thread_t, NoSuchThread> {
         // unsafe: C function's "checked" precondition met by type constraint on PID validity
         let t = unsafe { riot_sys::thread_get_unchecked(self.0) };
         // .as_ref() would have the null check built in, but we can't build a shared refernce out
@@ -163,6 +179,104 @@ impl KernelPID {
         Ok(unsafe { (*thread).priority })
     }
 
+    /// Change the thread's priority
+    ///
+    /// Lower numbers are higher priorities. If the change makes a thread more important than the
+    /// current thread, the scheduler switches to it right away.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the priority is not below `SCHED_PRIO_LEVELS`.
+    #[doc(alias = "sched_change_priority")]
+    pub fn set_priority(&self, prio: u8) -> Result<(), NoSuchThread> {
+        assert!(
+            (prio as u32) < riot_sys::SCHED_PRIO_LEVELS as u32,
+            "Priority exceeds SCHED_PRIO_LEVELS"
+        );
+        // Interrupts are disabled so that the thread can not terminate between the lookup and the
+        // change; any resulting switch happens when they are enabled again.
+        crate::interrupt::free(|_| {
+            let thread = self.thread()?;
+            // unsafe: OK per C API; the thread exists, and the priority is valid.
+            unsafe { riot_sys::sched_change_priority(thread as *mut _, prio) };
+            Ok(())
+        })
+    }
+
+    /// Send a numeric message to the thread without blocking
+    ///
+    /// If the thread is neither waiting for a message nor has space in its message queue, the
+    /// message is not sent, and [ReceiverNotWaiting](crate::msg::MsgSendError::ReceiverNotWaiting)
+    /// is returned. Producers can use that to slow down or drop data instead of blocking on a
+    /// lagging consumer.
+    ///
+    /// This can also be called in interrupts (where RIOT handles it like
+    /// [try_send_msg_from_isr](Self::try_send_msg_from_isr)).
+    #[cfg(riot_module_core_msg)]
+    #[doc(alias = "msg_try_send")]
+    pub fn try_send_msg(&self, type_: u16, value: u32) -> Result<(), crate::msg::MsgSendError> {
+        use crate::msg::WrapsMsgT;
+        let mut m = crate::msg::NumericMsg::new(type_, value).extract();
+        // unsafe: OK per C API
+        match unsafe { riot_sys::msg_try_send(&mut m, self.0) } {
+            1 => Ok(()),
+            0 => Err(crate::msg::MsgSendError::ReceiverNotWaiting),
+            _ => Err(crate::msg::MsgSendError::InvalidPID),
+        }
+    }
+
+    /// Send a numeric message to the thread from an interrupt
+    ///
+    /// This behaves like [try_send_msg](Self::try_send_msg), but the [InIsr](super::InIsr) token
+    /// saves the check for the context; see [crate::msg::send_from_isr] for details.
+    #[cfg(riot_module_core_msg)]
+    #[doc(alias = "msg_send_int")]
+    pub fn try_send_msg_from_isr(
+        &self,
+        in_isr: super::InIsr,
+        type_: u16,
+        value: u32,
+    ) -> Result<(), crate::msg::MsgSendError> {
+        crate::msg::send_from_isr(in_isr, self, crate::msg::NumericMsg::new(type_, value))
+    }
+
+    /// Report how full the thread's message queue is
+    ///
+    /// Returns None if the thread has no message queue (and thus only receives messages while it
+    /// is waiting for them).
+    #[cfg(riot_module_core_msg)]
+    pub fn msg_queue_stats(&self) -> Result<Option<MsgQueueStats>, NoSuchThread> {
+        crate::interrupt::free(|_| {
+            let thread = self.thread()?;
+            // unsafe: The thread exists, and its queue is only modified with interrupts disabled.
+            let (array, queue) = unsafe { ((*thread).msg_array, (*thread).msg_queue) };
+            if array.is_null() {
+                return Ok(None);
+            }
+            Ok(Some(MsgQueueStats {
+                capacity: queue.mask as usize + 1,
+                used: queue.write_count.wrapping_sub(queue.read_count) as usize,
+            }))
+        })
+    }
+
+    /// Read the scheduler's statistics of the thread
+    ///
+    /// This function is only available if the schedstatistics module is built.
+    #[cfg(riot_module_schedstatistics)]
+    #[doc(alias = "sched_pidlist")]
+    pub fn sched_stats(&self) -> Result<super::SchedStats, NoSuchThread> {
+        // Ensure the thread exists; the statistics of a stopped thread linger until the PID is
+        // reused.
+        self.thread()?;
+        Ok(crate::interrupt::free(|_| {
+            // unsafe: The PID is in range, and the scheduler only updates the entries with
+            // interrupts disabled.
+            let stats = unsafe { &*core::ptr::addr_of!(riot_sys::sched_pidlist[self.0 as usize]) };
+            super::SchedStats::from_c(stats)
+        }))
+    }
+
     /// Gather information about the stack's thread.
     ///
     /// A None being returned can have two reasons:
@@ -199,6 +313,111 @@ impl Into<raw::kernel_pid_t> for KernelPID {
     }
 }
 
+/// Longest thread name kept in a [ThreadInfo]; longer names are truncated
+const THREAD_INFO_NAME_MAX: usize = 24;
+
+/// Information about a thread at one point in time, as produced by [all_threads()]
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ThreadInfo {
+    pub pid: KernelPID,
+    /// The thread's name (if names are available, which needs develhelp)
+    pub name: Option<heapless::String<THREAD_INFO_NAME_MAX>>,
+    pub status: Status,
+    pub priority: u8,
+    /// Stack usage (if available, which needs develhelp)
+    pub stack: Option<StackStats>,
+}
+
+impl ThreadInfo {
+    fn snapshot(pid: KernelPID) -> Option<Self> {
+        let (thread, status, priority, name) = crate::interrupt::free(|_| {
+            let thread = pid.thread().ok()?;
+            let status = pid.status().ok()?;
+            // unsafe: The thread exists, and can not go away while interrupts are disabled
+            let priority = unsafe { (*thread).priority };
+            let name = pid.get_name().map(|name| {
+                let mut copy = heapless::String::new();
+                for c in name.chars() {
+                    if copy.push(c).is_err() {
+                        break;
+                    }
+                }
+                copy
+            });
+            Some((thread, status, priority, name))
+        })?;
+        // Measured outside the critical section, as it takes time proportional to the stack size
+        let stack = pid.stack_stats().ok();
+        if pid.thread().ok() != Some(thread) {
+            // The thread ended while its stack was measured
+            return None;
+        }
+        Some(ThreadInfo {
+            pid,
+            name,
+            status,
+            priority,
+            stack,
+        })
+    }
+}
+
+/// Iterate over all threads, taking a consistent snapshot of each
+///
+/// Unlike querying a [KernelPID] for one property after the other, each thread's name, status and
+/// priority are read at the same time, and threads that end while they are being inspected are
+/// skipped. This is the basis of `ps`-like tools:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::println;
+/// for thread in riot_wrappers::thread::all_threads() {
+///     println!(
+///         "{:?} {:?} {:?} prio {}",
+///         thread.pid, thread.name, thread.status, thread.priority
+///     );
+/// }
+/// ```
+pub fn all_threads() -> impl Iterator<Item = ThreadInfo> {
+    KernelPID::all_pids().filter_map(ThreadInfo::snapshot)
+}
+
+/// Occupancy of a thread's message queue, as returned by [KernelPID::msg_queue_stats()]
+#[cfg(riot_module_core_msg)]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct MsgQueueStats {
+    /// Number of messages the queue can hold
+    pub capacity: usize,
+    /// Number of messages waiting in the queue
+    pub used: usize,
+}
+
+#[cfg(riot_module_core_msg)]
+impl MsgQueueStats {
+    /// Number of messages that can be sent to the thread before its queue is full
+    pub fn free(&self) -> usize {
+        self.capacity.saturating_sub(self.used)
+    }
+}
+
+/// Number of messages waiting in the current thread's message queue
+///
+/// Returns None if the current thread has no message queue.
+#[cfg(riot_module_core_msg)]
+#[doc(alias = "msg_avail")]
+pub fn msg_avail() -> Option<usize> {
+    // unsafe: OK per C API
+    let avail = unsafe { raw::msg_avail() };
+    if avail < 0 {
+        None
+    } else {
+        Some(avail as usize)
+    }
+}
+
 /// PID of the currently active thread
 #[doc(alias = "thread_getpid")]
 pub fn get_pid() -> KernelPID {
@@ -207,9 +426,58 @@ pub fn get_pid() -> KernelPID {
     KernelPID(unsafe { raw::thread_getpid() })
 }
 
+/// Number of stack bytes the current thread is using right now
+///
+/// This is the distance between the top of the thread's stack and the current stack pointer
+/// (approximated by the location of a local variable); unlike [StackStats::used], which reports
+/// the high-water mark, this is cheap to evaluate as no stack needs to be scanned. All platforms
+/// supported by RIOT have downwards growing stacks.
+///
+/// Like [KernelPID::stack_stats()], this needs develhelp to be active.
+pub fn current_stack_usage() -> Result<usize, StackStatsError> {
+    #[cfg(riot_develhelp)]
+    return {
+        let thread = get_pid().thread()?;
+        let marker = 0u8;
+        // unsafe: Fields are present with develhelp, and the current thread is not going away.
+        let top = unsafe { (*thread).stack_start as usize + (*thread).stack_size as usize };
+        Ok(top.saturating_sub(&marker as *const u8 as usize))
+    };
+    #[cfg(not(riot_develhelp))]
+    return {
+        // Without develhelp, only the existence of the thread is checked
+        get_pid().thread()?;
+        Err(StackStatsError::InformationUnavailable)
+    };
+}
+
 /// Put the current thread in the "sleeping" state, only to be continue when something calls
 /// [KernelPID::wakeup()] on its PID.
 #[doc(alias = "thread_sleep")]
 pub fn sleep() {
     unsafe { raw::thread_sleep() }
 }
+
+/// Let other threads of the same priority run
+///
+/// The current thread is moved to the end of its priority's run queue; if no other thread of that
+/// priority is ready, it continues right away. (Threads of higher priority would already be
+/// running, so this is only useful for cooperative scheduling between equal-priority threads.)
+#[doc(alias = "thread_yield")]
+pub fn yield_now() {
+    // unsafe: OK per C API
+    unsafe { raw::thread_yield() }
+}
+
+/// Run the scheduler, switching to a higher priority thread if one became ready
+///
+/// Unlike [yield_now()], this does not let threads of the same priority run. It is needed after
+/// changing the run queue through means that do not trigger the scheduler themselves.
+///
+/// This takes an [InThread] token, as in interrupts, the switch is done when the interrupt
+/// returns anyway.
+#[doc(alias = "thread_yield_higher")]
+pub fn yield_higher(_in_thread: InThread) {
+    // unsafe: OK per C API
+    unsafe { raw::thread_yield_higher() }
+}
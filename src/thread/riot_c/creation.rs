@@ -1,81 +1,176 @@
 use super::{KernelPID, Status};
 
+use core::cell::UnsafeCell;
 use core::ffi::CStr;
 use core::intrinsics::transmute;
 use core::marker::PhantomData;
 use riot_sys as raw;
 use riot_sys::libc;
 
+/// What a new thread needs to start running, placed in its stack memory by [create()]
+struct Start<F> {
+    closure: F,
+    scope: Option<scoped::Link>,
+}
+
 /// Internal helper that does all the casting but relies on the caller to establish appropriate
 /// lifetimes.
 ///
+/// The closure is moved into the start of the stack memory (where it stays in place until the
+/// thread takes it), and only the remaining memory is passed to RIOT as the thread's stack.
+///
 /// This also returns a pointer to the created thread's control block inside the stack; that TCB
 /// can be used to get the thread's status even when the thread is already stopped and the PID may
 /// have been reused for a different thread. For short-lived threads that are done before this
 /// function returns, the TCB may be None.
-unsafe fn create<R>(
+unsafe fn create<F>(
     stack: &mut [u8],
-    closure: &mut R,
+    closure: F,
+    scope: Option<scoped::Link>,
     name: &CStr,
     priority: u8,
     flags: i32,
 ) -> (raw::kernel_pid_t, Option<*mut riot_sys::thread_t>)
 where
-    R: Send + FnMut(),
+    F: Send + FnOnce(),
 {
-    // overwriting name "R" as suggested as "copy[ing] over the parameters" on
+    // overwriting name "F" as suggested as "copy[ing] over the parameters" on
     // https://doc.rust-lang.org/error-index.html#E0401
-    unsafe extern "C" fn run<R>(x: *mut libc::c_void) -> *mut libc::c_void
+    unsafe extern "C" fn run<F>(x: *mut libc::c_void) -> *mut libc::c_void
     where
-        R: Send + FnMut(),
+        F: Send + FnOnce(),
     {
-        let closure: &mut R = transmute(x);
-        closure();
+        // unsafe: Written by create(), and read only once here
+        let start = core::ptr::read(x as *mut Start<F>);
+        if let Some(link) = start.scope {
+            scoped::enter(link);
+        }
+        (start.closure)();
+        outcome::set(Outcome::Completed);
+        #[cfg(riot_module_core_thread_flags)]
+        join::finished();
+        scoped::leave();
         0 as *mut libc::c_void
     }
 
+    let offset = stack
+        .as_ptr()
+        .align_offset(core::mem::align_of::<Start<F>>());
+    let used = offset.saturating_add(core::mem::size_of::<Start<F>>());
+    assert!(
+        used < stack.len(),
+        "Stack too small to even hold the thread's closure"
+    );
+    let (head, stack) = stack.split_at_mut(used);
+    let start = head.as_mut_ptr().add(offset) as *mut Start<F>;
+    start.write(Start { closure, scope });
+
+    // The thread is created sleeping, so that the bookkeeping about it can be reset (its PID may
+    // have been used by an earlier thread) before it can run.
+    let sleeping = flags & riot_sys::THREAD_CREATE_SLEEPING as i32 != 0;
+    let without_yield = flags & riot_sys::THREAD_CREATE_WOUT_YIELD as i32 != 0;
     let pid = raw::thread_create(
         transmute(stack.as_mut_ptr()),
         stack.len() as i32,
         priority,
-        flags,
-        Some(run::<R>),
-        closure as *mut R as *mut _,
+        flags | riot_sys::THREAD_CREATE_SLEEPING as i32,
+        Some(run::<F>),
+        start as *mut _,
         name.as_ptr() as _,
     );
 
-    let tcb = riot_sys::thread_get(pid);
+    if pid < 0 {
+        // The thread never ran, so the closure was not taken
+        core::ptr::drop_in_place(start);
+        return (pid, None);
+    }
+
+    outcome::created(pid);
+    #[cfg(riot_module_core_thread_flags)]
+    join::created(pid);
+
+    let thread = riot_sys::thread_get(pid);
     // FIXME: Rather than doing pointer comparisons, it'd be nicer to just get the stack's
     // calculated thread control block (TCB) position and look right in there.
-    let tcb = if tcb >= &stack[0] as *const u8 as *mut _
-        && tcb <= &stack[stack.len() - 1] as *const u8 as *mut _
+    let tcb = if thread >= &stack[0] as *const u8 as *mut _
+        && thread <= &stack[stack.len() - 1] as *const u8 as *mut _
     {
-        Some(crate::inline_cast_mut(tcb))
+        Some(crate::inline_cast_mut(thread))
     } else {
         None
     };
 
+    if !sleeping {
+        if without_yield {
+            // Like thread_wakeup, but without switching to the thread
+            crate::interrupt::free(|_| {
+                riot_sys::sched_set_status(
+                    crate::inline_cast_mut(thread),
+                    riot_sys::thread_status_t_STATUS_PENDING,
+                )
+            });
+        } else {
+            raw::thread_wakeup(pid);
+        }
+    }
+
     (pid, tcb)
 }
 
 /// Create a context for starting threads that take shorter than 'static references.
 ///
-/// Inside the scope, threads can be created using the `.spawn()` method of the scope passed in,
-/// similar to the scoped-threads RFC (which resembles crossbeam's threads). Unlike that, the scope
-/// has no dynamic memory of the spawned threads, and no actual way of waiting for a thread. If the
-/// callback returns, the caller has call the scope's `.reap()` method with all the threads that
-/// were launched; otherwise, the program panics.
+/// Inside the scope, threads can be created using the `.spawn_once()` (or `.spawn()`) method of
+/// the scope passed in, similar to [`std::thread::scope`]. Unlike that, the scope has no dynamic
+/// memory: Each thread's stack is provided by the caller, and the thread's closure is kept in
+/// that memory until the thread starts. Closures can borrow anything that outlives the scope:
+///
+/// ```no_run
+/// # #![no_std]
+/// use riot_wrappers::thread::scope;
+///
+/// let mut samples = [0u16; 64];
+/// let mut stack = [0u8; 1024];
+/// scope(|s| {
+///     s.spawn_once(
+///         &mut stack,
+///         || {
+///             for (i, sample) in samples.iter_mut().enumerate() {
+///                 *sample = i as u16;
+///             }
+///         },
+///         cstr::cstr!("filler"),
+///         5,
+///         0,
+///     )
+///     .unwrap();
+/// });
+/// // The thread is done here, and the borrow of samples has ended
+/// assert_eq!(samples[63], 63);
+/// ```
+///
+/// When the callback returns, `scope()` blocks until all threads spawned in it have terminated.
+/// This needs the core_thread_flags module; without it, the program panics if any thread is still
+/// running at that time. Threads can also be waited for individually through the scope's
+/// `.join()` method, which reports their [Outcome].
+///
+/// A thread that panics never terminates: Its stack may still be referenced (eg. by a nested
+/// scope or by a C component the thread registered something with), so it is left in place.
+/// Consequently, `scope()` blocks forever if any of its threads panics.
+///
+/// [`std::thread::scope`]: https://doc.rust-lang.org/std/thread/fn.scope.html
 pub fn scope<'env, F, R>(callback: F) -> R
 where
     F: for<'id> FnOnce(&mut CountingThreadScope<'env, 'id>) -> R,
 {
     let mut s = CountingThreadScope {
-        threads: 0,
+        pending: UnsafeCell::new(0),
+        owner: super::get_pid(),
         _phantom: PhantomData,
     };
 
     let ret = callback(&mut s);
 
+    // Running threads point to s, so it must not move before they are done
     s.wait_for_all();
 
     ret
@@ -100,15 +195,18 @@ where
 ///   monomorphized CountingThreadScope unique in the sense that no two instances of
 ///   CountingThreadScope can ever have the same type.
 ///
-///   By having unique types, it is ensured that a counted thread is only counted down (in
-///   [`.reap()`]) in the scope it was born in, and that no shenanigans with counters being swapped
-///   around with [core::mem::swap()] are used to trick the compiler into allowing use-after-free.
+///   By having unique types, it is ensured that a counted thread is only reaped or joined in the
+///   scope it was born in, and that no shenanigans with scopes being swapped around with
+///   [core::mem::swap()] are used to trick the compiler into allowing use-after-free.
 ///
 /// This technique was inspired by (and is explained well) in [the GhostCell
 /// Paper](http://plv.mpi-sws.org/rustbelt/ghostcell/paper.pdf).
 ///
 pub struct CountingThreadScope<'env, 'id> {
-    threads: u16, // a counter, but larger than kernel_pid_t
+    // Number of threads that have not terminated yet, which they count down themselves. Only
+    // accessed with interrupts disabled.
+    pending: UnsafeCell<u16>, // a counter, but larger than kernel_pid_t
+    owner: KernelPID,
     _phantom: PhantomData<(&'env (), &'id ())>,
 }
 
@@ -117,31 +215,33 @@ impl<'env, 'id> CountingThreadScope<'env, 'id> {
     /// readable name (ignored in no-DEVHELP mode), and is started with the priority and flags as
     /// per thread_create documentation.
     ///
-    /// The returned thread object can safely be discarded when the scope is not expected to ever
-    /// return, and needs to be passed on to `.reap()` otherwise.
+    /// The closure is moved into the start of the stack memory, which reduces the space available
+    /// to the thread accordingly.
     ///
-    /// Having the closure as a mutable reference (rather than a moved instance) is a bit
-    /// unergonomic as it means that `spawn(..., || { foo }, ..)` one-line invocations are
-    /// impossible, but is necessary as it avoids having the callback sitting in the Thread which
-    /// can't be prevented from moving around on the stack between the point when thread_create is
-    /// called (and the pointer is passed on to RIOT) and the point when the threads starts running
-    /// and that pointer is used.
-    pub fn spawn<R>(
+    /// The returned thread object can be used to [join](Self::join) the thread individually, but
+    /// can also be discarded: The scope waits for all its threads when it ends.
+    pub fn spawn_once<F>(
         &mut self,
         stack: &'env mut [u8],
-        closure: &'env mut R,
+        closure: F,
         name: &'env CStr,
         priority: u8,
         flags: i32,
     ) -> Result<CountedThread<'id>, raw::kernel_pid_t>
     where
-        R: Send + FnMut(),
+        F: Send + FnOnce() + 'env,
     {
-        self.threads = self.threads.checked_add(1).expect("Thread limit exceeded");
+        // Counting up before the thread is created, as it may run to completion right away
+        self.with_pending(|p| *p = p.checked_add(1).expect("Thread limit exceeded"));
 
-        let (pid, tcb) = unsafe { create(stack, closure, name, priority, flags) };
+        let link = scoped::Link {
+            owner: self.owner.0,
+            pending: &self.pending,
+        };
+        let (pid, tcb) = unsafe { create(stack, closure, Some(link), name, priority, flags) };
 
         if pid < 0 {
+            self.with_pending(|p| *p -= 1);
             return Err(pid);
         }
 
@@ -154,24 +254,65 @@ impl<'env, 'id> CountingThreadScope<'env, 'id> {
         })
     }
 
-    /// Assert that the thread has terminated, and remove it from the list of pending threads in
-    /// this context.
+    /// Start a thread like [`.spawn_once()`](Self::spawn_once), but running a closure that is
+    /// borrowed rather than moved into the thread's stack memory
+    pub fn spawn<R>(
+        &mut self,
+        stack: &'env mut [u8],
+        closure: &'env mut R,
+        name: &'env CStr,
+        priority: u8,
+        flags: i32,
+    ) -> Result<CountedThread<'id>, raw::kernel_pid_t>
+    where
+        R: Send + FnMut(),
+    {
+        self.spawn_once(stack, move || closure(), name, priority, flags)
+    }
+
+    fn with_pending<T>(&self, f: impl FnOnce(&mut u16) -> T) -> T {
+        // unsafe: Interrupts are disabled, and the reference does not leave the closure
+        crate::interrupt::free(|_| f(unsafe { &mut *self.pending.get() }))
+    }
+
+    /// Assert that the thread has terminated, and obtain its outcome
     ///
-    /// Unlike a (POSIX) wait, this will not block (for there is no SIGCHLDish thing in RIOT --
-    /// whoever wants to be notified would need to make their threads send an explicit signal), but
-    /// panic if the thread is not actually done yet.
-    pub fn reap(&mut self, thread: CountedThread<'id>) {
+    /// Unlike a (POSIX) wait, this will not block, but panic if the thread is not actually done
+    /// yet.
+    ///
+    /// As threads that panicked never terminate, they can not be reaped; their state is
+    /// available through [CountedThread::outcome()].
+    pub fn reap(&mut self, thread: CountedThread<'id>) -> Outcome {
         match thread.status() {
             Status::Stopped => (),
             _ => panic!("Attempted to reap running process"),
         }
 
-        self.threads -= 1;
+        thread.thread.outcome()
+    }
+
+    /// Block until the thread has terminated, and obtain its outcome
+    ///
+    /// This is the blocking version of [`.reap()`](Self::reap), and blocks forever if the thread
+    /// panicked.
+    ///
+    /// This function is only available if the core_thread_flags module is built.
+    #[cfg(riot_module_core_thread_flags)]
+    pub fn join(&mut self, thread: CountedThread<'id>) -> Outcome {
+        thread.thread.join();
+        self.reap(thread)
     }
 
-    fn wait_for_all(self) {
-        if self.threads != 0 {
-            panic!("Not all threads were waited for at scope end");
+    fn wait_for_all(&self) {
+        while self.with_pending(|p| *p) != 0 {
+            // unsafe: OK per C API. Threads set the flag after counting down; if that happened
+            // since the check, this returns right away.
+            #[cfg(riot_module_core_thread_flags)]
+            unsafe {
+                riot_sys::thread_flags_wait_any(JOIN_FLAG)
+            };
+            #[cfg(not(riot_module_core_thread_flags))]
+            panic!("Threads still running at scope end (waiting for them needs core_thread_flags)");
         }
     }
 }
@@ -192,6 +333,11 @@ impl<'id> CountedThread<'id> {
     pub fn status(&self) -> Status {
         self.thread.status()
     }
+
+    /// How the thread ended, see [TrackedThread::outcome()]
+    pub fn outcome(&self) -> Outcome {
+        self.thread.outcome()
+    }
 }
 
 /// Create a thread with a statically allocated stack
@@ -205,7 +351,7 @@ pub fn spawn<R>(
 where
     R: Send + FnMut(),
 {
-    let (pid, tcb) = unsafe { create(stack, closure, name, priority, flags) };
+    let (pid, tcb) = unsafe { create(stack, move || closure(), None, name, priority, flags) };
 
     if pid < 0 {
         return Err(pid);
@@ -217,6 +363,140 @@ where
     })
 }
 
+/// Builder for threads with all creation options of `thread_create`
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::thread::Builder;
+/// static mut STACK: [u8; 1024] = [0; 1024];
+/// static mut WORKER: fn() = || {
+///     // ...
+/// };
+/// let thread = Builder::new(cstr::cstr!("worker"))
+///     .priority(5)
+///     .sleeping(true)
+///     .spawn(unsafe { &mut STACK }, unsafe { &mut WORKER })
+///     .unwrap();
+/// // The thread starts running only now
+/// thread.pid().wakeup().unwrap();
+/// ```
+///
+/// Inside a [scope()], threads can be built with shorter lived components using
+/// [`.spawn_scoped()`](Builder::spawn_scoped).
+#[derive(Debug, Copy, Clone)]
+pub struct Builder<'a> {
+    name: &'a CStr,
+    priority: Option<u8>,
+    flags: i32,
+}
+
+impl<'a> Builder<'a> {
+    /// Start building a thread with the given name (which is ignored in no-DEVHELP mode)
+    ///
+    /// Unless set otherwise, the thread runs at the priority of the thread that spawns it, is
+    /// started right away, and gets to run immediately if that priority is higher than the
+    /// current thread's.
+    pub fn new(name: &'a CStr) -> Self {
+        Builder {
+            name,
+            priority: None,
+            flags: 0,
+        }
+    }
+
+    /// Set the thread's priority (lower numbers are more important)
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    fn flag(mut self, flag: u32, set: bool) -> Self {
+        if set {
+            self.flags |= flag as i32;
+        } else {
+            self.flags &= !(flag as i32);
+        }
+        self
+    }
+
+    /// Create the thread in sleeping state; it starts running when it is woken up through
+    /// [KernelPID::wakeup()]
+    #[doc(alias = "THREAD_CREATE_SLEEPING")]
+    pub fn sleeping(self, sleeping: bool) -> Self {
+        self.flag(riot_sys::THREAD_CREATE_SLEEPING, sleeping)
+    }
+
+    /// Do not switch to the new thread right away even if it is more important than the current
+    /// thread; it runs when the scheduler is next invoked
+    #[doc(alias = "THREAD_CREATE_WOUT_YIELD")]
+    pub fn without_yield(self, without_yield: bool) -> Self {
+        self.flag(riot_sys::THREAD_CREATE_WOUT_YIELD, without_yield)
+    }
+
+    /// Fill the stack with a marker pattern, so that its usage can be measured through
+    /// [KernelPID::stack_stats()]
+    ///
+    /// This costs time proportional to the stack size at creation.
+    #[doc(alias = "THREAD_CREATE_STACKTEST")]
+    pub fn stack_test(self, stack_test: bool) -> Self {
+        self.flag(riot_sys::THREAD_CREATE_STACKTEST, stack_test)
+    }
+
+    fn resolved_priority(&self) -> u8 {
+        self.priority
+            .unwrap_or_else(|| super::get_pid().priority().expect("Current thread exists"))
+    }
+
+    /// Start the thread inside a [scope()]
+    ///
+    /// This is equivalent to [CountingThreadScope::spawn()] with the builder's options.
+    pub fn spawn_scoped<'id, R>(
+        self,
+        scope: &mut CountingThreadScope<'a, 'id>,
+        stack: &'a mut [u8],
+        closure: &'a mut R,
+    ) -> Result<CountedThread<'id>, raw::kernel_pid_t>
+    where
+        R: Send + FnMut(),
+    {
+        let priority = self.resolved_priority();
+        scope.spawn(stack, closure, self.name, priority, self.flags)
+    }
+
+    /// Start the thread inside a [scope()], moving the closure into the thread's stack memory
+    ///
+    /// This is equivalent to [CountingThreadScope::spawn_once()] with the builder's options.
+    pub fn spawn_scoped_once<'id, F>(
+        self,
+        scope: &mut CountingThreadScope<'a, 'id>,
+        stack: &'a mut [u8],
+        closure: F,
+    ) -> Result<CountedThread<'id>, raw::kernel_pid_t>
+    where
+        F: Send + FnOnce() + 'a,
+    {
+        let priority = self.resolved_priority();
+        scope.spawn_once(stack, closure, self.name, priority, self.flags)
+    }
+}
+
+impl Builder<'static> {
+    /// Start the thread with a statically allocated stack
+    ///
+    /// This is equivalent to [spawn()] with the builder's options.
+    pub fn spawn<R>(
+        self,
+        stack: &'static mut [u8],
+        closure: &'static mut R,
+    ) -> Result<TrackedThread, raw::kernel_pid_t>
+    where
+        R: Send + FnMut(),
+    {
+        let priority = self.resolved_priority();
+        spawn(stack, closure, self.name, priority, self.flags)
+    }
+}
+
 /// A thread identified not only by its PID (which can be reused whenever the thread has quit) but
 /// also by a pointer to its thread control block. This gives a TrackedThread a better get_status()
 /// method that reliably reports Stopped even when the PID is reused.
@@ -253,4 +533,316 @@ impl TrackedThread {
             Status::Stopped
         }
     }
+
+    /// How the thread ended
+    ///
+    /// This is only accurate while the thread's PID is not reused, which can happen any time
+    /// after the thread has stopped; [Outcome::Unknown] is reported when the PID was reused (or,
+    /// as with threads that terminated before their TCB could be found, that can not be ruled
+    /// out).
+    pub fn outcome(&self) -> Outcome {
+        // Checking the status, whether the PID was reused and reading the outcome at once
+        crate::interrupt::free(|_| {
+            if !matches!(self.status(), Status::Stopped) {
+                // The status check ensured that the PID still belongs to this thread
+                return match outcome::get(self.pid) {
+                    Outcome::Panicked => Outcome::Panicked,
+                    _ => Outcome::Running,
+                };
+            }
+            match (self.pid.thread(), self.tcb) {
+                // The PID is in use again by another thread
+                (Ok(_), _) => Outcome::Unknown,
+                (Err(_), Some(_)) => outcome::get(self.pid),
+                (Err(_), None) => Outcome::Unknown,
+            }
+        })
+    }
+
+    /// Block until the thread has terminated
+    ///
+    /// The current thread waits for the [JOIN_FLAG] thread flag, which the terminating thread
+    /// sets as it exits. Only one thread may wait for a given thread at a time. As a thread that
+    /// panicked never exits, joining it blocks forever.
+    ///
+    /// This function is only available if the core_thread_flags module is built.
+    ///
+    /// ## Panics
+    ///
+    /// This panics when called from an interrupt, when a thread attempts to join itself, or when
+    /// another thread is already waiting for the thread.
+    #[cfg(riot_module_core_thread_flags)]
+    pub fn join(&self) {
+        crate::thread::InThread::new().expect("Joining is only possible from within threads");
+        assert!(
+            self.pid != crate::thread::get_pid() || matches!(self.status(), Status::Stopped),
+            "A thread can not join itself"
+        );
+        loop {
+            let done = crate::interrupt::free(|_| {
+                // Checking the status first: If the PID was reused, the join state belongs to
+                // another thread.
+                matches!(self.status(), Status::Stopped) || join::register(self.pid)
+            });
+            if done {
+                return;
+            }
+            // unsafe: OK per C API
+            unsafe { riot_sys::thread_flags_wait_any(JOIN_FLAG) };
+        }
+    }
+}
+
+/// How a thread spawned through this module has ended, as reported by
+/// [CountingThreadScope::reap()] and [TrackedThread::outcome()]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Outcome {
+    /// The thread has not stopped yet
+    Running,
+    /// The thread's closure returned
+    Completed,
+    /// The thread's closure panicked
+    ///
+    /// The thread does not terminate then, but stays parked forever, as anything on its stack
+    /// may still be referenced from elsewhere. It can thus not be reaped or joined.
+    ///
+    /// This is only reported when the crate's panic handler is used (`set_panic_handler`
+    /// feature).
+    Panicked,
+    /// The thread has stopped, but its outcome was lost as its PID was reused
+    Unknown,
+}
+
+/// Per-PID record of how threads spawned through this module end
+mod outcome {
+    use core::cell::UnsafeCell;
+
+    use super::Outcome;
+    use crate::thread::KernelPID;
+
+    const SLOTS: usize = riot_sys::MAXTHREADS as usize + 1;
+
+    struct Outcomes(UnsafeCell<[Outcome; SLOTS]>);
+
+    // unsafe: Only accessed with interrupts disabled
+    unsafe impl Sync for Outcomes {}
+
+    // Threads not spawned through this module show as Unknown
+    static OUTCOMES: Outcomes = Outcomes(UnsafeCell::new([Outcome::Unknown; SLOTS]));
+
+    /// Called when a thread is created, before it runs
+    pub(super) fn created(pid: riot_sys::kernel_pid_t) {
+        // unsafe: Interrupts are disabled, and the reference does not leave the closure
+        crate::interrupt::free(|_| unsafe { (*OUTCOMES.0.get())[pid as usize] = Outcome::Running });
+    }
+
+    /// Set the outcome of the current thread
+    pub(super) fn set(outcome: Outcome) {
+        let me = crate::thread::get_pid().0 as usize;
+        // unsafe: Interrupts are disabled, and the reference does not leave the closure
+        crate::interrupt::free(|_| unsafe { (*OUTCOMES.0.get())[me] = outcome });
+    }
+
+    pub(super) fn get(pid: KernelPID) -> Outcome {
+        // unsafe: Interrupts are disabled, and the reference does not leave the closure
+        crate::interrupt::free(|_| unsafe { (*OUTCOMES.0.get())[pid.0 as usize] })
+    }
+}
+
+/// Called by the panic handler: If the current thread was spawned through this module, record
+/// that it panicked
+///
+/// The thread is not ended: Whatever it borrowed or registered (eg. with C components or in a
+/// nested scope) stays valid only as long as it does not return, so the panic handler parks it
+/// forever.
+#[cfg(feature = "set_panic_handler")]
+pub(crate) fn record_panicked_thread() {
+    if outcome::get(super::get_pid()) == Outcome::Running {
+        outcome::set(Outcome::Panicked);
+    }
+}
+
+/// Thread flag used by [TrackedThread::join] to indicate that the awaited thread has terminated
+///
+/// Threads that join other threads should not use this flag for other purposes. See the
+/// [flags module](crate::thread::flags) for the other flags used by this crate.
+#[cfg(riot_module_core_thread_flags)]
+pub const JOIN_FLAG: riot_sys::thread_flags_t = 1 << 10;
+
+/// Bookkeeping between threads created through this module and threads joining them
+#[cfg(riot_module_core_thread_flags)]
+mod join {
+    use core::cell::UnsafeCell;
+
+    use super::JOIN_FLAG;
+    use crate::thread::KernelPID;
+
+    #[derive(Copy, Clone)]
+    enum State {
+        Running,
+        Waiting(riot_sys::kernel_pid_t),
+        Done,
+    }
+
+    const SLOTS: usize = riot_sys::MAXTHREADS as usize + 1;
+
+    struct States(UnsafeCell<[State; SLOTS]>);
+
+    // unsafe: Only accessed with interrupts disabled
+    unsafe impl Sync for States {}
+
+    static STATES: States = States(UnsafeCell::new([State::Running; SLOTS]));
+
+    /// Run a closure on the state slot of a PID, with interrupts disabled
+    fn with_state<R>(pid: riot_sys::kernel_pid_t, f: impl FnOnce(&mut State) -> R) -> R {
+        crate::interrupt::free(|_| {
+            // unsafe: Interrupts are disabled, and the reference does not leave the closure
+            let states = unsafe { &mut *STATES.0.get() };
+            f(&mut states[pid as usize])
+        })
+    }
+
+    /// Called when a thread is created, before it runs
+    ///
+    /// Nobody can be waiting for it yet, as its PID was not known before.
+    pub(super) fn created(pid: riot_sys::kernel_pid_t) {
+        with_state(pid, |s| *s = State::Running);
+    }
+
+    /// Called by a thread after its closure has returned
+    ///
+    /// This leaves interrupts disabled: The joining thread must not run before this thread is
+    /// fully stopped (and thus does not touch its stack any more), which happens when it returns
+    /// into the thread exit code. (This is the same procedure as in RIOT's `pthread_exit`).
+    pub(super) fn finished() {
+        // unsafe: OK per C API; interrupts are re-enabled when the next thread is scheduled.
+        unsafe { riot_sys::irq_disable() };
+        // unsafe: Interrupts are disabled, and the reference does not leave this function
+        let state = unsafe { &mut (*STATES.0.get())[crate::thread::get_pid().0 as usize] };
+        if let State::Waiting(joiner) = *state {
+            if let Ok(joiner) = KernelPID(joiner).thread() {
+                // unsafe: OK per C API; the thread pointer was just obtained from a valid PID. As
+                // interrupts are disabled, the switch to the woken thread happens only at exit.
+                unsafe { riot_sys::thread_flags_set(joiner as *mut _, JOIN_FLAG) };
+            }
+        }
+        *state = State::Done;
+    }
+
+    /// Register the current thread as waiting for the thread, or return true if it has already
+    /// terminated
+    pub(super) fn register(pid: KernelPID) -> bool {
+        let me = crate::thread::get_pid().0;
+        with_state(pid.0, |s| match *s {
+            State::Done => true,
+            State::Waiting(other) if other != me => {
+                panic!("Another thread is already joining this thread")
+            }
+            _ => {
+                *s = State::Waiting(me);
+                false
+            }
+        })
+    }
+}
+
+/// Bookkeeping between threads spawned in a [scope()] and the scope's owner
+mod scoped {
+    use core::cell::UnsafeCell;
+
+    /// Where a thread spawned in a scope reports its termination
+    #[derive(Copy, Clone)]
+    pub(super) struct Link {
+        /// Thread that waits in the scope
+        pub(super) owner: riot_sys::kernel_pid_t,
+        /// The scope's counter of pending threads
+        pub(super) pending: *const UnsafeCell<u16>,
+    }
+
+    const SLOTS: usize = riot_sys::MAXTHREADS as usize + 1;
+
+    struct Links(UnsafeCell<[Option<Link>; SLOTS]>);
+
+    // unsafe: Only accessed with interrupts disabled
+    unsafe impl Sync for Links {}
+
+    static LINKS: Links = Links(UnsafeCell::new([None; SLOTS]));
+
+    /// Called by a new thread spawned in a scope before it runs its closure
+    pub(super) fn enter(link: Link) {
+        let me = crate::thread::get_pid().0 as usize;
+        // unsafe: Interrupts are disabled, and the reference does not leave the closure
+        crate::interrupt::free(|_| unsafe { (*LINKS.0.get())[me] = Some(link) });
+    }
+
+    /// Called by any thread created through this module when it is done
+    ///
+    /// Like `join::finished()`, this leaves interrupts disabled: The scope's owner must not run
+    /// before this thread is fully stopped, as leaving the scope ends the borrow of the thread's
+    /// stack.
+    pub(super) fn leave() {
+        // unsafe: OK per C API; interrupts are re-enabled when the next thread is scheduled.
+        unsafe { riot_sys::irq_disable() };
+        let me = crate::thread::get_pid().0 as usize;
+        // unsafe: Interrupts are disabled, and the reference does not leave this function
+        let link = match unsafe { (*LINKS.0.get())[me].take() } {
+            Some(link) => link,
+            None => return,
+        };
+        // unsafe: The scope does not go away before its pending count is zero, and interrupts are
+        // disabled.
+        unsafe { *(*link.pending).get() -= 1 };
+        #[cfg(riot_module_core_thread_flags)]
+        if let Ok(owner) = crate::thread::KernelPID(link.owner).thread() {
+            // unsafe: OK per C API; the thread pointer was just obtained from a valid PID.
+            unsafe { riot_sys::thread_flags_set(owner as *mut _, super::JOIN_FLAG) };
+        }
+    }
+}
+
+/// Spawn a thread whose stack and main function are placed in statics
+///
+/// This is the Rust equivalent of the common pattern in RIOT C code where a daemon thread is
+/// started with a `static char stack[...]`. The arguments are the thread name (a string literal),
+/// the stack size in bytes, the priority, and the thread's main function (a function or a
+/// non-capturing closure); the result is that of [spawn()].
+///
+/// ```no_run
+/// # #![no_std]
+/// fn blink() {
+///     loop {
+///         // ...
+///     }
+/// }
+///
+/// let thread = riot_wrappers::static_thread!("blink", 1024, 6, blink).unwrap();
+/// ```
+///
+/// As every invocation of the macro has its own statics, the expression can run only once; this
+/// panics if it is run a second time. For more creation options, use [Builder::spawn()] with
+/// statics of your own.
+#[macro_export]
+macro_rules! static_thread {
+    ( $name:literal, $stacksize:expr, $priority:expr, $main:expr ) => {{
+        static mut STACK: [u8; $stacksize] = [0; $stacksize];
+        static mut MAIN: fn() = $main;
+        static mut STARTED: bool = false;
+
+        // unsafe: Only accessed in this critical section
+        let started = $crate::interrupt::free(|_| unsafe {
+            core::mem::replace(&mut *core::ptr::addr_of_mut!(STARTED), true)
+        });
+        if started {
+            panic!(concat!("Static thread ", $name, " was already started"));
+        }
+        // unsafe: The check above ensures that the statics are borrowed only once.
+        let (stack, main) = unsafe {
+            (
+                &mut *core::ptr::addr_of_mut!(STACK),
+                &mut *core::ptr::addr_of_mut!(MAIN),
+            )
+        };
+        $crate::thread::spawn(stack, main, $crate::cstr::cstr!($name), $priority, 0)
+    }};
 }
@@ -0,0 +1,93 @@
+/// Scheduler statistics of a thread, returned by [KernelPID::sched_stats()](super::KernelPID::sched_stats)
+///
+/// Times are in ticks of the clock the `schedstatistics` module uses (microseconds on ztimer or
+/// xtimer based builds).
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct SchedStats {
+    /// Total time the thread has run
+    ///
+    /// For the currently running thread, this does not include the time since it was last
+    /// scheduled.
+    pub runtime_ticks: u64,
+    /// Number of times the thread was scheduled
+    pub schedules: u32,
+    /// Time at which the thread was last scheduled
+    pub last_start: u32,
+}
+
+impl SchedStats {
+    pub(crate) fn from_c(stats: &riot_sys::schedstat_t) -> Self {
+        SchedStats {
+            runtime_ticks: stats.runtime_ticks as _,
+            schedules: stats.schedules as _,
+            last_start: stats.laststart as _,
+        }
+    }
+}
+
+/// How the CPU time was spent since boot, see [cpu_usage()]
+#[cfg(riot_module_core_idle_thread)]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct CpuUsage {
+    /// Time spent in threads other than the idle thread
+    pub busy_ticks: u64,
+    /// Time spent in the idle thread
+    pub idle_ticks: u64,
+}
+
+#[cfg(riot_module_core_idle_thread)]
+impl CpuUsage {
+    /// Share of the busy time in per mille, or None if no time was accounted yet
+    pub fn busy_permille(&self) -> Option<u16> {
+        let total = self.busy_ticks + self.idle_ticks;
+        if total == 0 {
+            return None;
+        }
+        Some((self.busy_ticks * 1000 / total) as u16)
+    }
+}
+
+/// Sum up the scheduler statistics of all threads into the time spent busy and idle since boot
+///
+/// Time is attributed to the idle thread by its priority; other threads running at idle priority
+/// count as idle as well. Time spent in interrupts is counted towards the thread they
+/// interrupted, and threads that have terminated are not counted any more.
+///
+/// Differences between two calls give the usage over the time in between, which is what
+/// `ps`-style monitors typically show:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::thread::cpu_usage;
+/// let clock = riot_wrappers::ztimer::Clock::msec();
+/// let before = cpu_usage();
+/// clock.sleep_ticks(1000);
+/// let after = cpu_usage();
+/// let busy = after.busy_ticks - before.busy_ticks;
+/// let idle = after.idle_ticks - before.idle_ticks;
+/// ```
+///
+/// This function is only available if the core_idle_thread module is built, as without an idle
+/// thread, idle time is not accounted to any thread.
+#[cfg(riot_module_core_idle_thread)]
+pub fn cpu_usage() -> CpuUsage {
+    let idle_priority = (riot_sys::SCHED_PRIO_LEVELS - 1) as u8;
+    let mut usage = CpuUsage {
+        busy_ticks: 0,
+        idle_ticks: 0,
+    };
+    for pid in super::KernelPID::all_pids() {
+        if let (Ok(stats), Ok(priority)) = (pid.sched_stats(), pid.priority()) {
+            if priority == idle_priority {
+                usage.idle_ticks += stats.runtime_ticks;
+            } else {
+                usage.busy_ticks += stats.runtime_ticks;
+            }
+        }
+    }
+    usage
+}
@@ -31,10 +31,84 @@ impl StackStats {
     pub fn used(&self) -> usize {
         self.size - self.free
     }
+
+    /// Share of the stack that was used, in percent (rounded up, so that only an untouched stack
+    /// shows as 0%)
+    pub fn used_percent(&self) -> u8 {
+        if self.size == 0 {
+            return 0;
+        }
+        // Both are at most the address space size, so this only overflows on 64 bit platforms
+        // with absurdly large stacks.
+        ((self.used() * 100 + self.size - 1) / self.size) as u8
+    }
+
+    /// Share of the stack that was never used, in percent
+    pub fn free_percent(&self) -> u8 {
+        100 - self.used_percent()
+    }
+}
+
+/// Tracker of the lowest free stack space observed of a thread
+///
+/// The free stack space reported in [StackStats] is already the lowest since the thread was
+/// created (as it is measured by how much of the stack was ever overwritten). This tracker adds
+/// to that by reporting when that value changed, so that reports only need to be made when a
+/// thread reaches a new peak:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::thread::{get_pid, StackWatermark};
+/// let mut watermark = StackWatermark::new(get_pid());
+/// // after some work
+/// if let Ok(Some(free)) = watermark.update() {
+///     riot_wrappers::println!("New stack peak: {} bytes left", free);
+/// }
+/// ```
+///
+/// If the thread ends and its PID is reused, the tracker keeps the lowest value across both.
+#[derive(Debug, Copy, Clone)]
+pub struct StackWatermark {
+    pid: super::KernelPID,
+    min_free: Option<usize>,
+}
+
+impl StackWatermark {
+    /// Start tracking a thread
+    pub const fn new(pid: super::KernelPID) -> Self {
+        StackWatermark {
+            pid,
+            min_free: None,
+        }
+    }
+
+    /// Measure the thread's stack, returning the free space if it is lower than at any previous
+    /// call
+    pub fn update(&mut self) -> Result<Option<usize>, StackStatsError> {
+        let free = self.pid.stack_stats()?.free();
+        match self.min_free {
+            Some(min_free) if min_free <= free => Ok(None),
+            _ => {
+                self.min_free = Some(free);
+                Ok(Some(free))
+            }
+        }
+    }
+
+    /// Lowest free stack space observed in any call to [update](Self::update)
+    pub fn min_free(&self) -> Option<usize> {
+        self.min_free
+    }
+
+    /// The tracked thread
+    pub fn pid(&self) -> super::KernelPID {
+        self.pid
+    }
 }
 
 #[non_exhaustive]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum StackStatsError {
     /// Requested PID does not correspond to a thread
     NoSuchThread,
@@ -47,3 +121,162 @@ impl From<super::NoSuchThread> for StackStatsError {
         StackStatsError::NoSuchThread
     }
 }
+
+/// Checker that reports threads whose free stack space dropped below a threshold
+///
+/// As RIOT does not report stack usage by itself, the checker needs to be run periodically, eg.
+/// from a low priority thread:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::thread::StackChecker;
+/// let mut checker = StackChecker::new(128);
+/// let clock = riot_wrappers::ztimer::Clock::msec();
+/// loop {
+///     checker.check_and_log();
+///     clock.sleep_ticks(5000);
+/// }
+/// ```
+///
+/// Each thread is reported only once per time its free stack drops below the threshold, so that
+/// a thread hovering at its limit does not flood the hook. Scanning stacks takes time
+/// proportional to the unused stack sizes, so this should not run in time critical threads.
+pub struct StackChecker {
+    threshold: usize,
+    /// Bit per PID (counted from KERNEL_PID_FIRST) that was reported and did not recover since
+    reported: u64,
+}
+
+impl StackChecker {
+    /// Create a checker that reports threads with fewer than `threshold` bytes of stack left
+    pub const fn new(threshold: usize) -> Self {
+        StackChecker {
+            threshold,
+            reported: 0,
+        }
+    }
+
+    /// Check all threads, calling the hook with every thread whose free stack is newly below
+    /// the threshold
+    ///
+    /// Without develhelp, no information is available and the hook is never called.
+    pub fn check(&mut self, hook: impl FnMut(super::KernelPID, &StackStats)) {
+        self.check_matching(|_| true, hook)
+    }
+
+    /// Like [check](Self::check), but only for threads selected by the filter
+    fn check_matching(
+        &mut self,
+        filter: impl Fn(super::KernelPID) -> bool,
+        mut hook: impl FnMut(super::KernelPID, &StackStats),
+    ) {
+        for (i, pid) in super::KernelPID::all_pids().enumerate() {
+            if !filter(pid) {
+                continue;
+            }
+            // Threads beyond what fits in the bit field are not deduplicated.
+            let bit = 1u64.checked_shl(i as u32).unwrap_or(0);
+            let stats = match pid.stack_stats() {
+                Ok(stats) => stats,
+                Err(_) => {
+                    self.reported &= !bit;
+                    continue;
+                }
+            };
+            if stats.free() < self.threshold {
+                if self.reported & bit == 0 || bit == 0 {
+                    hook(pid, &stats);
+                }
+                self.reported |= bit;
+            } else {
+                self.reported &= !bit;
+            }
+        }
+    }
+
+    /// Check all threads, printing a warning for every thread whose free stack is newly below
+    /// the threshold
+    pub fn check_and_log(&mut self) {
+        let threshold = self.threshold;
+        self.check(|pid, stats| {
+            crate::println!(
+                "Warning: thread {} ({}) has {} bytes of stack left (threshold {})",
+                pid.0,
+                pid.get_name().unwrap_or("unnamed"),
+                stats.free(),
+                threshold
+            );
+        });
+    }
+}
+
+/// Periodic watch over the free stack of a set of threads
+///
+/// This builds on [StackChecker] to provide a task that can be left running for the lifetime of
+/// a long-running deployment, eg. in a dedicated low priority thread:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::thread::{KernelPID, StackMonitor};
+/// # use riot_wrappers::ztimer::Ticks;
+/// # let (network, sensors): (KernelPID, KernelPID) = unimplemented!();
+/// let watched = [network, sensors];
+/// let mut monitor = StackMonitor::for_threads(&watched, 256);
+/// monitor.run(Ticks(60_000), |pid, stats| {
+///     riot_wrappers::println!("{:?} is low on stack: {} bytes left", pid, stats.free());
+/// });
+/// ```
+///
+/// As with the [StackChecker], the callback is called once every time a thread's free stack
+/// drops below the threshold, and only with develhelp enabled.
+pub struct StackMonitor<'a> {
+    checker: StackChecker,
+    /// Threads to watch, or None for all
+    pids: Option<&'a [super::KernelPID]>,
+}
+
+impl StackMonitor<'static> {
+    /// Create a monitor for all threads that reports threads with fewer than `threshold` bytes
+    /// of stack left
+    pub const fn new(threshold: usize) -> Self {
+        StackMonitor {
+            checker: StackChecker::new(threshold),
+            pids: None,
+        }
+    }
+}
+
+impl<'a> StackMonitor<'a> {
+    /// Create a monitor for the given threads that reports them when they have fewer than
+    /// `threshold` bytes of stack left
+    pub const fn for_threads(pids: &'a [super::KernelPID], threshold: usize) -> Self {
+        StackMonitor {
+            checker: StackChecker::new(threshold),
+            pids: Some(pids),
+        }
+    }
+
+    /// Sample the watched threads once, calling the hook for every thread whose free stack is
+    /// newly below the threshold
+    pub fn poll(&mut self, hook: impl FnMut(super::KernelPID, &StackStats)) {
+        let pids = self.pids;
+        self.checker
+            .check_matching(|pid| pids.map(|p| p.contains(&pid)).unwrap_or(true), hook)
+    }
+
+    /// Sample the watched threads at the given interval forever
+    ///
+    /// This function is only available if the ztimer_msec module is built.
+    #[cfg(riot_module_ztimer_msec)]
+    pub fn run(
+        &mut self,
+        interval: crate::ztimer::Ticks<1000>,
+        mut hook: impl FnMut(super::KernelPID, &StackStats),
+    ) -> ! {
+        let clock = crate::ztimer::Clock::msec();
+        loop {
+            self.poll(&mut hook);
+            clock.sleep_ticks(interval.0);
+        }
+    }
+}
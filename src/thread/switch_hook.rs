@@ -0,0 +1,81 @@
+//! Hook into the scheduler's context switches through the `sched_cb` module
+//!
+//! RIOT has a single scheduler callback; it is owned by this module, which dispatches to the
+//! application's [SwitchHook] and to the [idle hooks](crate::pm::set_idle_hooks).
+
+use super::KernelPID;
+use crate::interrupt::{CriticalSection, IsrCell};
+
+/// Callback for context switches, see [set_switch_hook]
+pub trait SwitchHook: Send {
+    /// Called when the scheduler switches from the thread `from` to the thread `to`
+    ///
+    /// `from` is None when the previous thread has just terminated (or, at startup, when there
+    /// was no previous thread).
+    ///
+    /// This is called from the scheduler with interrupts disabled, and thus needs to be short and
+    /// must not block.
+    fn switched(&mut self, from: Option<KernelPID>, to: KernelPID);
+}
+
+static SWITCH_HOOK: IsrCell<&'static mut dyn SwitchHook> = IsrCell::new();
+
+unsafe extern "C" fn sched_callback(active: riot_sys::kernel_pid_t, next: riot_sys::kernel_pid_t) {
+    // unsafe: The scheduler runs its callbacks with interrupts disabled
+    let cs = CriticalSection::new();
+    #[cfg(riot_module_core_idle_thread)]
+    crate::pm::on_switch(&cs, active, next);
+    SWITCH_HOOK.with_cs(&cs, |hook| {
+        if let Some(to) = KernelPID::new(next) {
+            hook.switched(KernelPID::new(active), to);
+        }
+    });
+}
+
+/// Make sure the scheduler calls this module's callback
+///
+/// This is idempotent, and used by all users of the callback.
+pub(crate) fn install() {
+    // unsafe: OK per C API
+    unsafe { riot_sys::sched_register_cb(Some(sched_callback)) };
+}
+
+/// Error returned by [set_switch_hook]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SetSwitchHookError {
+    /// A hook was already set
+    AlreadySet,
+}
+
+/// Install a hook that is called on every context switch
+///
+/// This can be used for tracing which thread runs when, or for attributing power consumption to
+/// threads:
+///
+/// ```no_run
+/// # #![no_std]
+/// # use riot_wrappers::thread::{set_switch_hook, KernelPID, SwitchHook};
+/// struct SwitchCounter(u32);
+/// impl SwitchHook for SwitchCounter {
+///     fn switched(&mut self, _from: Option<KernelPID>, _to: KernelPID) {
+///         self.0 += 1;
+///     }
+/// }
+/// static mut COUNTER: SwitchCounter = SwitchCounter(0);
+/// // unsafe: COUNTER is not accessed anywhere else
+/// set_switch_hook(unsafe { &mut COUNTER }).unwrap();
+/// ```
+///
+/// The hook can only be set once. It coexists with the [idle hooks](crate::pm::set_idle_hooks),
+/// but not with C code that registers its own scheduler callback.
+///
+/// This function is only available if the sched_cb module is built.
+#[doc(alias = "sched_register_cb")]
+pub fn set_switch_hook(hook: &'static mut dyn SwitchHook) -> Result<(), SetSwitchHookError> {
+    SWITCH_HOOK
+        .init(hook)
+        .map_err(|_| SetSwitchHookError::AlreadySet)?;
+    install();
+    Ok(())
+}
@@ -137,7 +137,9 @@ impl<const MS: bool, const FS: bool> TokenParts<MS, true, FS> {
     /// # use riot_wrappers::thread::*;
     /// fn thread(tok: StartToken) -> EndToken {
     ///     tok.with_message_queue::<4, _>(|tok| {
+    ///         let queue = tok.message_queue();
     ///         loop {
+    ///             let msg = queue.receive();
     ///             // ...
     ///         }
     ///     })
@@ -169,6 +171,19 @@ impl<const MS: bool, const FS: bool> TokenParts<MS, true, FS> {
     }
 }
 
+impl<const MS: bool, const FS: bool> TokenParts<MS, false, FS> {
+    /// Access the message queue set up in [TokenParts::with_message_queue()]
+    ///
+    /// The receiver borrows the token, ensuring that it is only used in the thread that owns the
+    /// queue, and only while the queue is set up.
+    #[cfg(riot_module_core_msg)]
+    pub fn message_queue(&self) -> crate::msg::QueueReceiver<'_> {
+        // unsafe: A `false` MSG_QUEUE is only produced by with_message_queue, and that never
+        // returns.
+        unsafe { crate::msg::QueueReceiver::new() }
+    }
+}
+
 impl<const MQ: bool> TokenParts<true, MQ, true> {
     /// Certify that nothing has been done in this thread that precludes the termination of the
     /// thread
@@ -241,6 +256,22 @@ impl InIsr {
     }
 
     /// Check that the code is running in IRQ mode
+    ///
+    /// As the error carries the opposite token, this is also the way for library code to pick
+    /// between the thread and the interrupt variant of a RIOT call at runtime:
+    ///
+    /// ```no_run
+    /// # #![no_std]
+    /// # use riot_wrappers::thread::{InIsr, KernelPID};
+    /// # let pid: KernelPID = unimplemented!();
+    /// let result = match InIsr::new() {
+    ///     Ok(in_isr) => pid.try_send_msg_from_isr(in_isr, 0x1234, 42),
+    ///     Err(_in_thread) => pid.try_send_msg(0x1234, 42),
+    /// };
+    /// ```
+    ///
+    /// This is the safe equivalent of C's `irq_is_in()`.
+    #[doc(alias = "irq_is_in")]
     pub fn new() -> Result<Self, InThread> {
         match InThread::new() {
             Ok(i) => Err(i),
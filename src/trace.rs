@@ -0,0 +1,46 @@
+//! Access to RIOT's [execution tracing](https://doc.riot-os.org/group__sys__trace.html) facility
+//!
+//! The trace module records 32-bit values along with a timestamp in a static ring buffer. Entries
+//! are cheap to record (the C implementation merely disables interrupts for the duration of a
+//! store), and can be recorded from threads and interrupts alike. Both C and Rust code record into
+//! the same buffer, which makes this a useful tool when timing problems span both languages.
+//!
+//! The buffer is printed to stdio using [`dump()`], typically from a shell command or at the end
+//! of a test run.
+//!
+//! The values carry no semantics of their own; it is up to the application to pick numbers that
+//! are meaningful in its context (eg. by using a different high byte for each component, and
+//! encoding details in the lower bytes).
+
+/// Record a value into the trace buffer
+///
+/// This is safe to call from any context, including interrupts.
+#[doc(alias = "trace")]
+pub fn trace(value: u32) {
+    // unsafe: C function is documented to be callable from any context
+    unsafe { riot_sys::trace(value) }
+}
+
+/// Record `start`, run the closure, and record `end` after its completion.
+///
+/// This is a convenience for the common case of measuring how long a section of code takes.
+pub fn traced<R>(start: u32, end: u32, f: impl FnOnce() -> R) -> R {
+    trace(start);
+    let result = f();
+    trace(end);
+    result
+}
+
+/// Print all recorded entries (with their time stamps and deltas) on stdio
+#[doc(alias = "trace_dump")]
+pub fn dump() {
+    // unsafe: C function has no preconditions
+    unsafe { riot_sys::trace_dump() }
+}
+
+/// Clear the trace buffer
+#[doc(alias = "trace_reset")]
+pub fn reset() {
+    // unsafe: C function has no preconditions
+    unsafe { riot_sys::trace_reset() }
+}
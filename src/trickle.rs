@@ -0,0 +1,160 @@
+//! # [Trickle timers](https://doc.riot-os.org/group__sys__trickle.html)
+//!
+//! The Trickle algorithm ([RFC 6206](https://www.rfc-editor.org/rfc/rfc6206)) lets nodes
+//! disseminate state with little overhead: A node transmits once in each interval unless it heard
+//! enough consistent transmissions from its neighbors, and intervals grow while the network is
+//! consistent and shrink when an inconsistency is detected.
+//!
+//! RIOT's trickle implementation drives its timing through messages sent to a thread; that thread
+//! needs to call [Trickle::handle_message] whenever it receives a message of the type given at
+//! [start](Trickle::start). The [Handler] is then called from there, ie. in that thread.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::trickle::{Handler, Trickle};
+//! struct Announce;
+//! impl Handler for Announce {
+//!     fn transmit(&mut self) {
+//!         // send the current state to the neighbors
+//!     }
+//! }
+//!
+//! const TRICKLE_MSG: u16 = 0x3f00;
+//! let trickle = Trickle::new(Announce);
+//! pin_utils::pin_mut!(trickle);
+//! trickle.start(TRICKLE_MSG, 100, 16, 3);
+//! loop {
+//!     let msg: riot_sys::msg_t = unimplemented!("receive a message");
+//!     if msg.type_ == TRICKLE_MSG {
+//!         trickle.handle_message();
+//!     }
+//!     // When a consistent transmission is received: trickle.increment_counter()
+//!     // When an inconsistency is detected: trickle.reset()
+//! }
+//! ```
+
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+
+/// Callbacks of a [Trickle] timer
+pub trait Handler {
+    /// The node should transmit its state now
+    ///
+    /// This is only called if fewer than the redundancy constant's number of consistent
+    /// transmissions were heard in the current interval.
+    fn transmit(&mut self);
+
+    /// A new interval of the given length (in milliseconds) has started
+    ///
+    /// This is informational; the default implementation does nothing.
+    fn new_interval(&mut self, interval_ms: u32) {
+        let _ = interval_ms;
+    }
+}
+
+/// A trickle timer with a [Handler] that is called on its events
+///
+/// As the C struct is referenced from pending timers, the timer is used in pinned form. It is
+/// stopped when dropped.
+pub struct Trickle<H: Handler> {
+    trickle: riot_sys::trickle_t,
+    handler: H,
+    // From the .start(), trickle has an internal reference to the handler
+    _phantom: PhantomPinned,
+}
+
+impl<H: Handler> Trickle<H> {
+    pub fn new(handler: H) -> Self {
+        Trickle {
+            trickle: Default::default(),
+            handler,
+            _phantom: PhantomPinned,
+        }
+    }
+
+    extern "C" fn callback(arg: *mut riot_sys::libc::c_void) {
+        let handler = unsafe { &mut *(arg as *mut H) };
+        handler.transmit();
+    }
+
+    /// Start the timer
+    ///
+    /// Messages with the given type are sent to the current thread, which needs to pass them on
+    /// to [handle_message](Self::handle_message). The minimum interval `imin` is given in
+    /// milliseconds; `imax` is the number of doublings of that to the maximum interval, and `k`
+    /// is the redundancy constant.
+    #[doc(alias = "trickle_start")]
+    pub fn start(self: &mut Pin<&mut Self>, msg_type: u16, imin: u32, imax: u8, k: u8) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        s.trickle.callback = riot_sys::trickle_callback_t {
+            func: Some(Self::callback),
+            args: &mut s.handler as *mut H as *mut _,
+        };
+        let pid = crate::thread::get_pid();
+        // unsafe: OK per C API; the struct is pinned, and stopped before it is dropped.
+        unsafe { riot_sys::trickle_start(pid.into(), &mut s.trickle, msg_type, imin, imax, k) };
+        s.handler.new_interval(s.trickle.I);
+    }
+
+    /// Process a trickle message that was received by the thread
+    ///
+    /// This calls the handler's [transmit](Handler::transmit) if a transmission is due, and its
+    /// [new_interval](Handler::new_interval) when an interval ended.
+    #[doc(alias = "trickle_callback")]
+    pub fn handle_message(self: &mut Pin<&mut Self>) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        let interval = s.trickle.I;
+        // unsafe: OK per C API; the callback's argument is still valid as s is pinned.
+        unsafe { riot_sys::trickle_callback(&mut s.trickle) };
+        if s.trickle.I != interval {
+            s.handler.new_interval(s.trickle.I);
+        }
+    }
+
+    /// Count a consistent transmission that was heard
+    #[doc(alias = "trickle_increment_counter")]
+    pub fn increment_counter(self: &mut Pin<&mut Self>) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        // unsafe: OK per C API
+        unsafe { riot_sys::trickle_increment_counter(&mut s.trickle) };
+    }
+
+    /// Reset the interval to its minimum, eg. because an inconsistency was detected
+    #[doc(alias = "trickle_reset_timer")]
+    pub fn reset(self: &mut Pin<&mut Self>) {
+        // unsafe: Nothing is moved out of the references
+        let s = unsafe { Pin::into_inner_unchecked(self.as_mut()) };
+        let interval = s.trickle.I;
+        // unsafe: OK per C API
+        unsafe { riot_sys::trickle_reset_timer(&mut s.trickle) };
+        if s.trickle.I != interval {
+            s.handler.new_interval(s.trickle.I);
+        }
+    }
+
+    /// Stop the timer
+    ///
+    /// It can be started again using [start](Self::start).
+    #[doc(alias = "trickle_stop")]
+    pub fn stop(&mut self) {
+        // unsafe: OK per C API; stopping a timer that was never started is harmless as all its
+        // timers are zero-initialized.
+        unsafe { riot_sys::trickle_stop(&mut self.trickle) };
+    }
+
+    /// Access the handler, eg. to update the state it transmits
+    pub fn handler(self: &mut Pin<&mut Self>) -> &mut H {
+        // unsafe: The handler is not structurally pinned; the C side only holds a pointer to it
+        // that is used while the trickle functions are called from here.
+        &mut unsafe { Pin::into_inner_unchecked(self.as_mut()) }.handler
+    }
+}
+
+impl<H: Handler> Drop for Trickle<H> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
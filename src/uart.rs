@@ -0,0 +1,184 @@
+//! Access to [RIOT's UART peripherals](https://doc.riot-os.org/group__drivers__periph__uart.html)
+//!
+//! A [Uart] is set up with an [RxBuffer] into which the receive interrupt stores incoming bytes;
+//! the application then reads them from there at its own pace. Bytes that arrive while the buffer
+//! is full are dropped (and counted, see [Uart::overruns]).
+//!
+//! Writing is blocking, as it is in RIOT.
+//!
+//! With the `with_embedded_io` feature, [Uart] implements the [embedded_io::Write] trait; with
+//! `with_embedded_io_async`, it also implements `embedded_io_async::Read`, with the receive
+//! interrupt waking the reading task.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::uart::{RxBuffer, Uart};
+//! static mut RX: RxBuffer<64> = RxBuffer::new();
+//! // unsafe: This is the only place where RX is accessed
+//! let mut uart = Uart::new(0, 115200, unsafe { &mut RX }).unwrap();
+//! uart.write(b"Hello\r\n");
+//! let mut buf = [0; 16];
+//! let n = uart.try_read(&mut buf);
+//! ```
+
+use core::cell::UnsafeCell;
+use core::task::Waker;
+
+use crate::error::{NegativeErrorExt, NumericError};
+
+/// Receive state shared between a [Uart] and its interrupt
+struct RxState<const N: usize> {
+    data: heapless::Deque<u8, N>,
+    overruns: u32,
+    waker: Option<Waker>,
+}
+
+/// Buffer into which a [Uart]'s receive interrupt stores incoming data
+///
+/// This is typically placed in a static, as the UART keeps using it for its whole lifetime.
+pub struct RxBuffer<const N: usize>(UnsafeCell<RxState<N>>);
+
+impl<const N: usize> RxBuffer<N> {
+    pub const fn new() -> Self {
+        RxBuffer(UnsafeCell::new(RxState {
+            data: heapless::Deque::new(),
+            overruns: 0,
+            waker: None,
+        }))
+    }
+}
+
+/// An initialized UART device with an attached receive buffer
+pub struct Uart<const N: usize> {
+    dev: riot_sys::uart_t,
+    // A 'static mut reference whose exclusivity we ensure by only accessing it in critical
+    // sections (in the thread) or in the interrupt.
+    rx: *mut RxState<N>,
+}
+
+extern "C" fn rx_callback<const N: usize>(arg: *mut riot_sys::libc::c_void, data: u8) {
+    // unsafe: Set up in Uart::new to point to the RxState. We're in the ISR, so the thread is not
+    // accessing it concurrently.
+    let state = unsafe { &mut *(arg as *mut RxState<N>) };
+    if state.data.push_back(data).is_err() {
+        state.overruns = state.overruns.wrapping_add(1);
+    }
+    if let Some(waker) = state.waker.take() {
+        waker.wake();
+    }
+}
+
+impl<const N: usize> Uart<N> {
+    /// Initialize the UART device with the given index (as in `UART_DEV(index)`) at the given
+    /// baud rate, storing received data in the buffer.
+    #[doc(alias = "uart_init")]
+    pub fn new(
+        index: usize,
+        baudrate: u32,
+        rx: &'static mut RxBuffer<N>,
+    ) -> Result<Self, NumericError> {
+        // unsafe: Side effect free C macro
+        let dev = unsafe { riot_sys::macro_UART_DEV(index as _) };
+        let rx = rx.0.get();
+        // unsafe: OK per C API; the argument is valid for 'static.
+        unsafe { riot_sys::uart_init(dev, baudrate, Some(rx_callback::<N>), rx as *mut _) }
+            .negative_to_error()?;
+        Ok(Uart { dev, rx })
+    }
+
+    /// Write all of the data, blocking until it is sent
+    #[doc(alias = "uart_write")]
+    pub fn write(&mut self, data: &[u8]) {
+        // unsafe: OK per C API
+        unsafe { riot_sys::uart_write(self.dev, data.as_ptr(), data.len() as _) };
+    }
+
+    /// Run a closure on the receive state with the receive interrupt locked out
+    fn with_rx<R>(&mut self, f: impl FnOnce(&mut RxState<N>) -> R) -> R {
+        // unsafe: The interrupt does not run while interrupts are disabled, and self is &mut.
+        crate::interrupt::free(|_| f(unsafe { &mut *self.rx }))
+    }
+
+    /// Read as many bytes as are available (and fit into `buf`), returning their number
+    ///
+    /// This does not block; it returns 0 if no data is available.
+    pub fn try_read(&mut self, buf: &mut [u8]) -> usize {
+        self.with_rx(|rx| {
+            let mut count = 0;
+            for slot in buf.iter_mut() {
+                match rx.data.pop_front() {
+                    Some(byte) => *slot = byte,
+                    None => break,
+                }
+                count += 1;
+            }
+            count
+        })
+    }
+
+    /// Number of bytes that were dropped because the receive buffer was full
+    pub fn overruns(&mut self) -> u32 {
+        self.with_rx(|rx| rx.overruns)
+    }
+
+    /// Turn the UART device off
+    #[doc(alias = "uart_poweroff")]
+    pub fn power_off(&mut self) {
+        unsafe { riot_sys::uart_poweroff(self.dev) }
+    }
+
+    /// Turn the UART device back on after [Self::power_off]
+    #[doc(alias = "uart_poweron")]
+    pub fn power_on(&mut self) {
+        unsafe { riot_sys::uart_poweron(self.dev) }
+    }
+}
+
+#[cfg(feature = "with_embedded_io")]
+impl<const N: usize> embedded_io::ErrorType for Uart<N> {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "with_embedded_io")]
+impl<const N: usize> embedded_io::Write for Uart<N> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Uart::write(self, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        // uart_write only returns when all data is out
+        Ok(())
+    }
+}
+
+#[cfg(feature = "with_embedded_io_async")]
+impl<const N: usize> embedded_io_async::Read for Uart<N> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        core::future::poll_fn(|cx| {
+            self.with_rx(|rx| {
+                if rx.data.is_empty() {
+                    rx.waker = Some(cx.waker().clone());
+                    core::task::Poll::Pending
+                } else {
+                    core::task::Poll::Ready(())
+                }
+            })
+        })
+        .await;
+        Ok(self.try_read(buf))
+    }
+}
+
+#[cfg(feature = "with_embedded_io_async")]
+impl<const N: usize> embedded_io_async::Write for Uart<N> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        // There is no non-blocking UART write in RIOT; as UART writes are typically short and
+        // bounded in time, blocking here is acceptable.
+        Uart::write(self, buf);
+        Ok(buf.len())
+    }
+}
@@ -10,7 +10,12 @@
 //!
 //! ## Incomplete
 //!
-//! So far, only a subset of VFS is implemented; in particular, the file system is read-only.
+//! So far, only a subset of VFS is implemented; in particular, files can not be removed or
+//! renamed, and directories can not be created.
+//!
+//! ## Implementing file systems
+//!
+//! File systems can be implemented in Rust and mounted using the [fs] module.
 
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
@@ -18,7 +23,7 @@ use core::mem::MaybeUninit;
 use riot_sys::libc;
 
 use crate::error::{NegativeErrorExt, NumericError};
-use crate::helpers::{PointerToCStr, SliceToCStr};
+use crate::cstr_helpers::{PointerToCStr, SliceToCStr};
 
 /// A file handle
 #[derive(Debug)]
@@ -71,6 +76,29 @@ impl File {
         })
     }
 
+    /// Open a file for writing, creating it if it does not exist and truncating it if it does.
+    pub fn create(path: &str) -> Result<Self, NumericError> {
+        Self::open_with_flags(
+            path,
+            (riot_sys::O_WRONLY | riot_sys::O_CREAT | riot_sys::O_TRUNC) as _,
+        )
+    }
+
+    /// Open an existing file for writing, without truncating it.
+    pub fn open_for_writing(path: &str) -> Result<Self, NumericError> {
+        Self::open_with_flags(path, riot_sys::O_WRONLY as _)
+    }
+
+    fn open_with_flags(path: &str, flags: libc::c_int) -> Result<Self, NumericError> {
+        let fileno =
+            unsafe { riot_sys::vfs_open(path as *const str as *const libc::c_char, flags, 0o644) }
+                .negative_to_error()?;
+        Ok(File {
+            fileno,
+            _not_send_sync: PhantomData,
+        })
+    }
+
     /// Obtain metadata of the file.
     pub fn stat(&self) -> Result<Stat, NumericError> {
         let mut stat = MaybeUninit::uninit();
@@ -93,6 +121,34 @@ impl File {
         .map(|len| len as _)
     }
 
+    /// Write from the given buffer at the current cursor position in the file, and advance the
+    /// cursor by the written length, which is also returned.
+    ///
+    /// As with POSIX writes, not all of the buffer may be written.
+    pub fn write(&mut self, buf: &[u8]) -> Result<usize, NumericError> {
+        (unsafe {
+            riot_sys::vfs_write(
+                self.fileno,
+                buf.as_ptr() as *const libc::c_void,
+                buf.len() as _,
+            )
+        })
+        .negative_to_error()
+        .map(|len| len as _)
+    }
+
+    /// Write all of the given buffer, repeating partial writes as necessary.
+    pub fn write_all(&mut self, mut buf: &[u8]) -> Result<(), NumericError> {
+        while !buf.is_empty() {
+            let written = self.write(buf)?;
+            if written == 0 {
+                return Err(crate::error::Errno::Io.into());
+            }
+            buf = &buf[written..];
+        }
+        Ok(())
+    }
+
     /// Move the file cursor to the indicated position.
     pub fn seek(&mut self, pos: SeekFrom) -> Result<usize, NumericError> {
         let (off, whence) = match pos {
@@ -266,3 +322,8 @@ impl<'a> Mount<'a> {
             .expect("Mount point not UTF-8 encoded")
     }
 }
+
+pub mod fs;
+
+#[cfg(feature = "with_coap_handler")]
+pub mod coap;
@@ -0,0 +1,257 @@
+//! A CoAP resource that serves and accepts files from a VFS directory
+//!
+//! [FileResource] is a [coap_handler::Handler] that maps the Uri-Path of a request to a file in a
+//! configured directory. GET reads the file (block-wise using Block2), and PUT (block-wise using
+//! Block1) replaces it. Unlike RIOT's `gcoap_fileserver` module, this works with any CoAP
+//! implementation that can run a coap_handler, and accepts writes, which makes it useful for
+//! pushing configuration files onto devices.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::gcoap::{self, SingleHandlerListener};
+//! # use riot_wrappers::coap_handler::GcoapHandler;
+//! use riot_wrappers::vfs::coap::FileResource;
+//!
+//! // Serve /nvm0/config/* as coap://[...]/config/*
+//! let mut handler = GcoapHandler(FileResource::<64>::new("/nvm0/config", 1));
+//! let mut listener = SingleHandlerListener::new(
+//!     cstr::cstr!("/config"),
+//!     riot_sys::COAP_GET | riot_sys::COAP_PUT | riot_sys::COAP_MATCH_SUBTREE,
+//!     &mut handler,
+//! );
+//! gcoap::scope(|greg| {
+//!     greg.register(&mut listener);
+//!     // ...
+//! });
+//! ```
+//!
+//! There is no access control beyond what the CoAP stack provides (eg. through OSCORE); the
+//! resource only ensures that paths do not leave the configured directory.
+
+use core::convert::TryInto;
+
+use coap_message::{MessageOption, MutableWritableMessage, ReadableMessage};
+use coap_numbers::{code, option};
+
+use super::{File, SeekFrom};
+use crate::coap_handler::block::Block;
+
+/// Largest block size exponent (SZX) used in responses, corresponding to 1024 byte blocks
+const MAX_SZX: u8 = 6;
+
+/// Request data of a [FileResource]: What is to be sent in the response
+pub struct RequestData<const PATH_MAX: usize>(Result<Action<PATH_MAX>, u8>);
+
+enum Action<const PATH_MAX: usize> {
+    Read {
+        path: heapless::String<PATH_MAX>,
+        block2: Block,
+    },
+    Written {
+        block1: Option<Block>,
+    },
+}
+
+/// A [coap_handler::Handler] that maps requests to files in a VFS directory
+///
+/// `PATH_MAX` is the longest file system path (including the directory and the terminating null
+/// byte) that is accessed; requests for longer paths are rejected with 4.00 Bad Request.
+pub struct FileResource<const PATH_MAX: usize> {
+    directory: &'static str,
+    skip_segments: usize,
+}
+
+impl<const PATH_MAX: usize> FileResource<PATH_MAX> {
+    /// Create a resource that serves files inside `directory`
+    ///
+    /// The first `skip_segments` Uri-Path segments of a request are the path under which the
+    /// resource is registered, and are not considered part of the file name.
+    pub const fn new(directory: &'static str, skip_segments: usize) -> Self {
+        FileResource {
+            directory,
+            skip_segments,
+        }
+    }
+
+    /// Build the null terminated file system path from the request's Uri-Path
+    fn path(&self, request: &impl ReadableMessage) -> Result<heapless::String<PATH_MAX>, u8> {
+        let mut path = heapless::String::new();
+        path.push_str(self.directory.trim_end_matches('/'))
+            .map_err(|_| code::INTERNAL_SERVER_ERROR)?;
+        let mut any = false;
+        for segment in request
+            .options()
+            .filter(|o| o.number() == option::URI_PATH)
+            .skip(self.skip_segments)
+        {
+            let segment = core::str::from_utf8(segment.value()).map_err(|_| code::BAD_REQUEST)?;
+            if segment.is_empty() || segment == "." || segment == ".." || segment.contains('/') {
+                return Err(code::BAD_REQUEST);
+            }
+            path.push('/').map_err(|_| code::BAD_REQUEST)?;
+            path.push_str(segment).map_err(|_| code::BAD_REQUEST)?;
+            any = true;
+        }
+        if !any {
+            return Err(code::NOT_FOUND);
+        }
+        path.push('\0').map_err(|_| code::BAD_REQUEST)?;
+        Ok(path)
+    }
+
+    fn block(request: &impl ReadableMessage, number: u16) -> Result<Option<Block>, u8> {
+        match request.options().find(|o| o.number() == number) {
+            None => Ok(None),
+            Some(o) => Block::parse(o.value()).map(Some).ok_or(code::BAD_REQUEST),
+        }
+    }
+
+    fn write(path: &str, block1: Option<Block>, payload: &[u8]) -> Result<(), u8> {
+        let offset = block1.map(|b| b.offset()).unwrap_or(0);
+        let mut file = if offset == 0 {
+            File::create(path)
+        } else {
+            File::open_for_writing(path)
+        }
+        .map_err(|_| code::INTERNAL_SERVER_ERROR)?;
+        if offset != 0 {
+            file.seek(SeekFrom::Start(offset))
+                .map_err(|_| code::REQUEST_ENTITY_INCOMPLETE)?;
+        }
+        file.write_all(payload)
+            .map_err(|_| code::REQUEST_ENTITY_TOO_LARGE)
+    }
+
+    fn extract(&mut self, request: &impl ReadableMessage) -> Result<Action<PATH_MAX>, u8> {
+        let path = self.path(request)?;
+        let method: u8 = request.code().into();
+        match method {
+            code::GET => {
+                let block2 = Self::block(request, option::BLOCK2)?.unwrap_or(Block {
+                    num: 0,
+                    more: false,
+                    szx: MAX_SZX,
+                });
+                Ok(Action::Read { path, block2 })
+            }
+            code::PUT => {
+                let block1 = Self::block(request, option::BLOCK1)?;
+                Self::write(&path, block1, request.payload())?;
+                Ok(Action::Written { block1 })
+            }
+            _ => Err(code::METHOD_NOT_ALLOWED),
+        }
+    }
+
+    fn set_code(response: &mut impl MutableWritableMessage, code: u8) {
+        response.set_code(
+            code.try_into()
+                .map_err(|_| "Message type can't express response code")
+                .unwrap(),
+        );
+    }
+
+    fn add_option(response: &mut impl MutableWritableMessage, number: u16, value: &[u8]) {
+        response.add_option(
+            number
+                .try_into()
+                .map_err(|_| "Message type can't express option")
+                .unwrap(),
+            value,
+        );
+    }
+
+    fn read(
+        response: &mut impl MutableWritableMessage,
+        path: &str,
+        mut block2: Block,
+    ) -> Result<(), u8> {
+        let mut file = File::open(path).map_err(|_| code::NOT_FOUND)?;
+        let filesize = file.stat().map_err(|_| code::INTERNAL_SERVER_ERROR)?.size();
+
+        // Shrink the block size to what fits in the response, keeping the offset
+        let offset = block2.offset();
+        let space = response.available_space().saturating_sub(8);
+        while block2.size() > space || block2.szx > MAX_SZX {
+            if block2.szx == 0 {
+                return Err(code::INTERNAL_SERVER_ERROR);
+            }
+            block2.szx -= 1;
+            block2.num *= 2;
+        }
+        if offset > filesize || (offset == filesize && offset != 0) {
+            return Err(code::BAD_OPTION);
+        }
+        let len = block2.size().min(filesize - offset);
+        block2.more = offset + len < filesize;
+
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|_| code::INTERNAL_SERVER_ERROR)?;
+
+        Self::set_code(response, code::CONTENT);
+        let (value, value_len) = block2.encode();
+        Self::add_option(response, option::BLOCK2, &value[..value_len]);
+        if block2.num == 0 {
+            let size2 = (filesize as u32).to_be_bytes();
+            Self::add_option(response, option::SIZE2, &size2);
+        }
+
+        let payload = &mut response.payload_mut()[..len];
+        let mut read = 0;
+        while read < len {
+            match file.read(&mut payload[read..]) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => read += n,
+            }
+        }
+        response.truncate(read);
+        Ok(())
+    }
+}
+
+impl<const PATH_MAX: usize> coap_handler::Handler for FileResource<PATH_MAX> {
+    type RequestData = RequestData<PATH_MAX>;
+
+    fn extract_request_data(&mut self, request: &impl ReadableMessage) -> Self::RequestData {
+        RequestData(self.extract(request))
+    }
+
+    fn estimate_length(&mut self, request: &Self::RequestData) -> usize {
+        match &request.0 {
+            Ok(Action::Read { block2, .. }) => block2.size() + 16,
+            _ => 8,
+        }
+    }
+
+    fn build_response(
+        &mut self,
+        response: &mut impl MutableWritableMessage,
+        request: Self::RequestData,
+    ) {
+        let result = match request.0 {
+            Ok(Action::Read { path, block2 }) => Self::read(response, &path, block2),
+            Ok(Action::Written { block1 }) => {
+                match block1 {
+                    Some(block1) if block1.more => {
+                        Self::set_code(response, code::CONTINUE);
+                        let (value, len) = block1.encode();
+                        Self::add_option(response, option::BLOCK1, &value[..len]);
+                    }
+                    Some(block1) => {
+                        Self::set_code(response, code::CHANGED);
+                        let (value, len) = block1.encode();
+                        Self::add_option(response, option::BLOCK1, &value[..len]);
+                    }
+                    None => Self::set_code(response, code::CHANGED),
+                }
+                response.set_payload(b"");
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(code) = result {
+            Self::set_code(response, code);
+            response.set_payload(b"");
+        }
+    }
+}
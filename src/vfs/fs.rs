@@ -0,0 +1,399 @@
+//! Implementing file systems in Rust
+//!
+//! A type that implements [FileSystem] can be mounted into the VFS through a [Mountable]; its
+//! files are then accessible to all VFS users, be it [File](super::File), the `vfs` shell command
+//! or C libraries.
+//!
+//! The trait is deliberately simple: Open files and directories are represented by a `usize`
+//! handle of the file system's choosing (eg. an index into a table), and file positions are
+//! tracked by the VFS, so reads and writes are always given an explicit offset.
+//!
+//! [StaticFiles] is a ready-made read-only file system for files compiled into the firmware:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::vfs::fs::{Mountable, StaticFiles};
+//! static BUNDLE: Mountable<StaticFiles> = Mountable::new(StaticFiles(&[
+//!     ("/index.html", b"<h1>Hello</h1>"),
+//!     ("/version", b"1.0"),
+//! ]));
+//! BUNDLE.mount(cstr::cstr!("/res")).unwrap();
+//! ```
+
+use core::cell::{Cell, UnsafeCell};
+use core::ffi::CStr;
+use core::mem::MaybeUninit;
+
+use riot_sys::libc;
+
+use crate::error::{Errno, NumericError};
+
+/// A file system implemented in Rust
+///
+/// All paths are relative to the mount point, and start with a slash.
+///
+/// Errors are reported to VFS users as they are; implementations should use the error numbers a
+/// POSIX file system would use (eg. `ENOENT` for missing files).
+pub trait FileSystem: Sync {
+    /// Open the file at `path` with the given `O_*` flags, returning a handle to it
+    fn open(&self, path: &str, flags: i32) -> Result<usize, NumericError>;
+
+    /// Release a handle obtained from [.open()](FileSystem::open)
+    fn close(&self, _handle: usize) {}
+
+    /// Read from the file at the given offset, returning the number of bytes read (0 at the end
+    /// of the file)
+    fn read(&self, handle: usize, offset: usize, buf: &mut [u8]) -> Result<usize, NumericError>;
+
+    /// Write to the file at the given offset, returning the number of bytes written
+    ///
+    /// The default implementation refuses any writes.
+    fn write(&self, _handle: usize, _offset: usize, _data: &[u8]) -> Result<usize, NumericError> {
+        Err(Errno::RoFs.into())
+    }
+
+    /// The current size of the file
+    fn size(&self, handle: usize) -> Result<usize, NumericError>;
+
+    /// Open the directory at `path` for listing, returning a cursor into it
+    ///
+    /// The default implementation has no directories.
+    fn open_dir(&self, _path: &str) -> Result<usize, NumericError> {
+        Err(Errno::NotDir.into())
+    }
+
+    /// Write the name of the entry at the cursor into `name` and advance the cursor
+    ///
+    /// Returns the length of the name, or None after the last entry.
+    fn read_dir(
+        &self,
+        _cursor: &mut usize,
+        _name: &mut [u8],
+    ) -> Result<Option<usize>, NumericError> {
+        Ok(None)
+    }
+
+    /// Release a cursor obtained from [.open_dir()](FileSystem::open_dir)
+    fn close_dir(&self, _cursor: usize) {}
+}
+
+/// The C structures through which VFS accesses a Rust file system
+struct Tables {
+    file_ops: riot_sys::vfs_file_ops_t,
+    dir_ops: riot_sys::vfs_dir_ops_t,
+    fs_ops: riot_sys::vfs_file_system_ops_t,
+    fs: riot_sys::vfs_file_system_t,
+    mount: riot_sys::vfs_mount_t,
+}
+
+/// A [FileSystem] along with the memory VFS needs to mount it
+///
+/// This is typically placed in a static, as file systems can not be unmounted.
+pub struct Mountable<F: FileSystem> {
+    fs: F,
+    // Only accessed in mount; after that, owned by VFS.
+    tables: UnsafeCell<MaybeUninit<Tables>>,
+    // Only accessed with interrupts disabled
+    mounted: Cell<bool>,
+}
+
+// The tables are only written once, guarded by `mounted`, and the file system is Sync.
+unsafe impl<F: FileSystem> Sync for Mountable<F> {}
+
+impl<F: FileSystem> Mountable<F> {
+    pub const fn new(fs: F) -> Self {
+        Mountable {
+            fs,
+            tables: UnsafeCell::new(MaybeUninit::uninit()),
+            mounted: Cell::new(false),
+        }
+    }
+
+    /// Access the file system, eg. to change files of a RAM file system from Rust
+    pub fn fs(&self) -> &F {
+        &self.fs
+    }
+
+    /// Mount the file system at the given location
+    ///
+    /// Fails with `EBUSY` if this file system or another one is already mounted there.
+    #[doc(alias = "vfs_mount")]
+    pub fn mount(&'static self, mount_point: &'static CStr) -> Result<(), NumericError> {
+        let was_mounted = crate::interrupt::free(|_| self.mounted.replace(true));
+        if was_mounted {
+            return Err(Errno::Busy.into());
+        }
+
+        // unsafe: The mounted flag ensures we're the only ones ever writing here.
+        let tables = unsafe { &mut *self.tables.get() }.write(Tables {
+            file_ops: riot_sys::vfs_file_ops_t {
+                open: Some(open::<F>),
+                close: Some(close::<F>),
+                read: Some(read::<F>),
+                write: Some(write::<F>),
+                lseek: Some(lseek::<F>),
+                fstat: Some(fstat::<F>),
+                ..Default::default()
+            },
+            dir_ops: riot_sys::vfs_dir_ops_t {
+                opendir: Some(opendir::<F>),
+                readdir: Some(readdir::<F>),
+                closedir: Some(closedir::<F>),
+                ..Default::default()
+            },
+            fs_ops: riot_sys::vfs_file_system_ops_t {
+                stat: Some(stat::<F>),
+                ..Default::default()
+            },
+            fs: Default::default(),
+            mount: Default::default(),
+        });
+        tables.fs.f_op = &tables.file_ops;
+        tables.fs.d_op = &tables.dir_ops;
+        tables.fs.fs_op = &tables.fs_ops;
+        tables.mount.fs = &tables.fs;
+        tables.mount.mount_point = mount_point.as_ptr();
+        tables.mount.mount_point_len = mount_point.to_bytes().len() as _;
+        tables.mount.private_data = &self.fs as *const F as *mut libc::c_void;
+
+        // unsafe: OK per C API; the mount structure is 'static.
+        let result = unsafe { riot_sys::vfs_mount(&mut tables.mount) };
+        if result < 0 {
+            // The tables were not taken up by VFS, and may be written again.
+            crate::interrupt::free(|_| self.mounted.set(false));
+            return Err(NumericError::from_constant(-result as _));
+        }
+        Ok(())
+    }
+}
+
+/// Obtain the file system a mount point was created for
+///
+/// unsafe: Only to be called on mount points created in [Mountable::mount] for the same F.
+unsafe fn fs<'a, F: FileSystem>(mp: *const riot_sys::vfs_mount_t) -> &'a F {
+    &*((*mp).private_data as *const F)
+}
+
+/// Convert a path passed in by VFS
+///
+/// unsafe: Only to be called with valid C strings that outlive 'a.
+unsafe fn path<'a>(path: *const libc::c_char) -> Result<&'a str, NumericError> {
+    CStr::from_ptr(path)
+        .to_str()
+        // Non-UTF-8 names can't be in a Rust file system
+        .map_err(|_| Errno::NoEnt.into())
+}
+
+fn result_to_c(result: Result<isize, NumericError>) -> isize {
+    match result {
+        Ok(v) => v,
+        Err(e) => e.number,
+    }
+}
+
+unsafe extern "C" fn open<F: FileSystem>(
+    filp: *mut riot_sys::vfs_file_t,
+    name: *const libc::c_char,
+    flags: libc::c_int,
+    _mode: riot_sys::mode_t,
+) -> libc::c_int {
+    let fs = fs::<F>((*filp).mp);
+    let result = path(name).and_then(|name| fs.open(name, flags as _));
+    result_to_c(result.map(|handle| {
+        (*filp).private_data.ptr = handle as *mut _;
+        0isize
+    })) as _
+}
+
+unsafe extern "C" fn close<F: FileSystem>(filp: *mut riot_sys::vfs_file_t) -> libc::c_int {
+    fs::<F>((*filp).mp).close((*filp).private_data.ptr as usize);
+    0
+}
+
+unsafe extern "C" fn read<F: FileSystem>(
+    filp: *mut riot_sys::vfs_file_t,
+    dest: *mut libc::c_void,
+    nbytes: riot_sys::size_t,
+) -> riot_sys::ssize_t {
+    let fs = fs::<F>((*filp).mp);
+    let buf = core::slice::from_raw_parts_mut(dest as *mut u8, nbytes as _);
+    let result = fs.read((*filp).private_data.ptr as usize, (*filp).pos as _, buf);
+    result_to_c(result.map(|n| {
+        (*filp).pos += n as riot_sys::off_t;
+        n as isize
+    })) as _
+}
+
+unsafe extern "C" fn write<F: FileSystem>(
+    filp: *mut riot_sys::vfs_file_t,
+    src: *const libc::c_void,
+    nbytes: riot_sys::size_t,
+) -> riot_sys::ssize_t {
+    let fs = fs::<F>((*filp).mp);
+    let data = core::slice::from_raw_parts(src as *const u8, nbytes as _);
+    let result = fs.write((*filp).private_data.ptr as usize, (*filp).pos as _, data);
+    result_to_c(result.map(|n| {
+        (*filp).pos += n as riot_sys::off_t;
+        n as isize
+    })) as _
+}
+
+unsafe extern "C" fn lseek<F: FileSystem>(
+    filp: *mut riot_sys::vfs_file_t,
+    off: riot_sys::off_t,
+    whence: libc::c_int,
+) -> riot_sys::off_t {
+    let base = match whence as _ {
+        riot_sys::SEEK_SET => 0,
+        riot_sys::SEEK_CUR => (*filp).pos,
+        riot_sys::SEEK_END => match fs::<F>((*filp).mp).size((*filp).private_data.ptr as usize) {
+            Ok(size) => size as riot_sys::off_t,
+            Err(e) => return e.number as _,
+        },
+        _ => return -(riot_sys::EINVAL as riot_sys::off_t),
+    };
+    let new = base + off;
+    if new < 0 {
+        return -(riot_sys::EINVAL as riot_sys::off_t);
+    }
+    (*filp).pos = new;
+    new
+}
+
+/// Populate a stat struct for a regular file or directory
+unsafe fn fill_stat(buf: *mut riot_sys::stat, size: usize, is_dir: bool) {
+    core::ptr::write_bytes(buf, 0, 1);
+    (*buf).st_size = size as _;
+    (*buf).st_mode = if is_dir {
+        riot_sys::S_IFDIR | 0o555
+    } else {
+        riot_sys::S_IFREG | 0o444
+    } as _;
+}
+
+unsafe extern "C" fn fstat<F: FileSystem>(
+    filp: *mut riot_sys::vfs_file_t,
+    buf: *mut riot_sys::stat,
+) -> libc::c_int {
+    let size = fs::<F>((*filp).mp).size((*filp).private_data.ptr as usize);
+    result_to_c(size.map(|size| {
+        fill_stat(buf, size, false);
+        0isize
+    })) as _
+}
+
+unsafe extern "C" fn stat<F: FileSystem>(
+    mountp: *mut riot_sys::vfs_mount_t,
+    name: *const libc::c_char,
+    buf: *mut riot_sys::stat,
+) -> libc::c_int {
+    let fs = fs::<F>(mountp);
+    let name = match path(name) {
+        Ok(name) => name,
+        Err(e) => return e.number as _,
+    };
+    if let Ok(cursor) = fs.open_dir(name) {
+        fs.close_dir(cursor);
+        fill_stat(buf, 0, true);
+        return 0;
+    }
+    let handle = match fs.open(name, riot_sys::O_RDONLY as _) {
+        Ok(handle) => handle,
+        Err(e) => return e.number as _,
+    };
+    let size = fs.size(handle);
+    fs.close(handle);
+    result_to_c(size.map(|size| {
+        fill_stat(buf, size, false);
+        0isize
+    })) as _
+}
+
+unsafe extern "C" fn opendir<F: FileSystem>(
+    dirp: *mut riot_sys::vfs_DIR,
+    dirname: *const libc::c_char,
+) -> libc::c_int {
+    let fs = fs::<F>((*dirp).mp);
+    let result = path(dirname).and_then(|name| fs.open_dir(name));
+    result_to_c(result.map(|cursor| {
+        (*dirp).private_data.ptr = cursor as *mut _;
+        0isize
+    })) as _
+}
+
+unsafe extern "C" fn readdir<F: FileSystem>(
+    dirp: *mut riot_sys::vfs_DIR,
+    entry: *mut riot_sys::vfs_dirent_t,
+) -> libc::c_int {
+    let fs = fs::<F>((*dirp).mp);
+    let mut cursor = (*dirp).private_data.ptr as usize;
+    let name = &mut (*entry).d_name;
+    // Leaving space for the terminating null byte
+    let name_buf = core::slice::from_raw_parts_mut(name.as_mut_ptr() as *mut u8, name.len() - 1);
+    let result = fs.read_dir(&mut cursor, name_buf);
+    (*dirp).private_data.ptr = cursor as *mut _;
+    match result {
+        Ok(Some(len)) if len < name.len() => {
+            name[len] = 0;
+            (*entry).d_ino = 0;
+            1
+        }
+        Ok(Some(_)) => NumericError::from(Errno::Overflow).number as _,
+        Ok(None) => 0,
+        Err(e) => e.number as _,
+    }
+}
+
+unsafe extern "C" fn closedir<F: FileSystem>(dirp: *mut riot_sys::vfs_DIR) -> libc::c_int {
+    fs::<F>((*dirp).mp).close_dir((*dirp).private_data.ptr as usize);
+    0
+}
+
+/// A read-only file system of files given as pairs of path and content
+///
+/// All files are in a single directory; the paths need to start with a slash.
+pub struct StaticFiles(pub &'static [(&'static str, &'static [u8])]);
+
+impl FileSystem for StaticFiles {
+    fn open(&self, path: &str, flags: i32) -> Result<usize, NumericError> {
+        if flags & riot_sys::O_ACCMODE as i32 != riot_sys::O_RDONLY as i32 {
+            return Err(Errno::RoFs.into());
+        }
+        self.0
+            .iter()
+            .position(|(name, _)| *name == path)
+            .ok_or(Errno::NoEnt.into())
+    }
+
+    fn read(&self, handle: usize, offset: usize, buf: &mut [u8]) -> Result<usize, NumericError> {
+        let data = self.0[handle].1;
+        let data = data.get(offset..).unwrap_or(&[]);
+        let len = data.len().min(buf.len());
+        buf[..len].copy_from_slice(&data[..len]);
+        Ok(len)
+    }
+
+    fn size(&self, handle: usize) -> Result<usize, NumericError> {
+        Ok(self.0[handle].1.len())
+    }
+
+    fn open_dir(&self, path: &str) -> Result<usize, NumericError> {
+        match path {
+            "/" | "" => Ok(0),
+            _ => Err(Errno::NoEnt.into()),
+        }
+    }
+
+    fn read_dir(&self, cursor: &mut usize, name: &mut [u8]) -> Result<Option<usize>, NumericError> {
+        let path = match self.0.get(*cursor) {
+            Some((path, _)) => path,
+            None => return Ok(None),
+        };
+        *cursor += 1;
+        let path = path.trim_start_matches('/').as_bytes();
+        name.get_mut(..path.len())
+            .ok_or(Errno::Overflow.into())?
+            .copy_from_slice(path);
+        Ok(Some(path.len()))
+    }
+}
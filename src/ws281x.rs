@@ -1,9 +1,22 @@
 //! Tools for using the [WS2812/SK6812 RGB LED
 //! (NeoPixel)](https://doc.riot-os.org/group__drivers__ws281x.html) drivers
+//!
+//! ```no_run
+//! # #![no_std]
+//! # use riot_wrappers::ws281x::{BufferedWs281x, GRB};
+//! # use riot_wrappers::color::Rgb;
+//! # let pin: riot_wrappers::gpio::GPIO = unimplemented!();
+//! let mut strip: BufferedWs281x<GRB, 8> = BufferedWs281x::init(pin);
+//! strip.fill(Rgb::BLACK);
+//! strip.set(0, Rgb::RED);
+//! strip.set(7, Rgb::BLUE.dimmed(64));
+//! strip.write();
+//! ```
 
 use core::convert::TryInto;
 use core::mem::MaybeUninit;
 
+use crate::color::Rgb;
 use crate::error::NegativeErrorExt;
 
 /// A WS281x chain backed by an owned buffer of compatible LEDs
@@ -45,7 +58,35 @@ impl<C: ChannelType + Default + Copy, const N: usize> BufferedWs281x<C, N> {
     }
 }
 
+impl<C: ColorChannels, const N: usize> BufferedWs281x<C, N> {
+    /// Set the color of the LED at the given index in the buffer
+    ///
+    /// The change becomes visible at the next [.write()](Self::write). Indices beyond the end of
+    /// the chain are ignored.
+    pub fn set(&mut self, index: usize, color: Rgb) {
+        if let Some(led) = self.buffer.get_mut(index) {
+            led.set_color(color);
+        }
+    }
+
+    /// Color of the LED at the given index in the buffer
+    pub fn get(&self, index: usize) -> Option<Rgb> {
+        self.buffer.get(index).map(|led| led.color())
+    }
+
+    /// Set all LEDs in the buffer to the same color
+    pub fn fill(&mut self, color: Rgb) {
+        for led in self.buffer.iter_mut() {
+            led.set_color(color);
+        }
+    }
+}
+
 impl<C: ChannelType, const N: usize> BufferedWs281x<C, N> {
+    /// Send the buffer to the LEDs
+    ///
+    /// This disables interrupts for the duration of the transmission for most implementations, as
+    /// the protocol has tight timing requirements.
     pub fn write(&mut self) {
         unsafe {
             riot_sys::ws281x_prepare_transmission(crate::inline_cast_mut(&mut self.dev as *mut _));
@@ -68,15 +109,39 @@ impl<C: ChannelType, const N: usize> BufferedWs281x<C, N> {
 /// pointer reinterpreted as u8 pointer, and any memory content is valid in this view.
 pub trait ChannelType: Sized {}
 
+/// A [ChannelType] whose LEDs can be set to an RGB color
+///
+/// This enables the color based accessors of [BufferedWs281x].
+pub trait ColorChannels: ChannelType {
+    /// Set the LED to the given color
+    ///
+    /// Any additional channels (eg. white) are turned off.
+    fn set_color(&mut self, color: Rgb);
+
+    /// The LED's color, ignoring any additional channels
+    fn color(&self) -> Rgb;
+}
+
 #[derive(Default, Copy, Clone)]
 #[repr(transparent)]
 pub struct GRBW([u8; 4]);
 
 impl ChannelType for GRBW {}
 
+impl ColorChannels for GRBW {
+    fn set_color(&mut self, color: Rgb) {
+        self.set_rgbw(color.r, color.g, color.b, 0);
+    }
+
+    fn color(&self) -> Rgb {
+        let (r, g, b, _) = self.rgbw();
+        Rgb::new(r, g, b)
+    }
+}
+
 impl GRBW {
     pub fn rgbw(&self) -> (u8, u8, u8, u8) {
-        (self.0[0], self.0[1], self.0[2], self.0[3])
+        (self.0[1], self.0[0], self.0[2], self.0[3])
     }
 
     pub fn set_rgbw(&mut self, r: u8, g: u8, b: u8, w: u8) {
@@ -90,9 +155,20 @@ pub struct GRB([u8; 3]);
 
 impl ChannelType for GRB {}
 
+impl ColorChannels for GRB {
+    fn set_color(&mut self, color: Rgb) {
+        self.set_rgb(color.r, color.g, color.b);
+    }
+
+    fn color(&self) -> Rgb {
+        let (r, g, b) = self.rgb();
+        Rgb::new(r, g, b)
+    }
+}
+
 impl GRB {
     pub fn rgb(&self) -> (u8, u8, u8) {
-        (self.0[0], self.0[1], self.0[2])
+        (self.0[1], self.0[0], self.0[2])
     }
 
     pub fn set_rgb(&mut self, r: u8, g: u8, b: u8) {
@@ -12,7 +12,12 @@
 #[cfg(riot_module_ztimer_periodic)]
 pub mod periodic;
 
+use core::cell::UnsafeCell;
 use core::convert::TryInto;
+use core::future::Future;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
 
 use riot_sys::ztimer_clock_t;
 
@@ -30,6 +35,7 @@ pub struct Clock<const HZ: u32>(*mut ztimer_clock_t);
 /// In memory, these are numbers of ticks. Semantically, these are durations of `self.0 / HZ`
 /// seconds.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ticks<const HZ: u32>(pub u32);
 
 
@@ -54,6 +60,16 @@ impl<const HZ: u32> Clock<HZ> {
         unsafe { riot_sys::ztimer_spin(crate::inline_cast_mut(self.0), duration) };
     }
 
+    /// Read the current time of the clock
+    ///
+    /// The value wraps around at the end of the u32 range; only differences between readings are
+    /// meaningful (and only if the clock was not stopped in between).
+    #[doc(alias = "ztimer_now")]
+    pub fn now(&self) -> Ticks<HZ> {
+        // unsafe: OK per C API
+        Ticks(unsafe { riot_sys::inline::ztimer_now(crate::inline_cast_mut(self.0)) })
+    }
+
     /// Pause the current thread for the given duration.
     ///
     /// The duration is converted into ticks (rounding up), and overflows are caught by sleeping
@@ -163,7 +179,184 @@ impl<const HZ: u32> Clock<HZ> {
 
         result
     }
+
+    /// Pause the current thread for the given number of ticks, or until a [SleepCanceller]
+    /// obtained in this thread is used.
+    ///
+    /// This returns `Err(Cancelled)` if the sleep was cut short. If the canceller was used before
+    /// the sleep started, the sleep ends right away; that way, a shutdown request can not get lost
+    /// between two sleeps.
+    ///
+    /// This uses a thread flag ([SLEEP_CANCEL_FLAG]) along with RIOT's `THREAD_FLAG_TIMEOUT`,
+    /// which is set from `ztimer_set_timeout_flag`.
+    ///
+    /// This function is only available if the core_thread_flags module is built.
+    #[cfg(riot_module_core_thread_flags)]
+    #[doc(alias = "ztimer_set_timeout_flag")]
+    pub fn sleep_cancellable(&self, ticks: Ticks<HZ>) -> Result<(), Cancelled> {
+        let timeout_flag = riot_sys::THREAD_FLAG_TIMEOUT as riot_sys::thread_flags_t;
+
+        // This is zero-initialized, which is the more efficient mode for ztimer_t.
+        let mut timer = riot_sys::ztimer_t::default();
+
+        // unsafe: OK per C API; flags are only ever cleared on the own thread.
+        unsafe { riot_sys::thread_flags_clear(timeout_flag) };
+        // unsafe: OK per C API; the timer is removed before it goes out of scope.
+        unsafe { riot_sys::ztimer_set_timeout_flag(self.0, &mut timer, ticks.0) };
+
+        // unsafe: OK per C API
+        let woken_by = unsafe { riot_sys::thread_flags_wait_any(timeout_flag | SLEEP_CANCEL_FLAG) };
+
+        // unsafe: OK per C API
+        let removed = unsafe { riot_sys::ztimer_remove(self.0, &mut timer) };
+        if !removed {
+            // The timer might have fired right after the cancellation woke us up; don't let that
+            // linger around to confuse other users of the flag.
+            // unsafe: OK per C API
+            unsafe { riot_sys::thread_flags_clear(timeout_flag) };
+        }
+
+        if woken_by & SLEEP_CANCEL_FLAG != 0 {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<const HZ: u32> Clock<HZ> {
+    /// Create a future that completes after the given number of ticks
+    ///
+    /// The timer is started when the future is first polled, and removed if the future is dropped
+    /// before it completes. The future is woken from the timer interrupt, so it can be used with
+    /// any executor (eg. the one in [crate::embassy]).
+    pub fn sleep_async(&self, ticks: Ticks<HZ>) -> SleepFuture<HZ> {
+        SleepFuture {
+            clock: *self,
+            ticks: ticks.0,
+            started: false,
+            timer: Default::default(),
+            shared: UnsafeCell::new(SleepShared {
+                fired: false,
+                waker: None,
+            }),
+            _pinned: PhantomPinned,
+        }
+    }
+}
+
+/// State of a [SleepFuture] that is shared with the timer callback
+struct SleepShared {
+    fired: bool,
+    waker: Option<Waker>,
+}
+
+/// Future returned by [Clock::sleep_async]
+pub struct SleepFuture<const HZ: u32> {
+    clock: Clock<HZ>,
+    ticks: u32,
+    started: bool,
+    // Both are pointed to by the running timer, and the shared part is mutated from the ISR.
+    timer: UnsafeCell<riot_sys::ztimer_t>,
+    shared: UnsafeCell<SleepShared>,
+    _pinned: PhantomPinned,
+}
+
+impl<const HZ: u32> Future for SleepFuture<HZ> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        extern "C" fn callback(arg: *mut riot_sys::libc::c_void) {
+            // unsafe: Was set to point to the pinned shared state, which outlives the timer. We're
+            // in the ISR, so the thread is not accessing it concurrently.
+            let shared = unsafe { &mut *(arg as *mut SleepShared) };
+            shared.fired = true;
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+
+        // unsafe: Nothing is moved out of self.
+        let this = unsafe { self.get_unchecked_mut() };
+
+        crate::interrupt::free(|_| {
+            // unsafe: Interrupts are off, so the callback does not run concurrently.
+            let shared = unsafe { &mut *this.shared.get() };
+            if shared.fired {
+                return Poll::Ready(());
+            }
+            shared.waker = Some(cx.waker().clone());
+
+            if !this.started {
+                // unsafe: The timer and its argument are pinned, and the timer is removed in drop.
+                unsafe {
+                    let timer = &mut *this.timer.get();
+                    timer.callback = Some(callback);
+                    timer.arg = this.shared.get() as *mut _;
+                    riot_sys::ztimer_set(this.clock.0, timer, this.ticks);
+                }
+                this.started = true;
+            }
+
+            Poll::Pending
+        })
+    }
+}
+
+impl<const HZ: u32> Drop for SleepFuture<HZ> {
+    fn drop(&mut self) {
+        if self.started {
+            // unsafe: OK per C API; removing a timer that has already fired is harmless.
+            unsafe { riot_sys::ztimer_remove(self.clock.0, self.timer.get()) };
+        }
+    }
 }
+
+/// Thread flag used by [Clock::sleep_cancellable] to indicate cancellation
+///
+/// Threads that use [Clock::sleep_cancellable] should not use this flag for other purposes.
+///
+/// See the [flags module](crate::thread::flags) for the other flags used by this crate.
+#[cfg(riot_module_core_thread_flags)]
+pub const SLEEP_CANCEL_FLAG: riot_sys::thread_flags_t = 1 << 13;
+
+/// Error returned from [Clock::sleep_cancellable] when the sleep was ended through a
+/// [SleepCanceller]
+#[cfg(riot_module_core_thread_flags)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Cancelled;
+
+/// A handle by which other threads or interrupts can end a [Clock::sleep_cancellable] in the
+/// thread it was created in
+///
+/// It can be copied around freely; cancelling a sleep on a thread that has terminated has no
+/// effect.
+#[cfg(riot_module_core_thread_flags)]
+#[derive(Copy, Clone, Debug)]
+pub struct SleepCanceller(crate::thread::KernelPID);
+
+#[cfg(riot_module_core_thread_flags)]
+impl SleepCanceller {
+    /// Create a canceller for sleeps of the current thread
+    pub fn for_current_thread() -> Self {
+        SleepCanceller(crate::thread::get_pid())
+    }
+
+    /// End the current (or, if the thread is not sleeping, the next) cancellable sleep of the
+    /// thread
+    ///
+    /// This can be called from any thread, and from interrupts.
+    #[doc(alias = "thread_flags_set")]
+    pub fn cancel(&self) {
+        // A thread that is gone has no sleep to cancel
+        let _ = crate::thread::flags::set(
+            self.0,
+            crate::thread::flags::Flags::from_bits(SLEEP_CANCEL_FLAG),
+        );
+    }
+}
+
 impl Clock<1> {
     /// Get the global second ZTimer clock, ZTIMER_SEC.
     ///
@@ -215,6 +408,7 @@ impl embedded_hal::blocking::delay::DelayUs<u32> for Clock<1000000> {
 /// constitute a reportable error, and is always resolved by rounding up (consistent with ZTimer's
 /// and Duration's behavior).
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Overflow;
 
 impl<const HZ: u32> Ticks<HZ> {
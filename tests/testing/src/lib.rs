@@ -0,0 +1,15 @@
+#![no_std]
+
+use riot_wrappers::riot_test;
+
+riot_test!(arithmetic, fallible);
+
+fn arithmetic() {
+    assert_eq!(1 + 1, 2);
+}
+
+fn fallible() -> Result<(), core::num::TryFromIntError> {
+    let small: u8 = 42u32.try_into()?;
+    assert_eq!(small, 42);
+    Ok(())
+}
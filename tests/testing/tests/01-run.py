@@ -0,0 +1,13 @@
+#!/usr/bin/env python3
+
+import sys
+from testrunner import run
+
+def test(child):
+    child.expect_exact("1..2")
+    child.expect_exact("ok 1 - arithmetic")
+    child.expect_exact("ok 2 - fallible")
+    child.expect("SUCCESS")
+
+if __name__ == "__main__":
+    sys.exit(run(test))